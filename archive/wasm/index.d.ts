@@ -54,6 +54,12 @@ export interface AiAnalysisResult {
   modelUsed: string
   /** 图片信息 JSON string */
   imageInfoJson: string
+  /** 均值哈希 (aHash)，16 位十六进制 */
+  averageHash: string
+  /** 差值哈希 (dHash)，16 位十六进制 */
+  differenceHash: string
+  /** DCT 感知哈希 (pHash)，16 位十六进制 */
+  perceptualHash: string
   /** 错误信息 */
   error?: string
 }
@@ -70,9 +76,44 @@ export declare function batchCompressToWebp(imagesData: Array<Array<number>>, op
 /** 批量转换图片格式 */
 export declare function batchConvertImageFormat(imagesData: Array<Array<number>>, options: FormatConversionOptions): Array<FormatConversionResult>
 
+/** 批量计算 git blob SHA-1，结果顺序与输入一致 */
+export declare function batchGitBlobSha(outputs: Array<Array<number>>): Array<string>
+
+/** 批量探测图片信息（只解析文件头） */
+export declare function batchProbeImages(imagesData: Array<Array<number>>): Array<ImageProbe>
+
 /** 检查模型是否可用 */
 export declare function checkModelAvailability(modelPath: string): boolean
 
+/** 无法参与分组的图片 */
+export interface ClusterFailure {
+  /** 图片在输入中的索引 */
+  index: number
+  /** 错误信息 */
+  error: string
+}
+
+/** 分组选项 */
+export interface ClusterOptions {
+  /** 距离阈值 (0-64)，默认 10 */
+  threshold?: number
+  /** 感知哈希算法 (ahash / dhash / phash)，默认 phash */
+  hash?: string
+  /** 是否返回只有一张图片的组，默认 false */
+  includeSingletons?: boolean
+}
+
+/** 分组结果 */
+export interface ClusterResult {
+  /** 相似图片组 */
+  groups: Array<DuplicateGroup>
+  /** 无法解码而被跳过的图片 */
+  failed: Array<ClusterFailure>
+}
+
+/** 对一批图片按相似度分组（WASM 导出），无法解码的图片跳过并在 failed 中列出 */
+export declare function clusterSimilarImages(imagesData: Array<Array<number>>, options?: ClusterOptions | undefined | null): ClusterResult
+
 /** 颜色信息 */
 export interface ColorInfo {
   name: string
@@ -84,9 +125,89 @@ export interface ColorInfo {
 /** 压缩图片为WebP格式 */
 export declare function compressToWebp(imageData: Array<number>, options?: WebPCompressOptions | undefined | null): WebPCompressResult
 
+/** 计算图片的感知哈希（WASM 导出） */
+export declare function computePerceptualHashes(imageData: Array<number>): PerceptualHashes
+
+/** 增量哈希计算器，大文件可以分块调用 update */
+export declare class ContentHasher {
+  /** 创建计算器，algorithm 为 sha256 或 blake3 */
+  constructor(algorithm: string)
+  /** 追加数据 */
+  update(chunk: Array<number>): void
+  /** 结束计算，返回编码后的摘要（hex / base32，默认 hex） */
+  finalize(encoding?: string | undefined | null): string
+}
+
+/** 内容哈希结果 */
+export interface ContentHashResult {
+  /** 使用的算法 */
+  algorithm: string
+  /** 原始字节的哈希 */
+  contentHash: string
+  /** 解码后像素的哈希 */
+  pixelHash?: string
+  /** 原始大小 */
+  size: number
+}
+
 /** 转换图片格式 */
 export declare function convertImageFormat(imageData: Array<number>, options: FormatConversionOptions): FormatConversionResult
 
+/** 创建增量哈希计算器 */
+export declare function createContentHasher(algorithm: string): ContentHasher
+
+/** 裁剪区域（原图坐标） */
+export interface CropRect {
+  x: number
+  y: number
+  width: number
+  height: number
+}
+
+/** 多倍图选项 */
+export interface DensityOptions {
+  /** 源图倍率，默认 3 */
+  sourceDensity?: number
+  /** 目标倍率，默认 1 到源图倍率的所有整数倍率，不能大于源图倍率 */
+  targetDensities?: Array<number>
+  /** 原始文件名，如 icon@3x.png，默认 image */
+  originalName?: string
+  /** 输出格式，默认 png */
+  format?: string
+  /** 编码质量 (1-100)，默认 90 */
+  quality?: number
+  /** 重采样滤镜，同 ResizeOptions.filter */
+  filter?: string
+}
+
+/** 多倍图结果 */
+export interface DensitySet {
+  /** 各倍率的输出，按倍率升序 */
+  variants: Array<DensityVariant>
+  /** @1x 基准宽度 */
+  baseWidth: number
+  /** @1x 基准高度 */
+  baseHeight: number
+  /** 源图尺寸是否正好是基准尺寸的整数倍，否则源图倍率下的输出会与原图相差 1-2 像素 */
+  exact: boolean
+}
+
+/** 单个倍率的输出 */
+export interface DensityVariant {
+  /** 编码后的数据 */
+  data: Array<number>
+  /** 倍率 */
+  density: number
+  /** 文件名，如 icon@2x.png，@1x 不带后缀 */
+  fileName: string
+  /** 宽度 */
+  width: number
+  /** 高度 */
+  height: number
+  /** 数据字节数 */
+  size: number
+}
+
 /** 检测到的对象 */
 export interface DetectedObject {
   name: string
@@ -95,6 +216,28 @@ export interface DetectedObject {
   category: string
 }
 
+/** 一组相似图片 */
+export interface DuplicateGroup {
+  /** 组内图片在输入中的索引（升序） */
+  indices: Array<number>
+  /** 推荐保留的图片索引 */
+  bestIndex: number
+  /** 组内图片的清晰度（拉普拉斯方差），顺序与 indices 一致 */
+  sharpness: Array<number>
+  /** 组内任意两张图片的最大距离 */
+  maxDistance: number
+}
+
+/** 文件名模板选项 */
+export interface FileNameOptions {
+  /** 模板，如 {yyyy}/{mm}/{hash:8}-{name}.{ext} */
+  template: string
+  /** 原始文件名，默认 image */
+  originalName?: string
+  /** 没有 EXIF 拍摄时间时使用的日期 (YYYY-MM-DD 或 ISO 8601) */
+  fallbackDate?: string
+}
+
 /** 格式转换选项 */
 export interface FormatConversionOptions {
   /** 目标格式 */
@@ -109,6 +252,14 @@ export interface FormatConversionOptions {
   colorSpace?: string
   /** 尺寸调整选项 */
   resize?: ResizeOptions
+  /** 元数据策略 (strip_all / strip_private / keep_all)，默认 strip_all；指定 preserveMetadata 时默认 keep_all */
+  metadataPolicy?: string
+  /** 需要迁移的元数据块，逗号分隔 (exif,xmp,icc,iptc / all)，默认全部；iptc 只写入 JPEG */
+  preserveMetadata?: string
+  /** 写入输出文件的 IPTC 字段，与迁移的 IPTC 合并；只支持 JPEG 输出 */
+  iptc?: IptcFields
+  /** 输出文件名模板，指定时在结果中返回 fileName */
+  naming?: FileNameOptions
 }
 
 /** 格式转换结果 */
@@ -129,17 +280,92 @@ export interface FormatConversionResult {
   originalHeight: number
   /** 转换时间 (毫秒) */
   conversionTime: number
+  /** 转换后数据的 git blob SHA-1，可用于判断仓库中是否已存在相同文件 */
+  gitBlobSha: string
+  /** 按 naming 模板生成的文件路径 */
+  fileName?: string
+  /** cover 裁剪或指定 crop 时原图中被保留的区域 */
+  crop?: CropRect
+  /** 目标格式放不下而被丢弃的元数据 JSON string，如 ["EXIF dropped: ..."] */
+  metadataWarningsJson: string
 }
 
+/** 生成多倍图（WASM 导出） */
+export declare function generateDensityVariants(imageData: Array<number>, options?: DensityOptions | undefined | null): DensitySet
+
+/** 生成 LQIP data URI（WASM 导出） */
+export declare function generateLqip(imageData: Array<number>, options?: LqipOptions | undefined | null): LqipResult
+
+/** 生成图片占位符（WASM 导出） */
+export declare function generatePlaceholders(imageData: Array<number>, options?: PlaceholderOptions | undefined | null): ImagePlaceholders
+
+/** 生成响应式图片变体（WASM 导出） */
+export declare function generateVariants(imageData: Array<number>, options?: VariantOptions | undefined | null): VariantSet
+
 /** 获取格式的详细信息 */
 export declare function getFormatInfo(formatStr: string): string
 
+/** 获取图片结构信息（颜色类型、位深、透明度、动画、DPI 等） */
+export declare function getImageDetails(imageData: Array<number>): ImageDetails
+
 /** 获取图片信息 */
 export declare function getImageInfo(imageData: Array<number>): string
 
 /** 获取支持的图片格式列表 */
 export declare function getSupportedFormats(): Array<string>
 
+/** 计算 git blob SHA-1：sha1("blob <len>\0" + bytes) */
+export declare function gitBlobSha(data: Array<number>): string
+
+/** 计算图片的内容哈希（SHA-256 / BLAKE3），可选计算像素哈希 */
+export declare function hashImage(imageData: Array<number>, options?: HashOptions | undefined | null): ContentHashResult
+
+/** 内容哈希选项 */
+export interface HashOptions {
+  /** 哈希算法 (sha256 / blake3)，默认 sha256 */
+  algorithm?: string
+  /** 输出编码 (hex / base32)，默认 hex */
+  encoding?: string
+  /** 是否同时计算解码后像素的哈希 */
+  includePixels?: boolean
+}
+
+/** 图片结构信息 */
+export interface ImageDetails {
+  /** 容器格式 (JPEG / PNG / GIF / WebP / BMP / TIFF) */
+  format: string
+  /** 宽度（按 EXIF 方向校正后） */
+  width: number
+  /** 高度（按 EXIF 方向校正后） */
+  height: number
+  /** 颜色类型，如 RGB8、RGBA16、RGB32F */
+  colorType: string
+  /** 通道数 */
+  channels: number
+  /** 每个通道的位深 */
+  bitDepth: number
+  /** 是否有透明通道 */
+  hasAlpha: boolean
+  /** 是否存在非完全不透明的像素 */
+  alphaUsed: boolean
+  /** 是否为动画 */
+  isAnimated: boolean
+  /** 帧数（静态图片为 1） */
+  frameCount: number
+  /** 循环次数，0 表示无限循环 */
+  loopCount?: number
+  /** 水平 DPI */
+  dpiX?: number
+  /** 垂直 DPI */
+  dpiY?: number
+  /** 物理宽度（英寸） */
+  physicalWidth?: number
+  /** 物理高度（英寸） */
+  physicalHeight?: number
+  /** 是否隔行扫描或渐进式编码 */
+  interlaced: boolean
+}
+
 /** 图片信息 */
 export interface ImageInfo {
   width: number
@@ -147,9 +373,135 @@ export interface ImageInfo {
   aspectRatio: number
 }
 
+/** 占位符结果 */
+export interface ImagePlaceholders {
+  /** BlurHash 字符串 */
+  blurhash: string
+  /** ThumbHash (Base64) */
+  thumbhash: string
+}
+
+/** 图片头探测结果 */
+export interface ImageProbe {
+  /** 容器格式 (JPEG / PNG / GIF / WebP / BMP / TIFF) */
+  format: string
+  /** 文件中存储的宽度 */
+  width: number
+  /** 文件中存储的高度 */
+  height: number
+  /** 按 EXIF 方向校正后的显示宽度 */
+  displayWidth: number
+  /** 按 EXIF 方向校正后的显示高度 */
+  displayHeight: number
+  /** 颜色类型，如 RGB8、Indexed8、CMYK8 */
+  colorType: string
+  /** 每个通道的位深 */
+  bitDepth: number
+  /** 是否有透明通道 */
+  hasAlpha: boolean
+  /** EXIF 方向 (1-8)，没有时为 1 */
+  orientation: number
+}
+
+/** 图片标签（写入时未指定的字段保留文件中原有的值） */
+export interface ImageTags {
+  /** 标签 JSON string，格式与 AiAnalysisResult.tagsJson 相同，如 ["风景", "日落"] */
+  tagsJson?: string
+  /** 描述 */
+  description?: string
+  /** 评分 (0-5) */
+  rating?: number
+}
+
+/** 单个变体 */
+export interface ImageVariant {
+  /** 编码后的数据 */
+  data: Array<number>
+  /** 输出格式扩展名 */
+  format: string
+  /** MIME 类型 */
+  mimeType: string
+  /** 宽度 */
+  width: number
+  /** 高度 */
+  height: number
+  /** 按模板生成的文件名 */
+  fileName: string
+  /** srcset 中使用的 URL（baseUrl + fileName） */
+  url: string
+  /** 数据字节数 */
+  size: number
+}
+
+/** 需要写入的 IPTC 字段，写入时未指定的字段保留文件中原有的值 */
+export interface IptcFields {
+  /** 关键词 JSON string，如 ["风景", "日落"] */
+  keywordsJson?: string
+  /** 说明 (Caption/Abstract) */
+  caption?: string
+  /** 署名 (Credit) */
+  credit?: string
+}
+
+/** LQIP 选项 */
+export interface LqipOptions {
+  /** 最长边像素 (8-64)，默认 24 */
+  size?: number
+  /** 高斯模糊半径，0 表示不模糊，默认 1.0 */
+  blur?: number
+  /** 输出格式，默认 jpeg；当前构建不支持 webp */
+  format?: string
+  /** data URI 的最大字节数，默认 2048 */
+  maxBytes?: number
+}
+
+/** LQIP 结果 */
+export interface LqipResult {
+  /** data URI，如 data:image/jpeg;base64,... */
+  dataUri: string
+  /** 占位图宽度 */
+  width: number
+  /** 占位图高度 */
+  height: number
+  /** 使用的编码质量 */
+  quality: number
+  /** data URI 字节数 */
+  byteSize: number
+}
+
+/** 计算两个感知哈希的汉明距离（WASM 导出） */
+export declare function perceptualHashDistance(a: string, b: string): number
+
+/** 感知哈希结果 */
+export interface PerceptualHashes {
+  /** 均值哈希 (aHash) */
+  averageHash: string
+  /** 差值哈希 (dHash) */
+  differenceHash: string
+  /** DCT 感知哈希 (pHash) */
+  perceptualHash: string
+}
+
+/** 占位符选项 */
+export interface PlaceholderOptions {
+  /** BlurHash 水平分量数 (1-9)，默认 4 */
+  xComponents?: number
+  /** BlurHash 垂直分量数 (1-9)，默认 3 */
+  yComponents?: number
+}
+
 /** 简单的加法函数，用于测试WASM接口 */
 export declare function plus100(input: number): number
 
+/** 只解析文件头探测图片尺寸、格式、颜色类型和方向（不解码像素） */
+export declare function probeImage(imageData: Array<number>): ImageProbe
+
+/** 读取图片 XMP 中的标签、描述和评分 */
+export declare function readImageTags(imageData: Array<number>): ImageTags
+
+/** 按模板生成上传文件名，用于未经转换直接上传的图片，扩展名根据文件头识别 */
+export declare function renderFileName(imageData: Array<number>, options: FileNameOptions): string
+
 /** 尺寸调整选项 */
 export interface ResizeOptions {
   /** 目标宽度 */
@@ -158,6 +510,80 @@ export interface ResizeOptions {
   height?: number
   /** 是否保持宽高比 */
   maintainAspectRatio?: boolean
+  /** 适配模式 (fill / inside / outside / cover / contain / pad)，pad 同 contain；未指定时按 maintainAspectRatio 使用 inside 或 fill */
+  fit?: string
+  /** cover 裁剪和 contain 填充的对齐位置 (center / north / northeast / east / southeast / ... / smart)，默认 center；smart 按边缘、饱和度和肤色选择 cover 的裁剪区域 */
+  gravity?: string
+  /** 重采样滤波器 (auto / nearest / bilinear / catmull-rom / mitchell / lanczos3)，默认 auto：大幅缩小时用 bilinear，小幅缩小时用 lanczos3，放大时用 catmull-rom */
+  filter?: string
+  /** 是否在线性光中缩放，避免细亮线和高对比纹理缩小后偏暗，默认 false */
+  linearLight?: boolean
+  /** 是否禁止放大，为 true 时输出不超过原图尺寸（contain 仍填充到指定的框），默认 false */
+  withoutEnlargement?: boolean
+  /** 按百分比缩放，如 50 表示缩小到一半，不能与 width / height 同时使用 */
+  scale?: number
+  /** 输出像素数上限（百万像素），超出时等比缩小 */
+  maxMegapixels?: number
+  /** contain 填充区域的背景 (transparent / blur / white / black / #rrggbb / #rrggbbaa)，默认 transparent，格式转换的输出不保留透明通道时默认 white；blur 用放大并模糊的原图铺满背景 */
+  background?: string
+  /** background 为 blur 时的模糊半径（输出像素），默认 20 */
+  backgroundBlur?: number
+  /** 缩放前先从原图裁出的区域（原图坐标，如 smartCrop 的结果），其余选项作用于裁剪后的图片 */
+  crop?: CropRect
+}
+
+/** 计算指定宽高比的智能裁剪区域（WASM 导出） */
+export declare function smartCrop(imageData: Array<number>, width: number, height: number): CropRect
+
+/** 按策略清理图片元数据 */
+export declare function stripMetadata(imageData: Array<number>, policy: string): StripMetadataResult
+
+/** 元数据清理结果 */
+export interface StripMetadataResult {
+  /** 清理后的数据 */
+  data: Array<number>
+  /** 被删除的元数据 JSON string，如 ["EXIF:GPS", "XMP"] */
+  removedJson: string
+  /** 原始大小 */
+  originalSize: number
+  /** 清理后大小 */
+  strippedSize: number
+}
+
+/** 变体生成选项 */
+export interface VariantOptions {
+  /** 输出宽度，默认 320/640/960/1280/1920，大于原图的宽度会被跳过 */
+  widths?: Array<number>
+  /** 输出格式，默认 jpeg，最后一个作为 <img> 的回退格式；当前构建不支持 webp */
+  formats?: Array<string>
+  /** 编码质量 (1-100)，默认 80 */
+  quality?: number
+  /** 重采样滤镜，同 ResizeOptions.filter */
+  filter?: string
+  /** 是否在线性光空间中缩放 */
+  linearLight?: boolean
+  /** 文件名模板，默认 {name}-{width}.{ext} */
+  naming?: FileNameOptions
+  /** 拼接在文件名前的 URL 前缀，如 https://cdn.example.com/blog/ */
+  baseUrl?: string
+  /** sizes 属性，默认 100vw */
+  sizes?: string
+  /** <img> 的 alt 文本 */
+  alt?: string
+}
+
+/** 变体生成结果 */
+export interface VariantSet {
+  /** 所有变体，按宽度升序，同宽度内按 formats 顺序 */
+  variants: Array<ImageVariant>
+  /** 回退格式的 srcset */
+  srcset: string
+  /** <picture> HTML 片段 */
+  picture: string
+  /** 原始宽度 */
+  originalWidth: number
+  /** 原始高度 */
+  originalHeight: number
 }
 
 /** WebP压缩配置 */
@@ -166,6 +592,8 @@ export interface WebPCompressOptions {
   quality?: number
   /** 是否使用无损压缩 */
   lossless?: boolean
+  /** 元数据策略 (strip_all / strip_private / keep_all)，默认 strip_all；WebP 编码器恢复后才会应用到压缩结果，目前只校验取值 */
+  metadataPolicy?: string
 }
 
 /** WebP压缩结果 */
@@ -183,3 +611,9 @@ export interface WebPCompressResult {
   /** 压缩后的高度 */
   height: number
 }
+
+/** 把关键词、说明和署名写入 JPEG 的 IPTC，与文件中已有的 IPTC 合并，不重新编码像素 */
+export declare function writeImageIptc(imageData: Array<number>, fields: IptcFields): Array<number>
+
+/** 把标签、描述和评分写入图片的 XMP（支持 JPEG / PNG / WebP） */
+export declare function writeImageTags(imageData: Array<number>, tags: ImageTags): Array<number>
@@ -26,6 +26,38 @@ function detectEnvironment() {
   return 'browser'; // 默认浏览器环境
 }
 
+/**
+ * 检测运行环境是否支持 WASM SIMD
+ *
+ * 校验一个只包含 i8x16.splat / i8x16.popcnt 指令的最小模块，不支持 SIMD 的引擎会返回 false
+ */
+function supportsSimd() {
+  try {
+    return WebAssembly.validate(
+      new Uint8Array([
+        0, 97, 115, 109, 1, 0, 0, 0, 1, 5, 1, 96, 0, 1, 123, 3, 2, 1, 0, 10, 10,
+        1, 8, 0, 65, 0, 253, 15, 253, 98, 11,
+      ]),
+    );
+  } catch {
+    return false;
+  }
+}
+
+/**
+ * 优先加载 SIMD 版本，不支持 SIMD 或 SIMD 版本未构建时回退到基础版本
+ */
+async function importWasm(basePath, simdPath) {
+  if (supportsSimd()) {
+    try {
+      return await import(simdPath);
+    } catch {
+      // SIMD 版本不存在时使用基础版本
+    }
+  }
+  return import(basePath);
+}
+
 /**
  * 初始化 WASM 模块
  */
@@ -46,7 +78,10 @@ async function init() {
 
       if (env === 'browser') {
         // Web 端：使用 web 版本
-        module = await import('./pkg-web/pixuli_wasm.js');
+        module = await importWasm(
+          './pkg-web/pixuli_wasm.js',
+          './pkg-web-simd/pixuli_wasm.js',
+        );
         // Web 版本的 default 函数需要调用以初始化 WASM
         // 检查 default 导出是否存在
         if (!module.default) {
@@ -71,7 +106,10 @@ async function init() {
       } else {
         // Node.js/Electron 主进程：使用 nodejs 版本
         // Node.js 版本是同步初始化的，不需要调用 default()
-        module = await import('./pkg-node/pixuli_wasm.js');
+        module = await importWasm(
+          './pkg-node/pixuli_wasm.js',
+          './pkg-node-simd/pixuli_wasm.js',
+        );
         // Node.js 版本的 WASM 模块已经同步初始化，不需要调用 default()
       }
 
@@ -149,6 +187,106 @@ export async function checkModelAvailability(modelPath) {
   return module.check_model_availability(modelPath);
 }
 
+export async function stripMetadata(imageData, policy) {
+  const module = await ensureInitialized();
+  return module.strip_metadata(imageData, policy);
+}
+
+export async function readImageTags(imageData) {
+  const module = await ensureInitialized();
+  return module.read_image_tags(imageData);
+}
+
+export async function writeImageTags(imageData, tags) {
+  const module = await ensureInitialized();
+  return module.write_image_tags(imageData, tags);
+}
+
+export async function getImageDetails(imageData) {
+  const module = await ensureInitialized();
+  return module.get_image_details(imageData);
+}
+
+export async function probeImage(imageData) {
+  const module = await ensureInitialized();
+  return module.probe_image(imageData);
+}
+
+export async function batchProbeImages(imagesData) {
+  const module = await ensureInitialized();
+  return module.batch_probe_images(imagesData);
+}
+
+export async function hashImage(imageData, options) {
+  const module = await ensureInitialized();
+  return module.hash_image(imageData, options);
+}
+
+export async function createContentHasher(algorithm) {
+  const module = await ensureInitialized();
+  return new module.ContentHasher(algorithm);
+}
+
+export async function gitBlobSha(data) {
+  const module = await ensureInitialized();
+  return module.git_blob_sha(data);
+}
+
+export async function batchGitBlobSha(outputs) {
+  const module = await ensureInitialized();
+  return module.batch_git_blob_sha(outputs);
+}
+
+export async function computePerceptualHashes(imageData) {
+  const module = await ensureInitialized();
+  return module.compute_perceptual_hashes(imageData);
+}
+
+export async function perceptualHashDistance(a, b) {
+  const module = await ensureInitialized();
+  return module.perceptual_hash_distance(a, b);
+}
+
+export async function clusterSimilarImages(imagesData, options) {
+  const module = await ensureInitialized();
+  return module.cluster_similar_images(imagesData, options);
+}
+
+export async function generatePlaceholders(imageData, options) {
+  const module = await ensureInitialized();
+  return module.generate_placeholders(imageData, options);
+}
+
+export async function generateLqip(imageData, options) {
+  const module = await ensureInitialized();
+  return module.generate_lqip(imageData, options);
+}
+
+export async function renderFileName(imageData, options) {
+  const module = await ensureInitialized();
+  return module.render_file_name(imageData, options);
+}
+
+export async function smartCrop(imageData, width, height) {
+  const module = await ensureInitialized();
+  return module.smart_crop(imageData, width, height);
+}
+
+export async function generateVariants(imageData, options) {
+  const module = await ensureInitialized();
+  return module.generate_variants(imageData, options);
+}
+
+export async function generateDensityVariants(imageData, options) {
+  const module = await ensureInitialized();
+  return module.generate_density_variants(imageData, options);
+}
+
+export async function writeImageIptc(imageData, fields) {
+  const module = await ensureInitialized();
+  return module.write_image_iptc(imageData, fields);
+}
+
 export async function plus100(input) {
   // 确保 WASM 已初始化
   const module = await ensureInitialized();
@@ -7,6 +7,8 @@ use image::{DynamicImage, GenericImageView};
 use serde_json::json;
 use std::path::Path;
 
+use crate::hash::PerceptualHashes;
+
 /// AI 分析配置选项
 #[wasm_bindgen]
 #[derive(Clone)]
@@ -74,6 +76,12 @@ pub struct AIAnalysisResult {
     pub model_used: String,
     /// 图片信息 JSON string
     pub image_info_json: String,
+    /// 均值哈希 (aHash)，16 位十六进制
+    pub average_hash: String,
+    /// 差值哈希 (dHash)，16 位十六进制
+    pub difference_hash: String,
+    /// DCT 感知哈希 (pHash)，16 位十六进制
+    pub perceptual_hash: String,
     /// 错误信息
     pub error: Option<String>,
 }
@@ -83,9 +91,10 @@ pub struct AIAnalysisResult {
 pub fn analyze_image(image_data: &[u8], options: Option<AIAnalysisOptions>) -> Result<AIAnalysisResult, JsValue> {
     let start_time = std::time::Instant::now();
 
-    // 加载图片
-    let img = image::load_from_memory(image_data)
-        .map_err(|e| JsValue::from_str(&format!("Failed to load image: {}", e)))?;
+    // 加载图片（按 EXIF 方向校正）
+    let img = crate::image::decode_image(image_data)
+        .map_err(|e| JsValue::from_str(&e))?
+        .image;
 
     let (width, height) = img.dimensions();
 
@@ -102,6 +111,9 @@ pub fn analyze_image(image_data: &[u8], options: Option<AIAnalysisOptions>) -> R
     let (tags, description, confidence, objects_json, colors_json, scene_type) =
         perform_basic_analysis(&img, &opts);
 
+    // 感知哈希，用于相似图片检测
+    let hashes = PerceptualHashes::from_image(&img);
+
     let analysis_time = start_time.elapsed().as_millis() as f64;
 
     let image_info = json!({
@@ -122,6 +134,9 @@ pub fn analyze_image(image_data: &[u8], options: Option<AIAnalysisOptions>) -> R
         analysis_time,
         model_used: "Basic Analysis (Rust)".to_string(),
         image_info_json: serde_json::to_string(&image_info).unwrap_or_default(),
+        average_hash: hashes.average_hash,
+        difference_hash: hashes.difference_hash,
+        perceptual_hash: hashes.perceptual_hash,
         error: None,
     })
 }
@@ -151,6 +166,9 @@ pub fn batch_analyze_images(
                     analysis_time: 0.0,
                     model_used: String::new(),
                     image_info_json: "{}".to_string(),
+                    average_hash: String::new(),
+                    difference_hash: String::new(),
+                    perceptual_hash: String::new(),
                     error: Some(format!("{:?}", e)),
                 });
             }
@@ -537,6 +555,9 @@ mod tests {
         assert!(!tags.is_empty());
         assert!(!result.description.is_empty());
         assert!(result.confidence > 0.0);
+        assert_eq!(result.perceptual_hash.len(), 16);
+        assert_eq!(result.average_hash.len(), 16);
+        assert_eq!(result.difference_hash.len(), 16);
     }
 
     #[test]
@@ -9,14 +9,19 @@ use image::GenericImageView;
 // use webp::{Encoder, PixelLayout};
 use serde::{Deserialize, Serialize};
 
+use crate::metadata::MetadataPolicy;
+
 /// WebP压缩配置
-#[wasm_bindgen]
+#[wasm_bindgen(getter_with_clone)]
 #[derive(Clone, Serialize, Deserialize)]
 pub struct WebPCompressOptions {
   /// 压缩质量 (0-100)
   pub quality: Option<u8>,
   /// 是否使用无损压缩
   pub lossless: Option<bool>,
+  /// 元数据策略 (strip_all / strip_private / keep_all)，默认 strip_all；
+  /// WebP 编码器恢复后才会应用到压缩结果，目前只校验取值
+  pub metadata_policy: Option<String>,
 }
 
 #[wasm_bindgen]
@@ -26,6 +31,7 @@ impl WebPCompressOptions {
     Self {
       quality: Some(80),
       lossless: Some(false),
+      metadata_policy: None,
     }
   }
 }
@@ -53,11 +59,16 @@ pub struct WebPCompressResult {
 // TODO: WebP 功能暂时禁用，等待找到纯 Rust 实现的 WebP 库
 fn compress_to_webp_impl(
   image_data: Vec<u8>,
-  _options: Option<WebPCompressOptions>,
+  options: Option<WebPCompressOptions>,
 ) -> Result<WebPCompressResult, String> {
+  // 元数据策略先行校验，WebP 编码恢复后用 carry_metadata 按策略写入压缩结果
+  let _metadata_policy = MetadataPolicy::parse_or(
+    options.as_ref().and_then(|o| o.metadata_policy.as_deref()),
+    MetadataPolicy::StripAll,
+  )?;
+
   // 解析图片以获取尺寸信息
-  let img = image::load_from_memory(&image_data)
-    .map_err(|e| format!("Failed to load image: {}", e))?;
+  let img = crate::image::decode_image(&image_data)?.image;
 
   let (width, height) = img.dimensions();
   let original_size = image_data.len() as u32;
@@ -179,6 +190,7 @@ mod tests {
         let options = WebPCompressOptions {
             quality: Some(60),
             lossless: Some(false),
+            metadata_policy: None,
         };
 
         let result = compress_to_webp(png_data.clone(), Some(options)).expect("Failed to compress with custom quality");
@@ -199,6 +211,7 @@ mod tests {
         let options = WebPCompressOptions {
             quality: None,
             lossless: Some(true),
+            metadata_policy: None,
         };
 
         let result = compress_to_webp(png_data.clone(), Some(options)).expect("Failed to compress in lossless mode");
@@ -283,6 +296,21 @@ mod tests {
         assert!(result.is_err(), "Should return error for empty data");
     }
 
+    #[test]
+    fn test_compress_to_webp_invalid_metadata_policy() {
+        // 测试无效的元数据策略
+        let png_data = generate_test_png(10, 10).expect("Failed to generate test PNG");
+
+        let options = WebPCompressOptions {
+            quality: Some(80),
+            lossless: Some(false),
+            metadata_policy: Some("unknown".to_string()),
+        };
+
+        let result = compress_to_webp_impl(png_data, Some(options));
+        assert!(result.err().unwrap().contains("Unsupported metadata policy"));
+    }
+
     #[test]
     fn test_batch_compress_to_webp_basic() {
         // 测试基本的批量压缩功能
@@ -320,6 +348,7 @@ mod tests {
         let options = WebPCompressOptions {
             quality: Some(70),
             lossless: Some(false),
+            metadata_policy: None,
         };
 
         let results = batch_compress_to_webp(images, Some(options)).expect("Failed to batch compress with options");
@@ -408,6 +437,7 @@ mod tests {
         let options = WebPCompressOptions {
             quality: Some(90),
             lossless: None,
+            metadata_policy: None,
         };
 
         let result = compress_to_webp(png_data.clone(), Some(options)).expect("Failed to compress with partial options");
@@ -483,6 +513,7 @@ mod tests {
             let options = WebPCompressOptions {
                 quality: Some(quality),
                 lossless: Some(false),
+                metadata_policy: None,
             };
 
             let result = compress_to_webp(png_data.clone(), Some(options))
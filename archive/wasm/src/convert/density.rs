@@ -0,0 +1,269 @@
+//! 多倍图生成
+//!
+//! 从按 @3x 等高倍率设计的素材导出 @2x / @1x，
+//! 所有倍率都从同一个 @1x 基准尺寸推导，保证各倍率的尺寸严格成比例
+
+use wasm_bindgen::prelude::*;
+use image::{DynamicImage, GenericImageView};
+use serde::{Deserialize, Serialize};
+
+use super::converters::{get_converter, validate_conversion_options, ConversionOptions};
+use super::naming::file_stem;
+use super::resize::{apply_resize, calculate_resize_dimensions};
+use super::types::{ResizeOptions, SupportedFormat};
+
+/// 默认源图倍率
+const DEFAULT_SOURCE_DENSITY: f32 = 3.0;
+/// 默认输出格式
+const DEFAULT_FORMAT: &str = "png";
+/// 默认编码质量
+const DEFAULT_QUALITY: u8 = 90;
+
+/// 多倍图选项
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DensityOptions {
+    /// 源图倍率，默认 3
+    pub source_density: Option<f32>,
+    /// 目标倍率，默认 1 到源图倍率的所有整数倍率，不能大于源图倍率
+    pub target_densities: Option<Vec<f32>>,
+    /// 原始文件名，如 icon@3x.png，默认 image
+    pub original_name: Option<String>,
+    /// 输出格式，默认 png
+    pub format: Option<String>,
+    /// 编码质量 (1-100)，默认 90
+    pub quality: Option<u8>,
+    /// 重采样滤镜，同 ResizeOptions.filter
+    pub filter: Option<String>,
+}
+
+/// 单个倍率的输出
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DensityVariant {
+    /// 编码后的数据
+    pub data: Vec<u8>,
+    /// 倍率
+    pub density: f32,
+    /// 文件名，如 icon@2x.png，@1x 不带后缀
+    pub file_name: String,
+    /// 宽度
+    pub width: u32,
+    /// 高度
+    pub height: u32,
+    /// 数据字节数
+    pub size: u32,
+}
+
+/// 多倍图结果
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DensitySet {
+    /// 各倍率的输出，按倍率升序
+    pub variants: Vec<DensityVariant>,
+    /// @1x 基准宽度
+    pub base_width: u32,
+    /// @1x 基准高度
+    pub base_height: u32,
+    /// 源图尺寸是否正好是基准尺寸的整数倍，否则源图倍率下的输出会与原图相差 1-2 像素
+    pub exact: bool,
+}
+
+/// 生成多倍图（WASM 导出）
+#[wasm_bindgen]
+pub fn generate_density_variants(image_data: &[u8], options: Option<DensityOptions>) -> Result<DensitySet, JsValue> {
+    let img = crate::image::decode_image(image_data)
+        .map_err(|e| JsValue::from_str(&e))?
+        .image;
+    create_density_variants(&img, &options.unwrap_or_default()).map_err(|e| JsValue::from_str(&e))
+}
+
+/// 计算 @1x 基准尺寸
+pub fn calculate_base_dimensions(original_width: u32, original_height: u32, source_density: f32) -> (u32, u32) {
+    let resize = ResizeOptions {
+        width: Some(((original_width as f32 / source_density).round() as u32).max(1)),
+        maintain_aspect_ratio: Some(true),
+        ..Default::default()
+    };
+    let (width, height) = calculate_resize_dimensions(original_width, original_height, &resize);
+    (width.max(1), height.max(1))
+}
+
+/// 计算指定倍率的尺寸：基准尺寸乘以倍率，整数倍率下各倍率严格成比例
+pub fn calculate_density_dimensions((base_width, base_height): (u32, u32), density: f32) -> (u32, u32) {
+    (
+        ((base_width as f32 * density).round() as u32).max(1),
+        ((base_height as f32 * density).round() as u32).max(1),
+    )
+}
+
+/// 从解码后的图片生成多倍图
+pub fn create_density_variants(img: &DynamicImage, options: &DensityOptions) -> Result<DensitySet, String> {
+    let source_density = options.source_density.unwrap_or(DEFAULT_SOURCE_DENSITY);
+    if !source_density.is_finite() || source_density < 1.0 {
+        return Err("Source density must be at least 1".to_string());
+    }
+
+    let mut densities = options
+        .target_densities
+        .clone()
+        .unwrap_or_else(|| (1..=source_density as u32).map(|density| density as f32).collect());
+    if densities.is_empty() {
+        return Err("At least one target density is required".to_string());
+    }
+    if let Some(density) = densities
+        .iter()
+        .find(|&&density| !density.is_finite() || density <= 0.0 || density > source_density)
+    {
+        return Err(format!(
+            "Target density {} must be greater than 0 and not exceed source density {}",
+            density, source_density
+        ));
+    }
+    densities.sort_by(f32::total_cmp);
+    densities.dedup();
+
+    let format_str = options.format.as_deref().unwrap_or(DEFAULT_FORMAT);
+    let format = SupportedFormat::from_string(format_str)
+        .ok_or_else(|| format!("Unsupported density format: {}", format_str))?;
+    let conversion_options = ConversionOptions {
+        quality: options.quality.unwrap_or(DEFAULT_QUALITY),
+        preserve_transparency: true,
+        lossless: false,
+    };
+    validate_conversion_options(&conversion_options)?;
+    let converter = get_converter(&format);
+
+    // 去掉文件名中已有的倍率后缀，如 icon@3x -> icon
+    let stem = file_stem(options.original_name.as_deref().unwrap_or("image"));
+    let stem = match stem.rfind('@') {
+        Some(index) if index > 0 && stem.ends_with('x') => stem[..index].to_string(),
+        _ => stem,
+    };
+
+    let (original_width, original_height) = img.dimensions();
+    let base = calculate_base_dimensions(original_width, original_height, source_density);
+    let exact = calculate_density_dimensions(base, source_density) == (original_width, original_height);
+
+    let mut variants = Vec::with_capacity(densities.len());
+    for density in densities {
+        let (width, height) = calculate_density_dimensions(base, density);
+        let resized = if (width, height) == (original_width, original_height) {
+            img.clone()
+        } else {
+            let resize = ResizeOptions {
+                width: Some(width),
+                height: Some(height),
+                maintain_aspect_ratio: Some(false),
+                filter: options.filter.clone(),
+                ..Default::default()
+            };
+            apply_resize(img, &resize)?
+        };
+
+        let data = converter.convert(&resized, &conversion_options)?;
+        variants.push(DensityVariant {
+            file_name: format!("{}{}.{}", stem, density_suffix(density), format.extension()),
+            density,
+            width,
+            height,
+            size: data.len() as u32,
+            data,
+        });
+    }
+
+    Ok(DensitySet {
+        variants,
+        base_width: base.0,
+        base_height: base.1,
+        exact,
+    })
+}
+
+/// 倍率后缀，@1x 为空
+fn density_suffix(density: f32) -> String {
+    if density == 1.0 {
+        String::new()
+    } else {
+        format!("@{}x", density)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{ImageBuffer, Rgba};
+
+    fn icon(width: u32, height: u32) -> DynamicImage {
+        DynamicImage::ImageRgba8(ImageBuffer::from_fn(width, height, |x, y| {
+            Rgba([(x * 2) as u8, (y * 2) as u8, 200, if (x + y) % 7 == 0 { 0 } else { 255 }])
+        }))
+    }
+
+    fn summary(set: &DensitySet) -> Vec<(String, u32, u32)> {
+        set.variants
+            .iter()
+            .map(|variant| (variant.file_name.clone(), variant.width, variant.height))
+            .collect()
+    }
+
+    #[test]
+    fn test_default_densities() {
+        let options = DensityOptions {
+            original_name: Some("assets/icon@3x.png".to_string()),
+            ..Default::default()
+        };
+        let set = create_density_variants(&icon(96, 72), &options).unwrap();
+        assert_eq!((set.base_width, set.base_height), (32, 24));
+        assert!(set.exact);
+        assert_eq!(
+            summary(&set),
+            vec![
+                ("icon.png".to_string(), 32, 24),
+                ("icon@2x.png".to_string(), 64, 48),
+                ("icon@3x.png".to_string(), 96, 72),
+            ]
+        );
+
+        for variant in &set.variants {
+            let decoded = image::load_from_memory(&variant.data).unwrap();
+            assert_eq!(decoded.dimensions(), (variant.width, variant.height));
+            assert!(decoded.color().has_alpha());
+        }
+    }
+
+    #[test]
+    fn test_dimensions_stay_proportional() {
+        // 100 不能被 3 整除，所有倍率都从 33x20 推导
+        let options = DensityOptions {
+            original_name: Some("logo.png".to_string()),
+            target_densities: Some(vec![2.0, 1.0, 1.5, 2.0]),
+            ..Default::default()
+        };
+        let set = create_density_variants(&icon(100, 61), &options).unwrap();
+        assert_eq!((set.base_width, set.base_height), (33, 20));
+        assert!(!set.exact);
+        assert_eq!(
+            summary(&set),
+            vec![
+                ("logo.png".to_string(), 33, 20),
+                ("logo@1.5x.png".to_string(), 50, 30),
+                ("logo@2x.png".to_string(), 66, 40),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid_options() {
+        let img = icon(30, 30);
+        for options in [
+            DensityOptions { source_density: Some(0.5), ..Default::default() },
+            DensityOptions { target_densities: Some(vec![]), ..Default::default() },
+            DensityOptions { target_densities: Some(vec![4.0]), ..Default::default() },
+            DensityOptions { target_densities: Some(vec![0.0]), ..Default::default() },
+            DensityOptions { format: Some("avif".to_string()), ..Default::default() },
+        ] {
+            assert!(create_density_variants(&img, &options).is_err());
+        }
+    }
+}
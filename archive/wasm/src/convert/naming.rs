@@ -0,0 +1,399 @@
+//! 上传文件名模板
+//!
+//! 根据模板生成仓库中的文件路径，如 `{yyyy}/{mm}/{hash:8}-{name}.{ext}`。
+//! 支持的占位符：
+//! - `{yyyy}` `{yy}` `{mm}` `{dd}` `{hh}` `{min}` `{ss}`：拍摄时间（EXIF），没有时使用 fallback_date
+//! - `{hash}` `{hash:N}`：内容 SHA-256（十六进制），可截取前 N 位
+//! - `{name}`：原始文件名（不含目录和扩展名）
+//! - `{ext}`：输出格式的扩展名
+//! - `{width}` `{height}`：输出尺寸
+//!
+//! 生成的每一级路径都会去除 GitHub / Gitee 不允许或在 Windows 上无法检出的字符
+
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::hash::content::{digest, HashAlgorithm};
+use crate::hash::to_hex;
+use crate::info::probe;
+use crate::metadata;
+
+use super::types::SupportedFormat;
+
+/// 单级路径的最大字节数
+const MAX_SEGMENT_BYTES: usize = 255;
+
+/// 文件名模板选项
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileNameOptions {
+    /// 模板，如 {yyyy}/{mm}/{hash:8}-{name}.{ext}
+    pub template: String,
+    /// 原始文件名，默认 image
+    pub original_name: Option<String>,
+    /// 没有 EXIF 拍摄时间时使用的日期 (YYYY-MM-DD 或 ISO 8601)
+    pub fallback_date: Option<String>,
+}
+
+/// 拍摄时间
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CaptureDate {
+    pub year: u32,
+    pub month: u32,
+    pub day: u32,
+    pub hour: u32,
+    pub minute: u32,
+    pub second: u32,
+}
+
+impl CaptureDate {
+    /// 解析 EXIF (YYYY:MM:DD HH:MM:SS)、YYYY-MM-DD 或 ISO 8601 格式的日期
+    pub fn parse(text: &str) -> Option<Self> {
+        let parts: Vec<u32> = text
+            .split(|c: char| !c.is_ascii_digit())
+            .filter(|part| !part.is_empty())
+            .take(6)
+            .map(|part| part.parse().ok())
+            .collect::<Option<_>>()?;
+        if parts.len() < 3 {
+            return None;
+        }
+
+        let date = Self {
+            year: parts[0],
+            month: parts[1],
+            day: parts[2],
+            hour: parts.get(3).copied().unwrap_or(0),
+            minute: parts.get(4).copied().unwrap_or(0),
+            second: parts.get(5).copied().unwrap_or(0),
+        };
+        let valid = (1000..=9999).contains(&date.year)
+            && (1..=12).contains(&date.month)
+            && (1..=31).contains(&date.day)
+            && date.hour < 24
+            && date.minute < 60
+            && date.second < 61;
+        valid.then_some(date)
+    }
+}
+
+/// 模板渲染所需的图片信息
+#[derive(Debug, Clone)]
+pub struct NamingContext {
+    /// 原始文件名（不含目录和扩展名）
+    pub name: String,
+    /// 输出扩展名
+    pub ext: String,
+    /// 内容 SHA-256（十六进制）
+    pub content_hash: String,
+    /// 输出宽度
+    pub width: u32,
+    /// 输出高度
+    pub height: u32,
+    /// 拍摄时间
+    pub date: Option<CaptureDate>,
+}
+
+impl NamingContext {
+    /// 根据输出数据和源图片构建上下文
+    ///
+    /// 拍摄时间从源图片的 EXIF 读取（转换后的数据通常已去除元数据），
+    /// 内容哈希基于实际上传的输出数据
+    pub fn new(
+        output: &[u8],
+        source: &[u8],
+        ext: &str,
+        (width, height): (u32, u32),
+        options: &FileNameOptions,
+    ) -> Self {
+        let date = metadata::read_exif(source)
+            .ok()
+            .flatten()
+            .and_then(|exif| exif.date_time_original.or(exif.date_time))
+            .and_then(|text| CaptureDate::parse(&text))
+            .or_else(|| options.fallback_date.as_deref().and_then(CaptureDate::parse));
+
+        Self {
+            name: file_stem(options.original_name.as_deref().unwrap_or("image")),
+            ext: ext.to_string(),
+            content_hash: to_hex(&digest(output, HashAlgorithm::Sha256)),
+            width,
+            height,
+            date,
+        }
+    }
+}
+
+/// 按模板生成上传文件名（WASM 导出）
+///
+/// 用于未经转换直接上传的图片，扩展名根据文件头识别
+#[wasm_bindgen]
+pub fn render_file_name(image_data: &[u8], options: FileNameOptions) -> Result<String, JsValue> {
+    let probe = probe::probe(image_data).map_err(|e| JsValue::from_str(&e))?;
+    let ext = SupportedFormat::from_string(&probe.format)
+        .map(|format| format.extension())
+        .ok_or_else(|| JsValue::from_str(&format!("Unsupported image format: {}", probe.format)))?;
+    let context = NamingContext::new(
+        image_data,
+        image_data,
+        ext,
+        (probe.display_width, probe.display_height),
+        &options,
+    );
+    render_template(&options.template, &context).map_err(|e| JsValue::from_str(&e))
+}
+
+/// 渲染模板并清理路径
+pub fn render_template(template: &str, context: &NamingContext) -> Result<String, String> {
+    let mut rendered = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        rendered.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| format!("Unclosed template placeholder in: {}", template))?;
+        rendered.push_str(&render_placeholder(&rest[start + 1..start + end], context)?);
+        rest = &rest[start + end + 1..];
+    }
+    rendered.push_str(rest);
+
+    let path = sanitize_path(&rendered);
+    if path.is_empty() {
+        return Err(format!("Template renders an empty file name: {}", template));
+    }
+    Ok(path)
+}
+
+/// 渲染单个占位符
+fn render_placeholder(placeholder: &str, context: &NamingContext) -> Result<String, String> {
+    let (key, arg) = match placeholder.split_once(':') {
+        Some((key, arg)) => (key, Some(arg)),
+        None => (placeholder, None),
+    };
+
+    let date = || {
+        context
+            .date
+            .ok_or_else(|| format!("Template placeholder {{{}}} requires a capture date", key))
+    };
+
+    let value = match key {
+        "yyyy" => format!("{:04}", date()?.year),
+        "yy" => format!("{:02}", date()?.year % 100),
+        "mm" => format!("{:02}", date()?.month),
+        "dd" => format!("{:02}", date()?.day),
+        "hh" => format!("{:02}", date()?.hour),
+        "min" => format!("{:02}", date()?.minute),
+        "ss" => format!("{:02}", date()?.second),
+        "hash" => {
+            let length = match arg {
+                Some(arg) => arg
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|n| (1..=context.content_hash.len()).contains(n))
+                    .ok_or_else(|| format!("Invalid hash length: {}", arg))?,
+                None => context.content_hash.len(),
+            };
+            context.content_hash[..length].to_string()
+        }
+        // 文件名中的分隔符不能生成额外的目录
+        "name" => context.name.replace(['/', '\\'], "-"),
+        "ext" => context.ext.clone(),
+        "width" => context.width.to_string(),
+        "height" => context.height.to_string(),
+        _ => return Err(format!("Unknown template placeholder: {{{}}}", placeholder)),
+    };
+
+    if arg.is_some() && key != "hash" {
+        return Err(format!("Unknown template placeholder: {{{}}}", placeholder));
+    }
+    Ok(value)
+}
+
+/// 去除目录和扩展名
+pub fn file_stem(file_name: &str) -> String {
+    let base = file_name.rsplit(['/', '\\']).next().unwrap_or(file_name);
+    match base.rfind('.') {
+        Some(index) if index > 0 => base[..index].to_string(),
+        _ => base.to_string(),
+    }
+}
+
+/// 清理路径：逐级去除非法字符，丢弃空目录、`.` 和 `..`
+pub fn sanitize_path(path: &str) -> String {
+    path.split(['/', '\\'])
+        .filter_map(sanitize_segment)
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// 清理单级路径，结果为空时返回 None
+fn sanitize_segment(segment: &str) -> Option<String> {
+    let mut cleaned = String::with_capacity(segment.len());
+    for c in segment.chars() {
+        let c = match c {
+            ':' | '*' | '?' | '"' | '<' | '>' | '|' | '#' | '%' => '-',
+            c if c.is_control() => continue,
+            c if c.is_whitespace() => '-',
+            c => c,
+        };
+        // 合并连续的替换字符
+        if c == '-' && cleaned.ends_with('-') {
+            continue;
+        }
+        cleaned.push(c);
+    }
+
+    // 首尾的点和空白在 Windows 上会被忽略，.git 目录无法提交
+    let mut cleaned = cleaned.trim_matches(|c| c == '.' || c == '-').to_string();
+    if cleaned.is_empty() {
+        return None;
+    }
+    if cleaned.eq_ignore_ascii_case("git") && segment.trim().starts_with('.') {
+        cleaned = "_git".to_string();
+    }
+    if is_windows_reserved(&cleaned) {
+        cleaned.insert(0, '_');
+    }
+
+    if cleaned.len() > MAX_SEGMENT_BYTES {
+        cleaned = truncate_segment(&cleaned);
+    }
+    Some(cleaned)
+}
+
+/// 截断超长的单级路径：保留最后的扩展名，只截短主干，截断处不留 `.` 或 `-`
+fn truncate_segment(segment: &str) -> String {
+    let (stem, extension) = match segment.rfind('.') {
+        Some(dot) if segment.len() - dot < MAX_SEGMENT_BYTES / 2 => segment.split_at(dot),
+        _ => (segment, ""),
+    };
+
+    let mut end = MAX_SEGMENT_BYTES - extension.len();
+    while !stem.is_char_boundary(end) {
+        end -= 1;
+    }
+    let stem = stem[..end].trim_end_matches(['.', '-']);
+    format!("{}{}", stem, extension)
+}
+
+/// Windows 保留的设备名（不区分大小写，忽略扩展名）
+fn is_windows_reserved(segment: &str) -> bool {
+    let stem = segment.split('.').next().unwrap_or(segment).to_ascii_uppercase();
+    matches!(stem.as_str(), "CON" | "PRN" | "AUX" | "NUL")
+        || (stem.len() == 4
+            && (stem.starts_with("COM") || stem.starts_with("LPT"))
+            && stem.as_bytes()[3].is_ascii_digit()
+            && stem.as_bytes()[3] != b'0')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context() -> NamingContext {
+        NamingContext {
+            name: "IMG_0042".to_string(),
+            ext: "webp".to_string(),
+            content_hash: "0123456789abcdef".repeat(4),
+            width: 1920,
+            height: 1080,
+            date: CaptureDate::parse("2024:05:01 10:20:30"),
+        }
+    }
+
+    #[test]
+    fn test_render_template() {
+        let ctx = context();
+        assert_eq!(
+            render_template("{yyyy}/{mm}/{hash:8}-{name}.{ext}", &ctx).unwrap(),
+            "2024/05/01234567-IMG_0042.webp"
+        );
+        assert_eq!(
+            render_template("{yy}{mm}{dd}-{hh}{min}{ss}_{width}x{height}.{ext}", &ctx).unwrap(),
+            "240501-102030_1920x1080.webp"
+        );
+        assert_eq!(render_template("{hash}", &ctx).unwrap().len(), 64);
+    }
+
+    #[test]
+    fn test_render_template_errors() {
+        let mut ctx = context();
+        assert_eq!(
+            render_template("{foo}.png", &ctx).unwrap_err(),
+            "Unknown template placeholder: {foo}"
+        );
+        assert!(render_template("{name:3}", &ctx).is_err());
+        assert!(render_template("{hash:0}", &ctx).is_err());
+        assert!(render_template("{hash:65}", &ctx).is_err());
+        assert!(render_template("{name", &ctx).is_err());
+        assert!(render_template("../", &ctx).is_err());
+
+        ctx.date = None;
+        assert_eq!(
+            render_template("{yyyy}/{name}", &ctx).unwrap_err(),
+            "Template placeholder {yyyy} requires a capture date"
+        );
+    }
+
+    #[test]
+    fn test_sanitize_path() {
+        assert_eq!(sanitize_path("a//b/./../c"), "a/b/c");
+        assert_eq!(sanitize_path("my photo: final?.jpg"), "my-photo-final-.jpg");
+        assert_eq!(sanitize_path(" .hidden./x"), "hidden/x");
+        assert_eq!(sanitize_path(".git/config"), "_git/config");
+        assert_eq!(sanitize_path("con.png"), "_con.png");
+        assert_eq!(sanitize_path("COM1/LPT0"), "_COM1/LPT0");
+        assert_eq!(sanitize_path("a\\b"), "a/b");
+        assert_eq!(sanitize_path(&"长".repeat(100)).len(), 255);
+        // 超长文件名截短主干，保留扩展名
+        let long = sanitize_path(&format!("{}.webp", "长".repeat(100)));
+        assert_eq!(long, format!("{}.webp", "长".repeat(83)));
+        let long = sanitize_path(&format!("{}-{}.jpg", "a".repeat(250), "b".repeat(50)));
+        assert_eq!(long, format!("{}.jpg", "a".repeat(250)));
+
+        let mut ctx = context();
+        ctx.name = "../../etc/passwd".to_string();
+        assert_eq!(render_template("{name}.{ext}", &ctx).unwrap(), "etc-passwd.webp");
+    }
+
+    #[test]
+    fn test_render_file_name() {
+        let img = image::DynamicImage::ImageRgb8(image::ImageBuffer::from_pixel(8, 6, image::Rgb([1, 2, 3])));
+        let options = FileNameOptions {
+            template: "{name}_{width}x{height}.{ext}".to_string(),
+            original_name: Some("photo.jpeg".to_string()),
+            fallback_date: None,
+        };
+
+        for (format, expected) in [
+            (image::ImageOutputFormat::Jpeg(80), "photo_8x6.jpg"),
+            (image::ImageOutputFormat::Png, "photo_8x6.png"),
+            (image::ImageOutputFormat::Tiff, "photo_8x6.tiff"),
+        ] {
+            let mut data = Vec::new();
+            img.write_to(&mut std::io::Cursor::new(&mut data), format).unwrap();
+            assert_eq!(render_file_name(&data, options.clone()).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_capture_date_and_file_stem() {
+        let expected = CaptureDate {
+            year: 2024,
+            month: 5,
+            day: 1,
+            hour: 0,
+            minute: 0,
+            second: 0,
+        };
+        assert_eq!(CaptureDate::parse("2024-05-01"), Some(expected));
+        assert_eq!(CaptureDate::parse("2024-05-01T08:09:10.123Z").unwrap().second, 10);
+        assert_eq!(CaptureDate::parse("0000:00:00 00:00:00"), None);
+        assert_eq!(CaptureDate::parse("2024"), None);
+
+        assert_eq!(file_stem("C:\\photos\\IMG_1.JPG"), "IMG_1");
+        assert_eq!(file_stem("dir/archive.tar.gz"), "archive.tar");
+        assert_eq!(file_stem(".env"), ".env");
+    }
+}
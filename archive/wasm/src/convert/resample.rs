@@ -0,0 +1,809 @@
+//! 重采样滤波器
+//!
+//! 可分离卷积实现的缩放：先按输出坐标预先计算每行/列的滤波权重，
+//! 再依次做水平和垂直两次一维卷积。缩小时按缩放比例放宽滤波器支撑范围，
+//! 以免丢失细节产生摩尔纹；nearest 直接取样，适合像素画。
+//!
+//! 缩小超过 4 倍时先按整数倍做区域平均，再用滤波器完成剩余的缩放，
+//! 避免超宽的滤波窗口；wasm 启用 simd128 时内层循环使用 SIMD 指令
+//! （SIMD 版本单独构建，见 package.json 的 build:wasm:*:simd）
+//!
+//! 可选在线性光中卷积：8 位图片用查找表完成 sRGB 与线性光之间的转换。
+//! 带 alpha 的图片（LumaA / RGBA）在预乘 alpha 后卷积，避免透明区域的颜色在边缘形成暗边
+
+use std::sync::OnceLock;
+
+use image::{DynamicImage, GenericImageView, ImageBuffer, Pixel};
+
+/// auto 模式下缩小超过该倍数时使用 bilinear
+const AUTO_FAST_RATIO: f64 = 3.0;
+
+/// 重采样滤波器
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ResampleFilter {
+    /// 按缩放比例自动选择，见 `resolve`
+    Auto,
+    Nearest,
+    Bilinear,
+    CatmullRom,
+    Mitchell,
+    Lanczos3,
+}
+
+impl ResampleFilter {
+    /// 从字符串解析滤波器
+    pub fn from_string(filter_str: &str) -> Option<Self> {
+        let normalized: String = filter_str
+            .to_lowercase()
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric())
+            .collect();
+        match normalized.as_str() {
+            "auto" => Some(ResampleFilter::Auto),
+            "nearest" | "point" => Some(ResampleFilter::Nearest),
+            "bilinear" | "linear" | "triangle" => Some(ResampleFilter::Bilinear),
+            "catmullrom" | "bicubic" => Some(ResampleFilter::CatmullRom),
+            "mitchell" => Some(ResampleFilter::Mitchell),
+            "lanczos3" | "lanczos" => Some(ResampleFilter::Lanczos3),
+            _ => None,
+        }
+    }
+
+    /// 解析可选的滤波器，未指定时为 auto
+    pub fn parse_or_auto(filter_str: Option<&str>) -> Result<Self, String> {
+        match filter_str {
+            Some(s) => Self::from_string(s).ok_or_else(|| format!("Unsupported resample filter: {}", s)),
+            None => Ok(ResampleFilter::Auto),
+        }
+    }
+
+    /// 按源尺寸和目标尺寸确定实际使用的滤波器
+    ///
+    /// auto：放大用 catmull-rom，小幅缩小用 lanczos3 保持锐利，大幅缩小用 bilinear 提高速度
+    pub fn resolve(self, source: (u32, u32), target: (u32, u32)) -> Self {
+        if self != ResampleFilter::Auto {
+            return self;
+        }
+        let ratio = (source.0 as f64 / target.0.max(1) as f64).max(source.1 as f64 / target.1.max(1) as f64);
+        if ratio >= AUTO_FAST_RATIO {
+            ResampleFilter::Bilinear
+        } else if ratio > 1.0 {
+            ResampleFilter::Lanczos3
+        } else {
+            ResampleFilter::CatmullRom
+        }
+    }
+
+    /// 滤波器在源像素单位下的支撑半径
+    fn support(&self) -> f32 {
+        match self {
+            ResampleFilter::Nearest => 0.5,
+            ResampleFilter::Bilinear => 1.0,
+            ResampleFilter::CatmullRom | ResampleFilter::Mitchell => 2.0,
+            ResampleFilter::Auto | ResampleFilter::Lanczos3 => 3.0,
+        }
+    }
+
+    /// 滤波核（未解析的 auto 按 lanczos3 处理）
+    fn kernel(&self, x: f32) -> f32 {
+        let x = x.abs();
+        match self {
+            ResampleFilter::Nearest => {
+                if x < 0.5 { 1.0 } else { 0.0 }
+            }
+            ResampleFilter::Bilinear => (1.0 - x).max(0.0),
+            ResampleFilter::CatmullRom => cubic(x, 0.0, 0.5),
+            ResampleFilter::Mitchell => cubic(x, 1.0 / 3.0, 1.0 / 3.0),
+            ResampleFilter::Auto | ResampleFilter::Lanczos3 => {
+                if x < 3.0 { sinc(x) * sinc(x / 3.0) } else { 0.0 }
+            }
+        }
+    }
+}
+
+/// Mitchell-Netravali 三次样条
+fn cubic(x: f32, b: f32, c: f32) -> f32 {
+    let x2 = x * x;
+    let x3 = x2 * x;
+    let value = if x < 1.0 {
+        (12.0 - 9.0 * b - 6.0 * c) * x3 + (-18.0 + 12.0 * b + 6.0 * c) * x2 + (6.0 - 2.0 * b)
+    } else if x < 2.0 {
+        (-b - 6.0 * c) * x3 + (6.0 * b + 30.0 * c) * x2 + (-12.0 * b - 48.0 * c) * x + (8.0 * b + 24.0 * c)
+    } else {
+        0.0
+    };
+    value / 6.0
+}
+
+fn sinc(x: f32) -> f32 {
+    if x == 0.0 {
+        1.0
+    } else {
+        let a = x * std::f32::consts::PI;
+        a.sin() / a
+    }
+}
+
+/// 预先计算的一维滤波权重表：第 i 个输出像素使用从 starts[i] 开始的 taps 个连续源像素
+struct Kernel {
+    starts: Vec<usize>,
+    taps: usize,
+    weights: Vec<f32>,
+}
+
+impl Kernel {
+    fn new(source_len: u32, target_len: u32, filter: ResampleFilter) -> Self {
+        let scale = source_len as f32 / target_len as f32;
+        // nearest 不随缩放放宽，保证每个输出像素只取一个源像素
+        let filter_scale = if filter == ResampleFilter::Nearest { 1.0 } else { scale.max(1.0) };
+        let support = filter.support() * filter_scale;
+        let source_len = source_len as usize;
+        // 所有窗口统一为 taps 个像素，内层循环长度固定，便于向量化
+        let taps = ((support * 2.0).ceil() as usize + 1).min(source_len);
+
+        let mut starts = Vec::with_capacity(target_len as usize);
+        let mut weights = vec![0.0f32; target_len as usize * taps];
+        for (i, window) in weights.chunks_exact_mut(taps).enumerate() {
+            let center = (i as f32 + 0.5) * scale;
+            let left = ((center - support).floor().max(0.0) as usize).min(source_len - 1);
+            let right = ((center + support).ceil() as usize).clamp(left + 1, source_len);
+            let start = left.min(source_len - taps);
+            let mut sum = 0.0;
+            for j in left..right.min(start + taps) {
+                let w = filter.kernel((j as f32 + 0.5 - center) / filter_scale);
+                window[j - start] = w;
+                sum += w;
+            }
+            if sum.abs() > f32::EPSILON {
+                window.iter_mut().for_each(|w| *w /= sum);
+            } else {
+                // 支撑范围内没有有效权重时退化为最近邻
+                let nearest = (center.floor() as usize).clamp(start, start + taps - 1);
+                window[nearest - start] = 1.0;
+            }
+            starts.push(start);
+        }
+        Self { starts, taps, weights }
+    }
+
+    fn window(&self, i: usize) -> &[f32] {
+        &self.weights[i * self.taps..(i + 1) * self.taps]
+    }
+}
+
+/// 大幅缩小时先用整数倍的区域平均预缩小，使最后一步滤波的缩小倍数不低于该值
+const PRESCALE_MIN_RATIO: u32 = 2;
+
+/// 区域平均预缩小的倍数（水平、垂直），1 表示不需要
+fn prescale_factors(source: (u32, u32), target: (u32, u32), filter: ResampleFilter) -> (u32, u32) {
+    if filter == ResampleFilter::Nearest {
+        return (1, 1);
+    }
+    let factor = |source: u32, target: u32| (source / target.max(1) / PRESCALE_MIN_RATIO).max(1);
+    (factor(source.0, target.0), factor(source.1, target.1))
+}
+
+/// 按 factor_x x factor_y 的块求平均，边缘不完整的块按实际像素数平均
+fn box_reduce<const N: usize, T: Copy>(
+    source: &[T],
+    (width, height): (u32, u32),
+    (factor_x, factor_y): (u32, u32),
+    decode: impl Fn(&[T]) -> [f32; N],
+) -> (Vec<f32>, (u32, u32)) {
+    let (reduced_width, reduced_height) = (width.div_ceil(factor_x), height.div_ceil(factor_y));
+    let (width, height) = (width as usize, height as usize);
+    let (factor_x, factor_y) = (factor_x as usize, factor_y as usize);
+    let row_len = reduced_width as usize * N;
+    let mut output = vec![0.0f32; row_len * reduced_height as usize];
+
+    for (y, row) in output.chunks_exact_mut(row_len).enumerate() {
+        let rows = y * factor_y..((y + 1) * factor_y).min(height);
+        let row_count = rows.len();
+        for source_y in rows {
+            let source_row = &source[source_y * width * N..(source_y + 1) * width * N];
+            for (target, block) in row.chunks_exact_mut(N).zip(source_row.chunks(factor_x * N)) {
+                for pixel in block.chunks_exact(N) {
+                    for (t, v) in target.iter_mut().zip(decode(pixel)) {
+                        *t += v;
+                    }
+                }
+            }
+        }
+        for (x, pixel) in row.chunks_exact_mut(N).enumerate() {
+            let column_count = factor_x.min(width - x * factor_x);
+            let scale = 1.0 / (row_count * column_count) as f32;
+            pixel.iter_mut().for_each(|v| *v *= scale);
+        }
+    }
+    (output, (reduced_width, reduced_height))
+}
+
+/// 对交错存储的多通道数据做可分离卷积缩放，大幅缩小时先区域平均预缩小
+fn resample_channels<const N: usize, T: Channel>(
+    source: &[T],
+    source_size: (u32, u32),
+    target_size: (u32, u32),
+    filter: ResampleFilter,
+    linear_light: bool,
+) -> Vec<T> {
+    let decode = |pixel: &[T]| decode_pixel::<N, T>(pixel, linear_light);
+    let encode = |pixel: &[f32]| encode_pixel::<N, T>(pixel, linear_light);
+    let factors = prescale_factors(source_size, target_size, filter);
+    if factors == (1, 1) {
+        return convolve::<N, _, _>(source, source_size, target_size, filter, decode, encode);
+    }
+    let (reduced, reduced_size) = box_reduce::<N, _>(source, source_size, factors, decode);
+    let identity = |pixel: &[f32]| std::array::from_fn(|c| pixel[c]);
+    convolve::<N, _, _>(&reduced, reduced_size, target_size, filter, identity, encode)
+}
+
+/// 先水平、后垂直的两次一维卷积，decode / encode 负责像素与卷积空间之间的转换
+fn convolve<const N: usize, T: Copy, U: Copy>(
+    source: &[T],
+    (source_width, source_height): (u32, u32),
+    (target_width, target_height): (u32, u32),
+    filter: ResampleFilter,
+    decode: impl Fn(&[T]) -> [f32; N],
+    encode: impl Fn(&[f32]) -> [U; N],
+) -> Vec<U> {
+    let horizontal = Kernel::new(source_width, target_width, filter);
+    let vertical = Kernel::new(source_height, target_height, filter);
+    // RGB 在中间结果中补齐为 4 个通道，每个像素正好对应一个 f32x4
+    let lanes = if N == 3 { 4 } else { N };
+    let row_len = target_width as usize * lanes;
+
+    // 水平方向的结果只保留当前垂直窗口需要的 taps 行，按行号循环存放
+    let taps = vertical.taps;
+    let mut ring = vec![0.0f32; row_len * taps];
+    let mut scratch = vec![0.0f32; source_width as usize * lanes];
+    let source_len = source_width as usize * N;
+    let mut next_row = 0;
+
+    let mut output = Vec::with_capacity(target_width as usize * N * target_height as usize);
+    let mut accumulator = vec![0.0f32; row_len];
+    for (y, &start) in vertical.starts.iter().enumerate() {
+        // 逐行转成 f32 后做水平卷积，窗口之前的行不再需要，直接跳过
+        for source_y in next_row.max(start)..start + taps {
+            let source_row = &source[source_y * source_len..(source_y + 1) * source_len];
+            for (s, pixel) in scratch.chunks_exact_mut(lanes).zip(source_row.chunks_exact(N)) {
+                s[..N].copy_from_slice(&decode(pixel));
+            }
+            let slot = source_y % taps;
+            let row = &mut ring[slot * row_len..(slot + 1) * row_len];
+            match lanes {
+                1 => simd::convolve_row::<1>(&scratch, row, &horizontal),
+                2 => simd::convolve_row::<2>(&scratch, row, &horizontal),
+                _ => simd::convolve_row::<4>(&scratch, row, &horizontal),
+            }
+        }
+        next_row = start + taps;
+
+        // 垂直方向：按权重累加整行
+        accumulator.iter_mut().for_each(|v| *v = 0.0);
+        for (k, &w) in vertical.window(y).iter().enumerate() {
+            if w != 0.0 {
+                let slot = (start + k) % taps;
+                simd::accumulate(&mut accumulator, &ring[slot * row_len..(slot + 1) * row_len], w);
+            }
+        }
+        for pixel in accumulator.chunks_exact(lanes) {
+            output.extend_from_slice(&encode(&pixel[..N]));
+        }
+    }
+    output
+}
+
+/// 卷积内层循环，wasm 启用 simd128 时使用 SIMD 指令
+#[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+mod simd {
+    use core::arch::wasm32::*;
+
+    use super::Kernel;
+
+    /// 一行的水平卷积，4 通道时每个像素用一个 f32x4 计算
+    pub fn convolve_row<const N: usize>(source: &[f32], output: &mut [f32], kernel: &Kernel) {
+        if N != 4 {
+            return super::scalar::convolve_row::<N>(source, output, kernel);
+        }
+        for (x, pixel) in output.chunks_exact_mut(4).enumerate() {
+            let window = kernel.window(x);
+            let pixels = &source[kernel.starts[x] * 4..(kernel.starts[x] + window.len()) * 4];
+            // SAFETY: pixels 的长度为 window.len() * 4，pixel 的长度为 4，读写都在切片范围内
+            unsafe {
+                let mut sum = f32x4_splat(0.0);
+                for (k, &w) in window.iter().enumerate() {
+                    let value = v128_load(pixels.as_ptr().add(k * 4) as *const v128);
+                    sum = f32x4_add(sum, f32x4_mul(value, f32x4_splat(w)));
+                }
+                v128_store(pixel.as_mut_ptr() as *mut v128, sum);
+            }
+        }
+    }
+
+    /// accumulator += row * weight
+    pub fn accumulate(accumulator: &mut [f32], row: &[f32], weight: f32) {
+        let lanes = accumulator.len() / 4 * 4;
+        // SAFETY: 只访问前 lanes 个元素，row 与 accumulator 等长
+        unsafe {
+            let w = f32x4_splat(weight);
+            for i in (0..lanes).step_by(4) {
+                let acc = accumulator.as_mut_ptr().add(i) as *mut v128;
+                let value = v128_load(row.as_ptr().add(i) as *const v128);
+                v128_store(acc, f32x4_add(v128_load(acc), f32x4_mul(value, w)));
+            }
+        }
+        super::scalar::accumulate(&mut accumulator[lanes..], &row[lanes..], weight);
+    }
+}
+
+#[cfg(not(all(target_arch = "wasm32", target_feature = "simd128")))]
+use scalar as simd;
+
+/// 卷积内层循环的标量实现，窗口长度固定，编译器可以自动向量化
+mod scalar {
+    use super::Kernel;
+
+    pub fn convolve_row<const N: usize>(source: &[f32], output: &mut [f32], kernel: &Kernel) {
+        for (x, pixel) in output.chunks_exact_mut(N).enumerate() {
+            let window = kernel.window(x);
+            let pixels = &source[kernel.starts[x] * N..(kernel.starts[x] + window.len()) * N];
+            let mut sum = [0.0f32; N];
+            for (value, &w) in pixels.chunks_exact(N).zip(window) {
+                for (s, &v) in sum.iter_mut().zip(value) {
+                    *s += v * w;
+                }
+            }
+            pixel.copy_from_slice(&sum);
+        }
+    }
+
+    pub fn accumulate(accumulator: &mut [f32], row: &[f32], weight: f32) {
+        for (acc, &v) in accumulator.iter_mut().zip(row) {
+            *acc += v * weight;
+        }
+    }
+}
+
+fn resample_buffer<P>(
+    buffer: &ImageBuffer<P, Vec<P::Subpixel>>,
+    width: u32,
+    height: u32,
+    filter: ResampleFilter,
+    linear_light: bool,
+) -> ImageBuffer<P, Vec<P::Subpixel>>
+where
+    P: Pixel,
+    P::Subpixel: Channel,
+{
+    let (source, size) = (buffer.as_raw(), buffer.dimensions());
+    let data = match P::CHANNEL_COUNT {
+        1 => resample_channels::<1, _>(source, size, (width, height), filter, linear_light),
+        2 => resample_channels::<2, _>(source, size, (width, height), filter, linear_light),
+        3 => resample_channels::<3, _>(source, size, (width, height), filter, linear_light),
+        _ => resample_channels::<4, _>(source, size, (width, height), filter, linear_light),
+    };
+    ImageBuffer::from_raw(width, height, data).expect("resampled buffer size matches dimensions")
+}
+
+/// 像素通道值与卷积使用的 f32 之间的转换（f32 保持通道原有的量程）
+trait Channel: Copy {
+    /// 通道最大值
+    const MAX: f32;
+
+    fn to_f32(self) -> f32;
+
+    fn from_f32(value: f32) -> Self;
+
+    /// sRGB 编码值转成线性光
+    fn to_linear(self) -> f32 {
+        srgb_to_linear(self.to_f32() / Self::MAX) * Self::MAX
+    }
+
+    /// 线性光转回 sRGB 编码值
+    fn from_linear(value: f32) -> Self {
+        Self::from_f32(linear_to_srgb(value / Self::MAX) * Self::MAX)
+    }
+}
+
+impl Channel for u8 {
+    const MAX: f32 = 255.0;
+
+    fn to_f32(self) -> f32 {
+        self as f32
+    }
+
+    /// 截断前加 0.5 实现四舍五入，比 f32::round 快
+    fn from_f32(value: f32) -> Self {
+        (value.clamp(0.0, 255.0) + 0.5) as u8
+    }
+
+    fn to_linear(self) -> f32 {
+        srgb_to_linear_table()[self as usize]
+    }
+
+    fn from_linear(value: f32) -> Self {
+        let index = (value.clamp(0.0, 255.0) * (LINEAR_TABLE_SIZE as f32 / 255.0) + 0.5) as usize;
+        linear_to_srgb_table()[index]
+    }
+}
+
+impl Channel for u16 {
+    const MAX: f32 = 65535.0;
+
+    fn to_f32(self) -> f32 {
+        self as f32
+    }
+
+    fn from_f32(value: f32) -> Self {
+        (value.clamp(0.0, 65535.0) + 0.5) as u16
+    }
+}
+
+impl Channel for f32 {
+    const MAX: f32 = 1.0;
+
+    fn to_f32(self) -> f32 {
+        self
+    }
+
+    fn from_f32(value: f32) -> Self {
+        value
+    }
+}
+
+/// 颜色通道数：LumaA / RGBA 的最后一个通道为 alpha
+fn color_channels(channels: usize) -> usize {
+    match channels {
+        2 | 4 => channels - 1,
+        _ => channels,
+    }
+}
+
+/// 像素转成卷积使用的值：颜色通道按需转成线性光后预乘 alpha，
+/// 完全透明像素的颜色不会混入边缘；alpha 通道保持原值
+fn decode_pixel<const N: usize, T: Channel>(pixel: &[T], linear_light: bool) -> [f32; N] {
+    let color_channels = color_channels(N);
+    let mut values = std::array::from_fn(|c| {
+        if linear_light && c < color_channels {
+            pixel[c].to_linear()
+        } else {
+            pixel[c].to_f32()
+        }
+    });
+    if color_channels < N {
+        let alpha = values[N - 1] / T::MAX;
+        values[..color_channels].iter_mut().for_each(|v| *v *= alpha);
+    }
+    values
+}
+
+/// 卷积结果转回像素，带 alpha 时先还原预乘
+fn encode_pixel<const N: usize, T: Channel>(pixel: &[f32], linear_light: bool) -> [T; N] {
+    let color_channels = color_channels(N);
+    let alpha = pixel[N - 1].clamp(0.0, T::MAX) / T::MAX;
+    std::array::from_fn(|c| {
+        if c >= color_channels {
+            return T::from_f32(pixel[c]);
+        }
+        let value = match color_channels < N {
+            true if alpha > 0.0 => pixel[c] / alpha,
+            true => 0.0,
+            false => pixel[c],
+        };
+        if linear_light {
+            T::from_linear(value)
+        } else {
+            T::from_f32(value)
+        }
+    })
+}
+
+/// sRGB 编码值 (0-1) 转成线性光
+fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// 线性光 (0-1) 转成 sRGB 编码值
+fn linear_to_srgb(value: f32) -> f32 {
+    if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// 线性光转 8 位 sRGB 查找表的分段数，足以让 256 个值往返后保持不变
+const LINEAR_TABLE_SIZE: usize = 4096;
+
+/// 8 位 sRGB 值对应的线性光 (0-255)
+fn srgb_to_linear_table() -> &'static [f32; 256] {
+    static TABLE: OnceLock<[f32; 256]> = OnceLock::new();
+    TABLE.get_or_init(|| std::array::from_fn(|i| srgb_to_linear(i as f32 / 255.0) * 255.0))
+}
+
+/// 线性光 (0-255 等分为 LINEAR_TABLE_SIZE 段) 对应的 8 位 sRGB 值
+fn linear_to_srgb_table() -> &'static [u8; LINEAR_TABLE_SIZE + 1] {
+    static TABLE: OnceLock<[u8; LINEAR_TABLE_SIZE + 1]> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        std::array::from_fn(|i| (linear_to_srgb(i as f32 / LINEAR_TABLE_SIZE as f32) * 255.0 + 0.5) as u8)
+    })
+}
+
+/// 用指定滤波器把图片缩放到 width x height，保持原有的颜色类型
+///
+/// linear_light 为 true 时颜色通道先从 sRGB 转成线性光再卷积，
+/// 避免缩小后细亮线和高对比纹理整体偏暗
+pub fn resample(
+    img: &DynamicImage,
+    width: u32,
+    height: u32,
+    filter: ResampleFilter,
+    linear_light: bool,
+) -> DynamicImage {
+    let (width, height) = (width.max(1), height.max(1));
+    if (width, height) == img.dimensions() {
+        return img.clone();
+    }
+    let filter = filter.resolve(img.dimensions(), (width, height));
+
+    match img {
+        DynamicImage::ImageLuma8(buffer) => {
+            DynamicImage::ImageLuma8(resample_buffer(buffer, width, height, filter, linear_light))
+        }
+        DynamicImage::ImageLumaA8(buffer) => {
+            DynamicImage::ImageLumaA8(resample_buffer(buffer, width, height, filter, linear_light))
+        }
+        DynamicImage::ImageRgb8(buffer) => {
+            DynamicImage::ImageRgb8(resample_buffer(buffer, width, height, filter, linear_light))
+        }
+        DynamicImage::ImageRgba8(buffer) => {
+            DynamicImage::ImageRgba8(resample_buffer(buffer, width, height, filter, linear_light))
+        }
+        DynamicImage::ImageLuma16(buffer) => {
+            DynamicImage::ImageLuma16(resample_buffer(buffer, width, height, filter, linear_light))
+        }
+        DynamicImage::ImageLumaA16(buffer) => {
+            DynamicImage::ImageLumaA16(resample_buffer(buffer, width, height, filter, linear_light))
+        }
+        DynamicImage::ImageRgb16(buffer) => {
+            DynamicImage::ImageRgb16(resample_buffer(buffer, width, height, filter, linear_light))
+        }
+        DynamicImage::ImageRgba16(buffer) => {
+            DynamicImage::ImageRgba16(resample_buffer(buffer, width, height, filter, linear_light))
+        }
+        DynamicImage::ImageRgb32F(buffer) => {
+            DynamicImage::ImageRgb32F(resample_buffer(buffer, width, height, filter, linear_light))
+        }
+        DynamicImage::ImageRgba32F(buffer) => {
+            DynamicImage::ImageRgba32F(resample_buffer(buffer, width, height, filter, linear_light))
+        }
+        other => {
+            DynamicImage::ImageRgba32F(resample_buffer(&other.to_rgba32f(), width, height, filter, linear_light))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{GrayImage, Luma, Rgb, RgbImage};
+
+    #[test]
+    fn test_parse_filter() {
+        assert_eq!(ResampleFilter::from_string("Catmull-Rom"), Some(ResampleFilter::CatmullRom));
+        assert_eq!(ResampleFilter::from_string("lanczos3"), Some(ResampleFilter::Lanczos3));
+        assert_eq!(ResampleFilter::from_string("box"), None);
+        assert_eq!(ResampleFilter::parse_or_auto(None), Ok(ResampleFilter::Auto));
+        assert_eq!(
+            ResampleFilter::parse_or_auto(Some("box")).unwrap_err(),
+            "Unsupported resample filter: box"
+        );
+    }
+
+    #[test]
+    fn test_auto_filter() {
+        let auto = ResampleFilter::Auto;
+        assert_eq!(auto.resolve((4000, 3000), (400, 300)), ResampleFilter::Bilinear);
+        assert_eq!(auto.resolve((1000, 800), (800, 640)), ResampleFilter::Lanczos3);
+        assert_eq!(auto.resolve((100, 100), (300, 300)), ResampleFilter::CatmullRom);
+        assert_eq!(ResampleFilter::Mitchell.resolve((4000, 3000), (40, 30)), ResampleFilter::Mitchell);
+    }
+
+    #[test]
+    fn test_kernels_partition_unity() {
+        for filter in [
+            ResampleFilter::Bilinear,
+            ResampleFilter::CatmullRom,
+            ResampleFilter::Mitchell,
+            ResampleFilter::Lanczos3,
+        ] {
+            for (source_len, target_len) in [(37, 11), (11, 37), (3, 40), (1000, 7)] {
+                let kernel = Kernel::new(source_len, target_len, filter);
+                for (i, &start) in kernel.starts.iter().enumerate() {
+                    let sum: f32 = kernel.window(i).iter().sum();
+                    assert!((sum - 1.0).abs() < 1e-4, "{:?}", filter);
+                    assert!(start + kernel.taps <= source_len as usize);
+                }
+            }
+        }
+        assert_eq!(cubic(0.0, 0.0, 0.5), 1.0);
+        assert!((cubic(0.0, 1.0 / 3.0, 1.0 / 3.0) - 8.0 / 9.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_nearest_keeps_pixel_art_sharp() {
+        // 2x2 棋盘放大 4 倍，nearest 只会出现原有颜色
+        let img = DynamicImage::ImageLuma8(GrayImage::from_fn(2, 2, |x, y| Luma([if (x + y) % 2 == 0 { 0 } else { 255 }])));
+        let scaled = resample(&img, 8, 8, ResampleFilter::Nearest, false).to_luma8();
+        assert!(scaled.pixels().all(|p| p.0[0] == 0 || p.0[0] == 255));
+        assert_eq!(scaled.get_pixel(3, 0).0[0], 0);
+        assert_eq!(scaled.get_pixel(4, 0).0[0], 255);
+
+        let smooth = resample(&img, 8, 8, ResampleFilter::Bilinear, false).to_luma8();
+        assert!(smooth.pixels().any(|p| p.0[0] != 0 && p.0[0] != 255));
+    }
+
+    #[test]
+    fn test_resample_keeps_color_type_and_solid_color() {
+        let img = DynamicImage::ImageRgb8(RgbImage::from_pixel(64, 48, Rgb([10, 120, 250])));
+        for filter in [
+            ResampleFilter::Auto,
+            ResampleFilter::Nearest,
+            ResampleFilter::Bilinear,
+            ResampleFilter::CatmullRom,
+            ResampleFilter::Mitchell,
+            ResampleFilter::Lanczos3,
+        ] {
+            let scaled = resample(&img, 17, 91, filter, false);
+            assert_eq!(scaled.dimensions(), (17, 91));
+            assert!(matches!(scaled, DynamicImage::ImageRgb8(_)));
+            assert!(scaled.to_rgb8().pixels().all(|p| p.0 == [10, 120, 250]));
+        }
+
+        let img16 = DynamicImage::ImageRgba16(ImageBuffer::from_pixel(10, 10, image::Rgba([1000, 2000, 3000, 65535])));
+        let scaled = resample(&img16, 3, 3, ResampleFilter::Lanczos3, false);
+        assert!(matches!(scaled, DynamicImage::ImageRgba16(_)));
+    }
+
+    #[test]
+    fn test_multi_step_downscale() {
+        assert_eq!(prescale_factors((6000, 4000), (640, 427), ResampleFilter::Lanczos3), (4, 4));
+        assert_eq!(prescale_factors((800, 600), (500, 100), ResampleFilter::Lanczos3), (1, 3));
+        assert_eq!(prescale_factors((6000, 4000), (640, 427), ResampleFilter::Nearest), (1, 1));
+
+        // 边缘不完整的块按实际像素数平均
+        let (reduced, size) = box_reduce::<1, _>(&[0u8, 10, 20, 30, 40], (5, 1), (2, 1), |p| [p[0] as f32]);
+        assert_eq!(size, (3, 1));
+        assert_eq!(reduced, vec![5.0, 25.0, 40.0]);
+
+        // 预缩小后的结果与直接卷积接近
+        let img = RgbImage::from_fn(800, 600, |x, y| Rgb([(x / 4) as u8, (y / 3) as u8, ((x + y) / 6) as u8]));
+        let filter = ResampleFilter::Lanczos3;
+        let decode = |p: &[u8]| decode_pixel::<3, u8>(p, false);
+        let encode = |p: &[f32]| encode_pixel::<3, u8>(p, false);
+        let direct = convolve::<3, _, _>(img.as_raw(), (800, 600), (100, 75), filter, decode, encode);
+        let stepped = resample_channels::<3, _>(img.as_raw(), (800, 600), (100, 75), filter, false);
+        assert!(direct.iter().zip(&stepped).all(|(&a, &b)| a.abs_diff(b) <= 2));
+    }
+
+    #[test]
+    fn test_linear_light_tables() {
+        for v in 0..=255u8 {
+            assert_eq!(u8::from_linear(v.to_linear()), v);
+        }
+        assert!((128u8.to_linear() - srgb_to_linear(128.0 / 255.0) * 255.0).abs() < 1e-4);
+        assert_eq!(u16::from_linear(40000u16.to_linear()), 40000);
+    }
+
+    #[test]
+    fn test_linear_light_keeps_thin_lines_bright() {
+        // 1 像素宽的黑白竖线缩小一半：线性光下为 50% 亮度，sRGB 编码约 188
+        let lines = |x: u32, alpha: u8| if x & 1 == 0 { image::LumaA([255, 255]) } else { image::LumaA([0, alpha]) };
+        let img = DynamicImage::ImageLumaA8(ImageBuffer::from_fn(64, 64, |x, _| lines(x, 255)));
+        let gamma = resample(&img, 32, 32, ResampleFilter::Bilinear, false).to_luma_alpha8();
+        let linear = resample(&img, 32, 32, ResampleFilter::Bilinear, true).to_luma_alpha8();
+        assert!(gamma.get_pixel(16, 16).0[0].abs_diff(128) <= 1);
+        assert!(linear.get_pixel(16, 16).0[0].abs_diff(188) <= 1);
+
+        // alpha 通道不做伽马转换
+        let img = DynamicImage::ImageLumaA8(ImageBuffer::from_fn(64, 64, |x, _| lines(x, 0)));
+        let linear = resample(&img, 32, 32, ResampleFilter::Bilinear, true).to_luma_alpha8();
+        assert_eq!(linear.get_pixel(16, 16).0, [255, 128]);
+    }
+
+    /// 透明背景上的红色圆形 logo，透明像素的 RGB 为黑色
+    fn logo_on_transparent() -> DynamicImage {
+        DynamicImage::ImageRgba8(ImageBuffer::from_fn(96, 96, |x, y| {
+            let (dx, dy) = (x as f32 - 47.5, y as f32 - 47.5);
+            if dx * dx + dy * dy < 30.0 * 30.0 {
+                image::Rgba([230, 20, 40, 255])
+            } else {
+                image::Rgba([0, 0, 0, 0])
+            }
+        }))
+    }
+
+    #[test]
+    fn test_premultiplied_alpha_has_no_dark_fringe() {
+        let logo = logo_on_transparent();
+        for filter in [ResampleFilter::Bilinear, ResampleFilter::CatmullRom, ResampleFilter::Lanczos3] {
+            for linear_light in [false, true] {
+                let scaled = resample(&logo, 37, 37, filter, linear_light).to_rgba8();
+                let mut edge_pixels = 0;
+                for pixel in scaled.pixels() {
+                    let [r, g, b, a] = pixel.0;
+                    if a == 0 || a == 255 {
+                        continue;
+                    }
+                    edge_pixels += 1;
+                    // 半透明边缘保持 logo 的颜色，不被透明区域的黑色拉暗
+                    if a >= 16 {
+                        assert!(r.abs_diff(230) <= 4 && g.abs_diff(20) <= 4 && b.abs_diff(40) <= 4, "{:?}", pixel);
+                    }
+                }
+                assert!(edge_pixels > 0);
+            }
+        }
+
+        // image 自带的缩放不预乘 alpha，边缘明显偏暗
+        let reference = logo.resize_exact(37, 37, image::imageops::FilterType::Triangle).to_rgba8();
+        assert!(reference.pixels().any(|p| p.0[3] >= 64 && p.0[3] < 255 && p.0[0] < 200));
+    }
+
+    #[test]
+    fn test_premultiplied_luma_alpha() {
+        // 白色文字在透明背景上
+        let img = DynamicImage::ImageLumaA8(ImageBuffer::from_fn(40, 40, |x, _| {
+            if x < 20 { image::LumaA([255, 255]) } else { image::LumaA([0, 0]) }
+        }));
+        let scaled = resample(&img, 15, 15, ResampleFilter::Lanczos3, false).to_luma_alpha8();
+        for pixel in scaled.pixels().filter(|p| p.0[1] >= 16) {
+            assert!(pixel.0[0] >= 250, "{:?}", pixel);
+        }
+        assert!(scaled.pixels().any(|p| p.0[1] > 0 && p.0[1] < 255));
+        // 完全透明的像素颜色为 0
+        assert_eq!(scaled.get_pixel(14, 7).0, [0, 0]);
+    }
+
+    /// 与 image 自带的 resize_exact 对比耗时，需要用 release 运行：
+    /// cargo test --release bench_resample -- --ignored --nocapture
+    #[test]
+    #[ignore]
+    fn bench_resample() {
+        use image::imageops::FilterType;
+        use std::time::Instant;
+
+        // 24 MP 照片缩小到常见的展示尺寸
+        let img = DynamicImage::ImageRgb8(RgbImage::from_fn(6000, 4000, |x, y| {
+            Rgb([(x % 251) as u8, (y % 241) as u8, ((x ^ y) % 256) as u8])
+        }));
+        for (width, height) in [(1920, 1280), (640, 427)] {
+            for (filter, reference) in [
+                (ResampleFilter::Bilinear, FilterType::Triangle),
+                (ResampleFilter::CatmullRom, FilterType::CatmullRom),
+                (ResampleFilter::Lanczos3, FilterType::Lanczos3),
+            ] {
+                let start = Instant::now();
+                let ours = resample(&img, width, height, filter, false);
+                let ours_time = start.elapsed();
+                let start = Instant::now();
+                let theirs = img.resize_exact(width, height, reference);
+                let theirs_time = start.elapsed();
+                assert_eq!(ours.dimensions(), theirs.dimensions());
+                println!(
+                    "{}x{} {:?}: {:?} vs image {:?} ({:.1}x)",
+                    width,
+                    height,
+                    filter,
+                    ours_time,
+                    theirs_time,
+                    theirs_time.as_secs_f64() / ours_time.as_secs_f64()
+                );
+            }
+        }
+    }
+}
@@ -1,16 +1,499 @@
 //! 图片尺寸调整功能模块
 //!
-//! 提供图片尺寸调整和宽高比计算功能
+//! 提供图片尺寸调整和宽高比计算功能，支持以下适配模式：
+//! - fill：拉伸到指定尺寸
+//! - inside：保持宽高比，缩放到框内（默认）
+//! - outside：保持宽高比，缩放到覆盖整个框
+//! - cover：同 outside，再按 gravity 裁掉超出部分，输出正好为指定尺寸；
+//!   gravity 为 smart 时按图片内容选择裁剪区域
+//! - contain（pad）：同 inside，再按 gravity 放置并填充到指定尺寸，
+//!   填充区域可以是透明、纯色或放大模糊后的原图
+//!
+//! 另外支持按百分比缩放（scale）、禁止放大（without_enlargement）和像素数上限（max_megapixels）
+
+use image::{DynamicImage, GenericImageView, Rgba, RgbaImage};
 
+use crate::convert::resample::{resample, ResampleFilter};
+use crate::convert::smartcrop::{find_smart_crop, CropRect};
 use crate::convert::types::ResizeOptions;
 
+/// 尺寸适配模式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ResizeFit {
+    Fill,
+    Inside,
+    Outside,
+    Cover,
+    Contain,
+}
+
+impl ResizeFit {
+    /// 从字符串解析适配模式
+    pub fn from_string(fit_str: &str) -> Option<Self> {
+        match fit_str.to_lowercase().as_str() {
+            "fill" => Some(ResizeFit::Fill),
+            "inside" => Some(ResizeFit::Inside),
+            "outside" => Some(ResizeFit::Outside),
+            "cover" => Some(ResizeFit::Cover),
+            "contain" | "pad" => Some(ResizeFit::Contain),
+            _ => None,
+        }
+    }
+
+    /// 适配模式名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ResizeFit::Fill => "fill",
+            ResizeFit::Inside => "inside",
+            ResizeFit::Outside => "outside",
+            ResizeFit::Cover => "cover",
+            ResizeFit::Contain => "contain",
+        }
+    }
+
+    /// 解析选项中的适配模式，未指定时由 maintain_aspect_ratio 决定（inside / fill）
+    pub fn from_options(resize: &ResizeOptions) -> Result<Self, String> {
+        match &resize.fit {
+            Some(fit) => Self::from_string(fit).ok_or_else(|| format!("Unsupported resize fit: {}", fit)),
+            None if resize.maintain_aspect_ratio.unwrap_or(true) => Ok(ResizeFit::Inside),
+            None => Ok(ResizeFit::Fill),
+        }
+    }
+}
+
+/// 裁剪或填充时的对齐位置
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Gravity {
+    Center,
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+    /// 按内容选择裁剪区域，只对 cover 生效，其余情况按居中处理
+    Smart,
+}
+
+impl Gravity {
+    /// 从字符串解析对齐位置，支持 north / top、northeast / top-right 等写法
+    pub fn from_string(gravity_str: &str) -> Option<Self> {
+        let normalized: String = gravity_str
+            .to_lowercase()
+            .chars()
+            .filter(|c| c.is_ascii_alphabetic())
+            .collect();
+        match normalized.as_str() {
+            "center" | "centre" => Some(Gravity::Center),
+            "north" | "top" => Some(Gravity::North),
+            "northeast" | "topright" => Some(Gravity::NorthEast),
+            "east" | "right" => Some(Gravity::East),
+            "southeast" | "bottomright" => Some(Gravity::SouthEast),
+            "south" | "bottom" => Some(Gravity::South),
+            "southwest" | "bottomleft" => Some(Gravity::SouthWest),
+            "west" | "left" => Some(Gravity::West),
+            "northwest" | "topleft" => Some(Gravity::NorthWest),
+            "smart" | "attention" => Some(Gravity::Smart),
+            _ => None,
+        }
+    }
+
+    /// 解析可选的对齐位置，未指定时居中
+    pub fn parse_or_center(gravity_str: Option<&str>) -> Result<Self, String> {
+        match gravity_str {
+            Some(s) => Self::from_string(s).ok_or_else(|| format!("Unsupported gravity: {}", s)),
+            None => Ok(Gravity::Center),
+        }
+    }
+
+    /// 在 free 像素的余量中计算偏移（水平、垂直）
+    pub fn offset(&self, free_x: u32, free_y: u32) -> (u32, u32) {
+        let x = match self {
+            Gravity::West | Gravity::NorthWest | Gravity::SouthWest => 0,
+            Gravity::East | Gravity::NorthEast | Gravity::SouthEast => free_x,
+            _ => free_x / 2,
+        };
+        let y = match self {
+            Gravity::North | Gravity::NorthWest | Gravity::NorthEast => 0,
+            Gravity::South | Gravity::SouthWest | Gravity::SouthEast => free_y,
+            _ => free_y / 2,
+        };
+        (x, y)
+    }
+}
+
+/// 默认背景模糊半径（输出像素）
+const DEFAULT_BACKGROUND_BLUR: f32 = 20.0;
+/// 模糊背景先在缩小到 1/8 的图上模糊再放大，避免在大图上做高斯模糊
+const BLUR_DOWNSCALE: u32 = 8;
+
+/// contain 填充区域的背景
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Background {
+    Transparent,
+    /// 纯色 (RGBA)
+    Color([u8; 4]),
+    /// 铺满输出并模糊的原图，参数为模糊半径
+    Blur(f32),
+}
+
+impl Background {
+    /// 从字符串解析背景：transparent、blur、white / black 或 #rgb / #rrggbb / #rrggbbaa
+    pub fn from_string(background_str: &str) -> Option<Self> {
+        let normalized = background_str.trim().to_lowercase();
+        match normalized.as_str() {
+            "transparent" | "none" => return Some(Background::Transparent),
+            "blur" => return Some(Background::Blur(DEFAULT_BACKGROUND_BLUR)),
+            "white" => return Some(Background::Color([255, 255, 255, 255])),
+            "black" => return Some(Background::Color([0, 0, 0, 255])),
+            _ => {}
+        }
+
+        let hex = normalized.strip_prefix('#')?;
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        let digits: Vec<u8> = match hex.len() {
+            // #rgb 每位重复一次
+            3 => hex.chars().map(|c| c.to_digit(16).unwrap() as u8 * 17).collect(),
+            6 | 8 => (0..hex.len())
+                .step_by(2)
+                .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+                .collect(),
+            _ => return None,
+        };
+        let alpha = digits.get(3).copied().unwrap_or(255);
+        Some(Background::Color([digits[0], digits[1], digits[2], alpha]))
+    }
+
+    /// 解析可选的背景，未指定时透明
+    pub fn parse_or_transparent(background_str: Option<&str>) -> Result<Self, String> {
+        match background_str {
+            Some(s) => Self::from_string(s).ok_or_else(|| format!("Unsupported background: {}", s)),
+            None => Ok(Background::Transparent),
+        }
+    }
+}
+
+/// 尺寸调整计划
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResizePlan {
+    /// 缩放后的尺寸
+    pub scaled_width: u32,
+    pub scaled_height: u32,
+    /// 缩放后从 (x, y) 裁剪出输出尺寸（cover）
+    pub crop: Option<(u32, u32)>,
+    /// 缩放后的图片放在输出画布的 (x, y)（contain）
+    pub pad: Option<(u32, u32)>,
+    /// 最终输出尺寸
+    pub width: u32,
+    pub height: u32,
+    /// 缩放使用的滤波器（已按缩放比例解析 auto）
+    pub filter: ResampleFilter,
+    /// 是否在线性光中缩放
+    pub linear_light: bool,
+    /// 填充区域的背景（contain）
+    pub background: Background,
+}
+
+/// 根据适配模式计算尺寸调整计划
+pub fn plan_resize(original_width: u32, original_height: u32, resize: &ResizeOptions) -> Result<ResizePlan, String> {
+    // 指定了裁剪区域时按裁剪后的尺寸规划
+    let (original_width, original_height) = match &resize.crop {
+        Some(rect) => {
+            validate_crop(rect, original_width, original_height)?;
+            (rect.width, rect.height)
+        }
+        None => (original_width, original_height),
+    };
+    let filter = ResampleFilter::parse_or_auto(resize.filter.as_deref())?;
+    let mut plan = plan_box(original_width, original_height, resize)?;
+    if let Some(budget) = pixel_budget(resize) {
+        if plan.width as u64 * plan.height as u64 > budget {
+            plan = plan_box(original_width, original_height, &shrink_to_budget(&plan, resize, budget)?)?;
+        }
+    }
+    plan.filter = filter.resolve((original_width, original_height), (plan.scaled_width, plan.scaled_height));
+    plan.linear_light = resize.linear_light.unwrap_or(false);
+    plan.background = match Background::parse_or_transparent(resize.background.as_deref())? {
+        Background::Blur(radius) => Background::Blur(resize.background_blur.unwrap_or(radius)),
+        background => background,
+    };
+    Ok(plan)
+}
+
+/// 计算缩放、裁剪和填充的尺寸
+fn plan_box(original_width: u32, original_height: u32, resize: &ResizeOptions) -> Result<ResizePlan, String> {
+    let fit = ResizeFit::from_options(resize)?;
+    let gravity = Gravity::parse_or_center(resize.gravity.as_deref())?;
+    let without_enlargement = resize.without_enlargement.unwrap_or(false);
+
+    if let Some(percent) = resize.scale {
+        let percent = if without_enlargement { percent.min(100.0) } else { percent };
+        let scale = percent as f64 / 100.0;
+        return Ok(ResizePlan::scaled(
+            (original_width as f64 * scale).round() as u32,
+            (original_height as f64 * scale).round() as u32,
+        ));
+    }
+
+    let (target_width, target_height) = match (resize.width, resize.height, fit) {
+        (Some(width), Some(height), ResizeFit::Outside | ResizeFit::Cover | ResizeFit::Contain) => (width, height),
+        // 只指定一边或 fill / inside 时沿用原有的尺寸计算
+        _ => {
+            let (width, height) = calculate_free_dimensions(original_width, original_height, resize);
+            let (width, height) = match without_enlargement {
+                // fill 两边各自不超过原图，其余模式超出时保持原图尺寸
+                true if fit == ResizeFit::Fill => (width.min(original_width), height.min(original_height)),
+                true if width > original_width || height > original_height => (original_width, original_height),
+                _ => (width, height),
+            };
+            return Ok(ResizePlan::scaled(width, height));
+        }
+    };
+
+    match fit {
+        ResizeFit::Outside | ResizeFit::Cover => {
+            let scale = (target_width as f64 / original_width as f64)
+                .max(target_height as f64 / original_height as f64);
+            let (scaled_width, scaled_height) = if without_enlargement && scale > 1.0 {
+                (original_width, original_height)
+            } else {
+                (
+                    ((original_width as f64 * scale).round() as u32).max(target_width),
+                    ((original_height as f64 * scale).round() as u32).max(target_height),
+                )
+            };
+            if fit == ResizeFit::Outside {
+                return Ok(ResizePlan::scaled(scaled_width, scaled_height));
+            }
+            // 禁止放大时原图可能小于框，只裁掉超出的一边
+            let (width, height) = (target_width.min(scaled_width), target_height.min(scaled_height));
+            Ok(ResizePlan {
+                scaled_width,
+                scaled_height,
+                crop: Some(gravity.offset(scaled_width - width, scaled_height - height)),
+                pad: None,
+                width,
+                height,
+                filter: ResampleFilter::Auto,
+                linear_light: false,
+                background: Background::Transparent,
+            })
+        }
+        _ => {
+            let (scaled_width, scaled_height) =
+                calculate_aspect_ratio_resize(original_width, original_height, target_width, target_height);
+            let (scaled_width, scaled_height) = if without_enlargement && scaled_width > original_width {
+                (original_width, original_height)
+            } else {
+                (scaled_width, scaled_height)
+            };
+            let (scaled_width, scaled_height) = (scaled_width.clamp(1, target_width), scaled_height.clamp(1, target_height));
+            Ok(ResizePlan {
+                scaled_width,
+                scaled_height,
+                crop: None,
+                pad: Some(gravity.offset(target_width - scaled_width, target_height - scaled_height)),
+                width: target_width,
+                height: target_height,
+                filter: ResampleFilter::Auto,
+                linear_light: false,
+                background: Background::Transparent,
+            })
+        }
+    }
+}
+
+/// 像素数上限
+fn pixel_budget(resize: &ResizeOptions) -> Option<u64> {
+    resize
+        .max_megapixels
+        .map(|megapixels| ((megapixels as f64 * 1_000_000.0) as u64).max(1))
+}
+
+/// 把超出像素数上限的输出尺寸等比缩小，返回按新尺寸重新规划的选项
+///
+/// cover / contain / fill 的输出就是指定的框；inside / outside 的输出与缩小后的框宽高比相同，
+/// 统一按 inside 放入框内，避免 outside 取整后超出上限
+fn shrink_to_budget(plan: &ResizePlan, resize: &ResizeOptions, budget: u64) -> Result<ResizeOptions, String> {
+    let factor = (budget as f64 / (plan.width as f64 * plan.height as f64)).sqrt();
+    let fit = match ResizeFit::from_options(resize)? {
+        ResizeFit::Outside => ResizeFit::Inside,
+        fit => fit,
+    };
+    Ok(ResizeOptions {
+        width: Some(((plan.width as f64 * factor) as u32).max(1)),
+        height: Some(((plan.height as f64 * factor) as u32).max(1)),
+        fit: Some(fit.as_str().to_string()),
+        scale: None,
+        max_megapixels: None,
+        ..resize.clone()
+    })
+}
+
+impl ResizePlan {
+    /// cover 裁剪区域换算到原图坐标
+    pub fn crop_rect(&self, original_width: u32, original_height: u32) -> CropRect {
+        let (x, y) = self.crop.unwrap_or((0, 0));
+        let scale = (original_width as f64 / self.width as f64).min(original_height as f64 / self.height as f64);
+        let width = ((self.width as f64 * scale).round() as u32).clamp(1, original_width);
+        let height = ((self.height as f64 * scale).round() as u32).clamp(1, original_height);
+        // 偏移按余量的比例换算，保证 gravity 为边缘时正好贴边
+        let offset = |offset: u32, free: u32, original_free: u32| {
+            if free == 0 {
+                0
+            } else {
+                (offset as f64 / free as f64 * original_free as f64).round() as u32
+            }
+        };
+        CropRect {
+            x: offset(x, self.scaled_width - self.width, original_width - width),
+            y: offset(y, self.scaled_height - self.height, original_height - height),
+            width,
+            height,
+        }
+    }
+
+    /// 只缩放、不裁剪也不填充的计划
+    fn scaled(width: u32, height: u32) -> Self {
+        let (width, height) = (width.max(1), height.max(1));
+        Self {
+            scaled_width: width,
+            scaled_height: height,
+            crop: None,
+            pad: None,
+            width,
+            height,
+            filter: ResampleFilter::Auto,
+            linear_light: false,
+            background: Background::Transparent,
+        }
+    }
+}
+
+/// 按选项调整图片尺寸，输出尺寸与 `plan_resize` 的结果一致
+pub fn apply_resize(img: &DynamicImage, resize: &ResizeOptions) -> Result<DynamicImage, String> {
+    apply_resize_with_crop(img, resize).map(|(resized, _)| resized)
+}
+
+/// 按选项调整图片尺寸，cover 时同时返回原图中被保留的区域
+pub fn apply_resize_with_crop(
+    img: &DynamicImage,
+    resize: &ResizeOptions,
+) -> Result<(DynamicImage, Option<CropRect>), String> {
+    let (original_width, original_height) = img.dimensions();
+
+    // 先裁出指定区域，其余选项作用于裁剪结果，返回的区域换算回原图坐标
+    if let Some(rect) = resize.crop {
+        validate_crop(&rect, original_width, original_height)?;
+        let cropped = img.crop_imm(rect.x, rect.y, rect.width, rect.height);
+        let (resized, inner) = apply_resize_with_crop(&cropped, &ResizeOptions { crop: None, ..resize.clone() })?;
+        let rect = match inner {
+            Some(inner) => CropRect { x: rect.x + inner.x, y: rect.y + inner.y, ..inner },
+            None => rect,
+        };
+        return Ok((resized, Some(rect)));
+    }
+
+    let plan = plan_resize(original_width, original_height, resize)?;
+    if plan.crop.is_none() {
+        return Ok((execute_plan(img, &plan), None));
+    }
+
+    if Gravity::parse_or_center(resize.gravity.as_deref())? != Gravity::Smart {
+        return Ok((execute_plan(img, &plan), Some(plan.crop_rect(original_width, original_height))));
+    }
+
+    // 智能裁剪：先在原图中裁出选定区域，再缩放到输出尺寸
+    let rect = find_smart_crop(img, plan.width, plan.height);
+    let cropped = img.crop_imm(rect.x, rect.y, rect.width, rect.height);
+    let filter = ResampleFilter::parse_or_auto(resize.filter.as_deref())?
+        .resolve((rect.width, rect.height), (plan.width, plan.height));
+    let resized = resample(&cropped, plan.width, plan.height, filter, plan.linear_light);
+    Ok((resized, Some(rect)))
+}
+
+/// 检查裁剪区域非空且在原图范围内
+fn validate_crop(rect: &CropRect, width: u32, height: u32) -> Result<(), String> {
+    let inside = rect.x.checked_add(rect.width).is_some_and(|right| right <= width)
+        && rect.y.checked_add(rect.height).is_some_and(|bottom| bottom <= height);
+    if rect.width == 0 || rect.height == 0 || !inside {
+        return Err(format!(
+            "Crop {}x{} at ({}, {}) is outside the {}x{} image",
+            rect.width, rect.height, rect.x, rect.y, width, height
+        ));
+    }
+    Ok(())
+}
+
+/// 执行尺寸调整计划
+pub fn execute_plan(img: &DynamicImage, plan: &ResizePlan) -> DynamicImage {
+    let scaled = resample(img, plan.scaled_width, plan.scaled_height, plan.filter, plan.linear_light);
+
+    if let Some((x, y)) = plan.crop {
+        return scaled.crop_imm(x, y, plan.width, plan.height);
+    }
+    if let Some((x, y)) = plan.pad {
+        // 透明背景在输出不支持透明度的格式时为黑色
+        let mut canvas = match plan.background {
+            Background::Transparent => RgbaImage::from_pixel(plan.width, plan.height, Rgba([0, 0, 0, 0])),
+            Background::Color(color) => RgbaImage::from_pixel(plan.width, plan.height, Rgba(color)),
+            Background::Blur(radius) => blurred_background(img, plan.width, plan.height, radius, plan.linear_light),
+        };
+        image::imageops::overlay(&mut canvas, &scaled.to_rgba8(), x as i64, y as i64);
+        return DynamicImage::ImageRgba8(canvas);
+    }
+    scaled
+}
+
+/// 生成模糊背景：原图居中铺满输出尺寸后模糊
+fn blurred_background(img: &DynamicImage, width: u32, height: u32, radius: f32, linear_light: bool) -> RgbaImage {
+    let (original_width, original_height) = img.dimensions();
+    let (small_width, small_height) = ((width / BLUR_DOWNSCALE).max(1), (height / BLUR_DOWNSCALE).max(1));
+    let scale = (small_width as f64 / original_width as f64).max(small_height as f64 / original_height as f64);
+    let scaled_width = ((original_width as f64 * scale).round() as u32).max(small_width);
+    let scaled_height = ((original_height as f64 * scale).round() as u32).max(small_height);
+
+    let small = resample(img, scaled_width, scaled_height, ResampleFilter::Bilinear, linear_light).crop_imm(
+        (scaled_width - small_width) / 2,
+        (scaled_height - small_height) / 2,
+        small_width,
+        small_height,
+    );
+    let small = if radius > 0.0 { small.blur(radius / BLUR_DOWNSCALE as f32) } else { small };
+    resample(&small, width, height, ResampleFilter::Bilinear, linear_light).to_rgba8()
+}
+
 /// 计算调整后的尺寸
+///
+/// 返回最终输出尺寸：指定了 fit 时为该模式的输出（cover / contain 为指定的框），
+/// 并应用 scale、without_enlargement 和 max_megapixels
 pub fn calculate_resize_dimensions(
     original_width: u32,
     original_height: u32,
     resize: &ResizeOptions,
 ) -> (u32, u32) {
-    let maintain_aspect_ratio = resize.maintain_aspect_ratio.unwrap_or(true);
+    match plan_resize(original_width, original_height, resize) {
+        Ok(plan) => (plan.width, plan.height),
+        Err(_) => calculate_free_dimensions(original_width, original_height, resize),
+    }
+}
+
+/// 按 width / height 和宽高比计算尺寸，不考虑裁剪、填充和其它限制
+fn calculate_free_dimensions(
+    original_width: u32,
+    original_height: u32,
+    resize: &ResizeOptions,
+) -> (u32, u32) {
+    let fit = resize.fit.as_deref().and_then(ResizeFit::from_string);
+    let maintain_aspect_ratio = match fit {
+        Some(ResizeFit::Fill) => false,
+        Some(_) => true,
+        None => resize.maintain_aspect_ratio.unwrap_or(true),
+    };
 
     match (resize.width, resize.height) {
         (Some(target_width), Some(target_height)) => {
@@ -108,6 +591,36 @@ pub fn validate_resize_options(resize: &ResizeOptions) -> Result<(), String> {
         }
     }
 
+    if let Some(scale) = resize.scale {
+        if resize.width.is_some() || resize.height.is_some() {
+            return Err("Scale cannot be combined with width or height".to_string());
+        }
+        if !(scale > 0.0 && scale <= 1000.0) {
+            return Err("Scale must be between 0 and 1000 percent".to_string());
+        }
+    }
+    if let Some(megapixels) = resize.max_megapixels {
+        if !(megapixels.is_finite() && megapixels > 0.0) {
+            return Err("Max megapixels must be greater than 0".to_string());
+        }
+    }
+
+    if let Some(radius) = resize.background_blur {
+        if !(radius.is_finite() && radius >= 0.0) {
+            return Err("Background blur must not be negative".to_string());
+        }
+    }
+    if let Some(rect) = &resize.crop {
+        if rect.width == 0 || rect.height == 0 {
+            return Err("Crop width and height must be greater than 0".to_string());
+        }
+    }
+
+    ResizeFit::from_options(resize)?;
+    Background::parse_or_transparent(resize.background.as_deref())?;
+    Gravity::parse_or_center(resize.gravity.as_deref())?;
+    ResampleFilter::parse_or_auto(resize.filter.as_deref())?;
+
     Ok(())
 }
 
@@ -139,9 +652,28 @@ pub fn get_recommended_resize_options(
         width: Some(target_width),
         height: Some(target_height),
         maintain_aspect_ratio: Some(true),
+        ..Default::default()
     }
 }
 
+/// 按像素数上限获取推荐的尺寸调整选项，不放大
+pub fn get_recommended_resize_options_for_megapixels(
+    original_width: u32,
+    original_height: u32,
+    max_megapixels: f32,
+) -> ResizeOptions {
+    let mut resize = ResizeOptions {
+        maintain_aspect_ratio: Some(true),
+        without_enlargement: Some(true),
+        max_megapixels: Some(max_megapixels),
+        ..Default::default()
+    };
+    let (target_width, target_height) = calculate_resize_dimensions(original_width, original_height, &resize);
+    resize.width = Some(target_width);
+    resize.height = Some(target_height);
+    resize
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -152,6 +684,7 @@ mod tests {
             width: Some(200),
             height: Some(150),
             maintain_aspect_ratio: Some(true),
+            ..Default::default()
         };
 
         let (width, height) = calculate_resize_dimensions(400, 300, &resize);
@@ -163,8 +696,8 @@ mod tests {
     fn test_calculate_resize_dimensions_width_only() {
         let resize = ResizeOptions {
             width: Some(200),
-            height: None,
             maintain_aspect_ratio: Some(true),
+            ..Default::default()
         };
 
         let (width, height) = calculate_resize_dimensions(400, 300, &resize);
@@ -175,9 +708,9 @@ mod tests {
     #[test]
     fn test_calculate_resize_dimensions_height_only() {
         let resize = ResizeOptions {
-            width: None,
             height: Some(150),
             maintain_aspect_ratio: Some(true),
+            ..Default::default()
         };
 
         let (width, height) = calculate_resize_dimensions(400, 300, &resize);
@@ -191,6 +724,7 @@ mod tests {
             width: Some(200),
             height: Some(100),
             maintain_aspect_ratio: Some(false),
+            ..Default::default()
         };
 
         let (width, height) = calculate_resize_dimensions(400, 300, &resize);
@@ -204,6 +738,7 @@ mod tests {
             width: Some(200),
             height: Some(150),
             maintain_aspect_ratio: Some(true),
+            ..Default::default()
         };
 
         assert!(validate_resize_options(&resize).is_ok());
@@ -215,6 +750,7 @@ mod tests {
             width: Some(0),
             height: Some(150),
             maintain_aspect_ratio: Some(true),
+            ..Default::default()
         };
 
         assert!(validate_resize_options(&resize).is_err());
@@ -227,4 +763,312 @@ mod tests {
         assert!(options.height.is_some());
         assert_eq!(options.maintain_aspect_ratio, Some(true));
     }
+
+    fn fit_options(width: u32, height: u32, fit: &str, gravity: Option<&str>) -> ResizeOptions {
+        ResizeOptions {
+            width: Some(width),
+            height: Some(height),
+            fit: Some(fit.to_string()),
+            gravity: gravity.map(|g| g.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_plan_resize_fit_modes() {
+        // 400x300 放入 100x100 的框
+        let cover = plan_resize(400, 300, &fit_options(100, 100, "cover", None)).unwrap();
+        assert_eq!((cover.scaled_width, cover.scaled_height), (133, 100));
+        assert_eq!(cover.crop, Some((16, 0)));
+        assert_eq!((cover.width, cover.height), (100, 100));
+
+        let contain = plan_resize(400, 300, &fit_options(100, 100, "contain", Some("south"))).unwrap();
+        assert_eq!((contain.scaled_width, contain.scaled_height), (100, 75));
+        assert_eq!(contain.pad, Some((0, 25)));
+        assert_eq!((contain.width, contain.height), (100, 100));
+
+        let outside = plan_resize(400, 300, &fit_options(100, 100, "outside", None)).unwrap();
+        assert_eq!((outside.width, outside.height), (133, 100));
+        assert_eq!(outside.crop, None);
+
+        let inside = plan_resize(400, 300, &fit_options(100, 100, "inside", None)).unwrap();
+        assert_eq!((inside.width, inside.height), (100, 75));
+
+        let fill = plan_resize(400, 300, &fit_options(100, 100, "fill", None)).unwrap();
+        assert_eq!((fill.width, fill.height), (100, 100));
+        assert_eq!((fill.scaled_width, fill.scaled_height), (100, 100));
+
+        assert_eq!(calculate_resize_dimensions(400, 300, &fit_options(100, 100, "cover", None)), (100, 100));
+        assert_eq!(calculate_resize_dimensions(400, 300, &fit_options(100, 100, "outside", None)), (133, 100));
+    }
+
+    #[test]
+    fn test_gravity() {
+        assert_eq!(Gravity::from_string("top-right"), Some(Gravity::NorthEast));
+        assert_eq!(Gravity::from_string("SouthWest"), Some(Gravity::SouthWest));
+        assert_eq!(Gravity::from_string("middle"), None);
+        assert_eq!(Gravity::from_string("attention"), Some(Gravity::Smart));
+        assert_eq!(Gravity::Smart.offset(10, 20), (5, 10));
+        assert_eq!(Gravity::NorthEast.offset(10, 20), (10, 0));
+        assert_eq!(Gravity::Center.offset(10, 21), (5, 10));
+        assert_eq!(Gravity::SouthWest.offset(10, 20), (0, 20));
+
+        let cover = plan_resize(300, 400, &fit_options(100, 100, "cover", Some("north"))).unwrap();
+        assert_eq!(cover.crop, Some((0, 0)));
+        let cover = plan_resize(300, 400, &fit_options(100, 100, "cover", Some("south"))).unwrap();
+        assert_eq!(cover.crop, Some((0, 33)));
+
+        assert!(validate_resize_options(&fit_options(100, 100, "stretch", None)).is_err());
+        assert!(validate_resize_options(&fit_options(100, 100, "cover", Some("middle"))).is_err());
+    }
+
+    #[test]
+    fn test_apply_resize_exact_box() {
+        use image::{ImageBuffer, Rgb};
+
+        // 左半红、右半蓝
+        let img = DynamicImage::ImageRgb8(ImageBuffer::from_fn(400, 200, |x, _| {
+            if x < 200 { Rgb([255, 0, 0]) } else { Rgb([0, 0, 255]) }
+        }));
+
+        let west = apply_resize(&img, &fit_options(50, 50, "cover", Some("west"))).unwrap();
+        assert_eq!(west.dimensions(), (50, 50));
+        assert_eq!(west.to_rgb8().get_pixel(25, 25).0, [255, 0, 0]);
+        let east = apply_resize(&img, &fit_options(50, 50, "cover", Some("east"))).unwrap();
+        assert_eq!(east.to_rgb8().get_pixel(25, 25).0, [0, 0, 255]);
+
+        let contain = apply_resize(&img, &fit_options(100, 100, "contain", Some("north"))).unwrap();
+        assert_eq!(contain.dimensions(), (100, 100));
+        let contain = contain.to_rgba8();
+        assert_eq!(contain.get_pixel(10, 10).0[3], 255);
+        assert_eq!(contain.get_pixel(10, 90).0[3], 0);
+    }
+
+    #[test]
+    fn test_apply_resize_crop_rect() {
+        use image::{ImageBuffer, Rgb};
+
+        let img = DynamicImage::ImageRgb8(ImageBuffer::from_pixel(400, 300, Rgb([128, 128, 128])));
+        let (resized, rect) = apply_resize_with_crop(&img, &fit_options(100, 100, "cover", Some("east"))).unwrap();
+        assert_eq!(resized.dimensions(), (100, 100));
+        assert_eq!(rect, Some(CropRect { x: 100, y: 0, width: 300, height: 300 }));
+
+        let (_, rect) = apply_resize_with_crop(&img, &fit_options(100, 100, "contain", None)).unwrap();
+        assert_eq!(rect, None);
+
+        // 右下角的彩色主体
+        let img = DynamicImage::ImageRgb8(ImageBuffer::from_fn(400, 300, |x, y| {
+            if x > 300 && y > 200 && (x / 4 + y / 4) % 2 == 0 { Rgb([230, 30, 30]) } else { Rgb([128, 128, 128]) }
+        }));
+        let (resized, rect) = apply_resize_with_crop(&img, &fit_options(60, 120, "cover", Some("smart"))).unwrap();
+        assert_eq!(resized.dimensions(), (60, 120));
+        let rect = rect.unwrap();
+        assert!(rect.x + rect.width > 380);
+    }
+
+    #[test]
+    fn test_apply_resize_explicit_crop() {
+        use image::{ImageBuffer, Rgb};
+
+        // 只有指定区域是红色
+        let img = DynamicImage::ImageRgb8(ImageBuffer::from_fn(400, 300, |x, y| {
+            if (100..300).contains(&x) && (50..150).contains(&y) { Rgb([230, 30, 30]) } else { Rgb([128, 128, 128]) }
+        }));
+        let crop = CropRect { x: 100, y: 50, width: 200, height: 100 };
+        let resize = ResizeOptions {
+            width: Some(100),
+            crop: Some(crop),
+            ..Default::default()
+        };
+        assert_eq!(calculate_resize_dimensions(400, 300, &resize), (100, 50));
+        let (resized, rect) = apply_resize_with_crop(&img, &resize).unwrap();
+        assert_eq!(resized.dimensions(), (100, 50));
+        assert_eq!(rect, Some(crop));
+        assert!(resized.to_rgb8().pixels().all(|p| p.0 == [230, 30, 30]));
+
+        // cover 在裁剪结果内再取居中区域，返回原图坐标
+        let cover = ResizeOptions { crop: Some(crop), ..fit_options(50, 50, "cover", None) };
+        let (resized, rect) = apply_resize_with_crop(&img, &cover).unwrap();
+        assert_eq!(resized.dimensions(), (50, 50));
+        assert_eq!(rect, Some(CropRect { x: 150, y: 50, width: 100, height: 100 }));
+
+        let outside = ResizeOptions {
+            crop: Some(CropRect { x: 300, y: 0, width: 200, height: 100 }),
+            ..Default::default()
+        };
+        assert!(apply_resize_with_crop(&img, &outside).is_err());
+        assert!(plan_resize(400, 300, &outside).is_err());
+        let empty = ResizeOptions {
+            crop: Some(CropRect { x: 0, y: 0, width: 0, height: 10 }),
+            ..Default::default()
+        };
+        assert!(validate_resize_options(&empty).is_err());
+    }
+
+    #[test]
+    fn test_plan_resize_filter() {
+        let mut resize = fit_options(100, 100, "cover", None);
+        assert_eq!(plan_resize(4000, 3000, &resize).unwrap().filter, ResampleFilter::Bilinear);
+        assert_eq!(plan_resize(150, 120, &resize).unwrap().filter, ResampleFilter::Lanczos3);
+        assert_eq!(plan_resize(50, 50, &resize).unwrap().filter, ResampleFilter::CatmullRom);
+        assert!(!plan_resize(50, 50, &resize).unwrap().linear_light);
+        resize.linear_light = Some(true);
+        assert!(plan_resize(50, 50, &resize).unwrap().linear_light);
+
+        resize.filter = Some("mitchell".to_string());
+        assert_eq!(plan_resize(4000, 3000, &resize).unwrap().filter, ResampleFilter::Mitchell);
+        resize.filter = Some("box".to_string());
+        assert!(plan_resize(4000, 3000, &resize).is_err());
+        assert!(validate_resize_options(&resize).is_err());
+    }
+
+    #[test]
+    fn test_without_enlargement() {
+        let mut resize = fit_options(1000, 1000, "inside", None);
+        assert_eq!(calculate_resize_dimensions(200, 200, &resize), (1000, 1000));
+        resize.without_enlargement = Some(true);
+        assert_eq!(calculate_resize_dimensions(200, 200, &resize), (200, 200));
+        // 缩小不受影响
+        assert_eq!(calculate_resize_dimensions(2000, 1000, &resize), (1000, 500));
+
+        resize.height = None;
+        assert_eq!(calculate_resize_dimensions(200, 200, &resize), (200, 200));
+
+        let mut cover = fit_options(300, 100, "cover", None);
+        cover.without_enlargement = Some(true);
+        let plan = plan_resize(200, 200, &cover).unwrap();
+        assert_eq!((plan.scaled_width, plan.scaled_height), (200, 200));
+        assert_eq!((plan.width, plan.height), (200, 100));
+        assert_eq!(plan.crop, Some((0, 50)));
+
+        let mut contain = fit_options(1000, 1000, "contain", None);
+        contain.without_enlargement = Some(true);
+        let plan = plan_resize(200, 100, &contain).unwrap();
+        assert_eq!((plan.scaled_width, plan.scaled_height), (200, 100));
+        assert_eq!((plan.width, plan.height), (1000, 1000));
+        assert_eq!(plan.pad, Some((400, 450)));
+
+        let mut fill = fit_options(1000, 100, "fill", None);
+        fill.without_enlargement = Some(true);
+        assert_eq!(calculate_resize_dimensions(200, 200, &fill), (200, 100));
+    }
+
+    #[test]
+    fn test_scale_percentage() {
+        let mut resize = ResizeOptions {
+            scale: Some(50.0),
+            ..Default::default()
+        };
+        assert!(validate_resize_options(&resize).is_ok());
+        assert_eq!(calculate_resize_dimensions(400, 300, &resize), (200, 150));
+        assert_eq!(calculate_resize_dimensions(401, 301, &resize), (201, 151));
+
+        resize.scale = Some(150.0);
+        assert_eq!(calculate_resize_dimensions(400, 300, &resize), (600, 450));
+        resize.without_enlargement = Some(true);
+        assert_eq!(calculate_resize_dimensions(400, 300, &resize), (400, 300));
+
+        resize.scale = Some(0.0);
+        assert!(validate_resize_options(&resize).is_err());
+        resize.scale = Some(50.0);
+        resize.width = Some(100);
+        assert!(validate_resize_options(&resize).is_err());
+    }
+
+    #[test]
+    fn test_max_megapixels() {
+        let mut resize = ResizeOptions {
+            max_megapixels: Some(2.0),
+            ..Default::default()
+        };
+        let (width, height) = calculate_resize_dimensions(4000, 3000, &resize);
+        assert!(width * height <= 2_000_000);
+        assert!(width * height > 1_990_000);
+        assert!((width as f64 / height as f64 - 4.0 / 3.0).abs() < 0.01);
+        // 未超出时不变
+        assert_eq!(calculate_resize_dimensions(1200, 900, &resize), (1200, 900));
+
+        // cover / contain 的框整体等比缩小
+        let mut cover = fit_options(1200, 630, "cover", None);
+        cover.max_megapixels = Some(0.5);
+        let plan = plan_resize(4000, 3000, &cover).unwrap();
+        assert_eq!((plan.width, plan.height), (975, 512));
+        assert!(plan.crop.is_some());
+
+        let mut outside = fit_options(1000, 1000, "outside", None);
+        outside.max_megapixels = Some(1.0);
+        let (width, height) = calculate_resize_dimensions(4000, 3000, &outside);
+        assert!(width * height <= 1_000_000);
+
+        resize.max_megapixels = Some(0.0);
+        assert!(validate_resize_options(&resize).is_err());
+    }
+
+    #[test]
+    fn test_get_recommended_resize_options_for_megapixels() {
+        let options = get_recommended_resize_options_for_megapixels(6000, 4000, 12.0);
+        let (width, height) = (options.width.unwrap(), options.height.unwrap());
+        assert!(width * height <= 12_000_000);
+        assert_eq!((width, height), (4242, 2828));
+        assert_eq!(options.without_enlargement, Some(true));
+        assert_eq!(calculate_resize_dimensions(6000, 4000, &options), (4242, 2828));
+
+        let options = get_recommended_resize_options_for_megapixels(200, 200, 12.0);
+        assert_eq!((options.width, options.height), (Some(200), Some(200)));
+    }
+
+    #[test]
+    fn test_background() {
+        assert_eq!(Background::from_string("Transparent"), Some(Background::Transparent));
+        assert_eq!(Background::from_string("blur"), Some(Background::Blur(DEFAULT_BACKGROUND_BLUR)));
+        assert_eq!(Background::from_string("#fff"), Some(Background::Color([255, 255, 255, 255])));
+        assert_eq!(Background::from_string("#1E90FF"), Some(Background::Color([30, 144, 255, 255])));
+        assert_eq!(Background::from_string("#00000080"), Some(Background::Color([0, 0, 0, 128])));
+        assert_eq!(Background::from_string("#12345"), None);
+        assert_eq!(Background::from_string("#ggg"), None);
+        assert_eq!(Background::from_string("pink"), None);
+        assert_eq!(ResizeFit::from_string("pad"), Some(ResizeFit::Contain));
+
+        let mut resize = fit_options(1200, 630, "pad", None);
+        resize.background = Some("blur".to_string());
+        resize.background_blur = Some(40.0);
+        assert_eq!(plan_resize(800, 800, &resize).unwrap().background, Background::Blur(40.0));
+        resize.background_blur = Some(-1.0);
+        assert!(validate_resize_options(&resize).is_err());
+        resize.background_blur = None;
+        resize.background = Some("rainbow".to_string());
+        assert!(validate_resize_options(&resize).is_err());
+        assert!(plan_resize(800, 800, &resize).is_err());
+    }
+
+    #[test]
+    fn test_pad_background() {
+        use image::{ImageBuffer, Rgb};
+
+        // 左半红、右半蓝
+        let img = DynamicImage::ImageRgb8(ImageBuffer::from_fn(400, 200, |x, _| {
+            if x < 200 { Rgb([255, 0, 0]) } else { Rgb([0, 0, 255]) }
+        }));
+
+        let mut resize = fit_options(200, 200, "pad", Some("south"));
+        resize.background = Some("#00ff00".to_string());
+        let padded = apply_resize(&img, &resize).unwrap().to_rgba8();
+        assert_eq!(padded.dimensions(), (200, 200));
+        assert_eq!(padded.get_pixel(10, 10).0, [0, 255, 0, 255]);
+        assert_eq!(padded.get_pixel(10, 190).0, [255, 0, 0, 255]);
+
+        // 模糊背景铺满整个画布，颜色来自原图对应的一侧
+        resize.gravity = None;
+        resize.background = Some("blur".to_string());
+        let padded = apply_resize(&img, &resize).unwrap().to_rgba8();
+        assert_eq!(padded.dimensions(), (200, 200));
+        let top_left = padded.get_pixel(5, 5).0;
+        let top_right = padded.get_pixel(194, 5).0;
+        assert_eq!(top_left[3], 255);
+        assert!(top_left[0] > 150 && top_left[2] < 100);
+        assert!(top_right[2] > 150 && top_right[0] < 100);
+        // 中间是清晰的原图
+        assert_eq!(padded.get_pixel(10, 100).0, [255, 0, 0, 255]);
+        assert_eq!(padded.get_pixel(190, 100).0, [0, 0, 255, 255]);
+    }
 }
@@ -0,0 +1,299 @@
+//! 智能裁剪
+//!
+//! 参考 smartcrop.js：在缩小的分析图上计算边缘、肤色和饱和度特征，
+//! 按候选窗口内特征的加权和（中心和三分线附近权重更高，窗口外为负）打分，
+//! 选出最可能包含主体的裁剪区域
+
+use wasm_bindgen::prelude::*;
+use image::imageops::FilterType;
+use image::{DynamicImage, GenericImageView, RgbImage};
+use serde::{Deserialize, Serialize};
+
+use crate::image::decode_image;
+
+/// 分析图的最长边
+const ANALYSIS_SIZE: u32 = 256;
+/// 候选窗口的移动步长（分析图像素）
+const STEP: u32 = 8;
+/// 打分时的采样间隔（分析图像素）
+const SCORE_DOWNSAMPLE: u32 = 4;
+/// 候选窗口相对最大窗口的缩放比例
+const SCALES: [f64; 3] = [1.0, 0.9, 0.8];
+
+const SKIN_COLOR: [f64; 3] = [0.78, 0.57, 0.44];
+const SKIN_THRESHOLD: f64 = 0.8;
+const SKIN_BRIGHTNESS: (f64, f64) = (0.2, 1.0);
+const SKIN_BIAS: f64 = 0.01;
+const SKIN_WEIGHT: f64 = 1.8;
+const SATURATION_THRESHOLD: f64 = 0.4;
+const SATURATION_BRIGHTNESS: (f64, f64) = (0.05, 0.9);
+const SATURATION_BIAS: f64 = 0.2;
+const SATURATION_WEIGHT: f64 = 0.1;
+const DETAIL_WEIGHT: f64 = 0.2;
+const EDGE_RADIUS: f64 = 0.4;
+const EDGE_WEIGHT: f64 = -20.0;
+const OUTSIDE_IMPORTANCE: f64 = -0.5;
+
+/// 裁剪区域（原图坐标）
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CropRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// 计算指定宽高比的智能裁剪区域（WASM 导出）
+#[wasm_bindgen]
+pub fn smart_crop(image_data: &[u8], width: u32, height: u32) -> Result<CropRect, JsValue> {
+    if width == 0 || height == 0 {
+        return Err(JsValue::from_str("Width and height must be greater than 0"));
+    }
+    let img = decode_image(image_data).map_err(|e| JsValue::from_str(&e))?.image;
+    Ok(find_smart_crop(&img, width, height))
+}
+
+/// 在原图中找出宽高比为 width:height 的最佳裁剪区域
+pub fn find_smart_crop(img: &DynamicImage, width: u32, height: u32) -> CropRect {
+    let (original_width, original_height) = img.dimensions();
+    let scale = (ANALYSIS_SIZE as f64 / original_width.max(original_height) as f64).min(1.0);
+    let analysis_width = ((original_width as f64 * scale).round() as u32).max(1);
+    let analysis_height = ((original_height as f64 * scale).round() as u32).max(1);
+    let analysis = img
+        .resize_exact(analysis_width, analysis_height, FilterType::Triangle)
+        .to_rgb8();
+
+    let features = Features::analyze(&analysis);
+
+    // 分析图中能容纳的最大窗口
+    let aspect = width as f64 / height as f64;
+    let (max_width, max_height) = if analysis_width as f64 / analysis_height as f64 > aspect {
+        ((analysis_height as f64 * aspect) as u32, analysis_height)
+    } else {
+        (analysis_width, (analysis_width as f64 / aspect) as u32)
+    };
+
+    let mut best = (f64::MIN, 0, 0, max_width.max(1), max_height.max(1));
+    for crop_scale in SCALES {
+        let crop_width = ((max_width as f64 * crop_scale) as u32).max(1);
+        let crop_height = ((max_height as f64 * crop_scale) as u32).max(1);
+        for y in (0..=analysis_height - crop_height).step_by(STEP as usize) {
+            for x in (0..=analysis_width - crop_width).step_by(STEP as usize) {
+                let score = features.score(x, y, crop_width, crop_height);
+                if score > best.0 {
+                    best = (score, x, y, crop_width, crop_height);
+                }
+            }
+        }
+    }
+
+    // 映射回原图坐标，并保证不越界
+    let (_, x, y, crop_width, crop_height) = best;
+    let to_original = |v: u32| (v as f64 / scale).round() as u32;
+    let mut rect_width = to_original(crop_width).min(original_width);
+    let mut rect_height = to_original(crop_height).min(original_height);
+    // 按原图尺寸修正宽高比
+    if (rect_width as f64 / rect_height as f64) > aspect {
+        rect_width = ((rect_height as f64 * aspect).round() as u32).clamp(1, original_width);
+    } else {
+        rect_height = ((rect_width as f64 / aspect).round() as u32).clamp(1, original_height);
+    }
+    CropRect {
+        x: to_original(x).min(original_width - rect_width),
+        y: to_original(y).min(original_height - rect_height),
+        width: rect_width,
+        height: rect_height,
+    }
+}
+
+/// 分析图的特征（0-1）
+struct Features {
+    width: u32,
+    height: u32,
+    detail: Vec<f64>,
+    skin: Vec<f64>,
+    saturation: Vec<f64>,
+}
+
+impl Features {
+    fn analyze(img: &RgbImage) -> Self {
+        let (width, height) = img.dimensions();
+        let lightness: Vec<f64> = img.pixels().map(|p| cie(p.0)).collect();
+        let at = |x: u32, y: u32| lightness[(y * width + x) as usize];
+
+        let mut detail = Vec::with_capacity(lightness.len());
+        let mut skin = Vec::with_capacity(lightness.len());
+        let mut saturation = Vec::with_capacity(lightness.len());
+        for (x, y, pixel) in img.enumerate_pixels() {
+            let l = at(x, y);
+            // 边缘：亮度的拉普拉斯响应，边框像素直接使用亮度
+            let edge = if x == 0 || y == 0 || x == width - 1 || y == height - 1 {
+                l
+            } else {
+                4.0 * l - at(x - 1, y) - at(x + 1, y) - at(x, y - 1) - at(x, y + 1)
+            };
+            detail.push(edge.clamp(0.0, 255.0) / 255.0);
+
+            let brightness = l / 255.0;
+            let skin_value = skin_similarity(pixel.0);
+            skin.push(
+                if skin_value > SKIN_THRESHOLD && in_range(brightness, SKIN_BRIGHTNESS) {
+                    (skin_value - SKIN_THRESHOLD) / (1.0 - SKIN_THRESHOLD)
+                } else {
+                    0.0
+                },
+            );
+
+            let sat = hsl_saturation(pixel.0);
+            saturation.push(
+                if sat > SATURATION_THRESHOLD && in_range(brightness, SATURATION_BRIGHTNESS) {
+                    (sat - SATURATION_THRESHOLD) / (1.0 - SATURATION_THRESHOLD)
+                } else {
+                    0.0
+                },
+            );
+        }
+
+        Self {
+            width,
+            height,
+            detail,
+            skin,
+            saturation,
+        }
+    }
+
+    /// 候选窗口得分（按窗口面积归一化）
+    fn score(&self, crop_x: u32, crop_y: u32, crop_width: u32, crop_height: u32) -> f64 {
+        let (mut detail_total, mut skin_total, mut saturation_total) = (0.0, 0.0, 0.0);
+        for y in (0..self.height).step_by(SCORE_DOWNSAMPLE as usize) {
+            for x in (0..self.width).step_by(SCORE_DOWNSAMPLE as usize) {
+                let index = (y * self.width + x) as usize;
+                let importance = importance(x, y, crop_x, crop_y, crop_width, crop_height);
+                let detail = self.detail[index];
+                detail_total += detail * importance;
+                skin_total += self.skin[index] * (detail + SKIN_BIAS) * importance;
+                saturation_total += self.saturation[index] * (detail + SATURATION_BIAS) * importance;
+            }
+        }
+        (detail_total * DETAIL_WEIGHT + skin_total * SKIN_WEIGHT + saturation_total * SATURATION_WEIGHT)
+            / (crop_width as f64 * crop_height as f64)
+    }
+}
+
+/// 像素在窗口中的重要性：中心和三分线附近更高，靠近边缘迅速下降，窗口外为负
+fn importance(x: u32, y: u32, crop_x: u32, crop_y: u32, crop_width: u32, crop_height: u32) -> f64 {
+    if x < crop_x || y < crop_y || x >= crop_x + crop_width || y >= crop_y + crop_height {
+        return OUTSIDE_IMPORTANCE;
+    }
+    let px = ((x - crop_x) as f64 / crop_width as f64 - 0.5).abs() * 2.0;
+    let py = ((y - crop_y) as f64 / crop_height as f64 - 0.5).abs() * 2.0;
+    let dx = (px - 1.0 + EDGE_RADIUS).max(0.0);
+    let dy = (py - 1.0 + EDGE_RADIUS).max(0.0);
+    let d = (dx * dx + dy * dy) * EDGE_WEIGHT;
+    let mut s = 1.41 - (px * px + py * py).sqrt();
+    s += (s + d + 0.5).max(0.0) * 1.2 * (thirds(px) + thirds(py));
+    s + d
+}
+
+/// 靠近三分线时接近 1
+fn thirds(v: f64) -> f64 {
+    let v = (((v - 1.0 / 3.0 + 1.0) % 2.0) * 0.5 - 0.5) * 16.0;
+    (1.0 - v * v).max(0.0)
+}
+
+/// 感知亮度 (0-255)
+fn cie([r, g, b]: [u8; 3]) -> f64 {
+    0.5126 * b as f64 + 0.7152 * g as f64 + 0.0722 * r as f64
+}
+
+/// 与肤色方向的相似度 (越接近 1 越像肤色)
+fn skin_similarity([r, g, b]: [u8; 3]) -> f64 {
+    let magnitude = ((r as f64).powi(2) + (g as f64).powi(2) + (b as f64).powi(2)).sqrt();
+    if magnitude == 0.0 {
+        return 0.0;
+    }
+    let dr = r as f64 / magnitude - SKIN_COLOR[0];
+    let dg = g as f64 / magnitude - SKIN_COLOR[1];
+    let db = b as f64 / magnitude - SKIN_COLOR[2];
+    1.0 - (dr * dr + dg * dg + db * db).sqrt()
+}
+
+/// HSL 饱和度
+fn hsl_saturation([r, g, b]: [u8; 3]) -> f64 {
+    let max = r.max(g).max(b) as f64 / 255.0;
+    let min = r.min(g).min(b) as f64 / 255.0;
+    if max == min {
+        return 0.0;
+    }
+    let l = (max + min) / 2.0;
+    let d = max - min;
+    if l > 0.5 {
+        d / (2.0 - max - min)
+    } else {
+        d / (max + min)
+    }
+}
+
+fn in_range(value: f64, (min, max): (f64, f64)) -> bool {
+    value >= min && value <= max
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{ImageBuffer, Rgb};
+
+    /// 灰色背景上的彩色细节主体
+    fn subject_at(width: u32, height: u32, cx: u32, cy: u32) -> DynamicImage {
+        DynamicImage::ImageRgb8(ImageBuffer::from_fn(width, height, |x, y| {
+            let inside = x.abs_diff(cx) < 40 && y.abs_diff(cy) < 40;
+            if inside && (x / 4 + y / 4) % 2 == 0 {
+                Rgb([220, 40, 30])
+            } else if inside {
+                Rgb([30, 200, 60])
+            } else {
+                Rgb([128, 128, 128])
+            }
+        }))
+    }
+
+    #[test]
+    fn test_smart_crop_follows_subject() {
+        let img = subject_at(600, 300, 480, 150);
+        let rect = find_smart_crop(&img, 1, 1);
+        assert_eq!(rect.width, rect.height);
+        assert!(rect.height <= 300);
+        // 主体位于右侧，裁剪区域应包含它
+        assert!(rect.x <= 440 && rect.x + rect.width >= 520);
+
+        let img = subject_at(300, 600, 150, 90);
+        let rect = find_smart_crop(&img, 1, 1);
+        assert!(rect.y <= 50 && rect.y + rect.height >= 130);
+    }
+
+    #[test]
+    fn test_smart_crop_bounds_and_aspect() {
+        let img = subject_at(641, 479, 100, 100);
+        for (width, height) in [(16, 9), (1, 3), (1000, 10)] {
+            let rect = find_smart_crop(&img, width, height);
+            assert!(rect.x + rect.width <= 641);
+            assert!(rect.y + rect.height <= 479);
+            let expected = width as f64 / height as f64;
+            let actual = rect.width as f64 / rect.height as f64;
+            assert!((actual - expected).abs() / expected < 0.1 || rect.height <= 2 || rect.width <= 2);
+        }
+    }
+
+    #[test]
+    fn test_feature_helpers() {
+        assert!(skin_similarity([200, 146, 112]) > 0.9);
+        assert!(skin_similarity([20, 40, 200]) < 0.5);
+        assert_eq!(hsl_saturation([100, 100, 100]), 0.0);
+        assert!((hsl_saturation([255, 0, 0]) - 1.0).abs() < 1e-9);
+        assert!((thirds(1.0 / 3.0) - 1.0).abs() < 1e-9);
+        assert_eq!(importance(0, 0, 10, 10, 20, 20), OUTSIDE_IMPORTANCE);
+        assert!(importance(20, 20, 10, 10, 20, 20) > importance(11, 11, 10, 10, 20, 20));
+    }
+}
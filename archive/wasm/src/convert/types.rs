@@ -5,8 +5,12 @@
 use wasm_bindgen::prelude::*;
 use serde::{Deserialize, Serialize};
 
+use crate::convert::naming::FileNameOptions;
+use crate::convert::smartcrop::CropRect;
+use crate::metadata::IptcFields;
+
 /// 格式转换选项
-#[wasm_bindgen]
+#[wasm_bindgen(getter_with_clone)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FormatConversionOptions {
     /// 目标格式
@@ -21,11 +25,20 @@ pub struct FormatConversionOptions {
     pub color_space: Option<String>,
     /// 尺寸调整选项
     pub resize: Option<ResizeOptions>,
+    /// 元数据策略 (strip_all / strip_private / keep_all)，默认 strip_all；
+    /// 指定了 preserve_metadata 时默认 keep_all
+    pub metadata_policy: Option<String>,
+    /// 需要迁移的元数据块，逗号分隔 (exif,xmp,icc,iptc / all)，默认全部；iptc 只写入 JPEG
+    pub preserve_metadata: Option<String>,
+    /// 写入输出文件的 IPTC 字段，与迁移的 IPTC 合并；只支持 JPEG 输出
+    pub iptc: Option<IptcFields>,
+    /// 输出文件名模板，指定时在结果中返回 file_name
+    pub naming: Option<FileNameOptions>,
 }
 
 /// 尺寸调整选项
-#[wasm_bindgen]
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ResizeOptions {
     /// 目标宽度
     pub width: Option<u32>,
@@ -33,10 +46,33 @@ pub struct ResizeOptions {
     pub height: Option<u32>,
     /// 是否保持宽高比
     pub maintain_aspect_ratio: Option<bool>,
+    /// 适配模式 (fill / inside / outside / cover / contain / pad)，pad 同 contain；未指定时按 maintain_aspect_ratio 使用 inside 或 fill
+    pub fit: Option<String>,
+    /// cover 裁剪和 contain 填充的对齐位置 (center / north / northeast / east / southeast / ... / smart)，默认 center；
+    /// smart 按边缘、饱和度和肤色选择 cover 的裁剪区域
+    pub gravity: Option<String>,
+    /// 重采样滤波器 (auto / nearest / bilinear / catmull-rom / mitchell / lanczos3)，默认 auto：
+    /// 大幅缩小时用 bilinear，小幅缩小时用 lanczos3，放大时用 catmull-rom
+    pub filter: Option<String>,
+    /// 是否在线性光中缩放，避免细亮线和高对比纹理缩小后偏暗，默认 false
+    pub linear_light: Option<bool>,
+    /// 是否禁止放大，为 true 时输出不超过原图尺寸（contain 仍填充到指定的框），默认 false
+    pub without_enlargement: Option<bool>,
+    /// 按百分比缩放，如 50 表示缩小到一半，不能与 width / height 同时使用
+    pub scale: Option<f32>,
+    /// 输出像素数上限（百万像素），超出时等比缩小
+    pub max_megapixels: Option<f32>,
+    /// contain 填充区域的背景 (transparent / blur / white / black / #rrggbb / #rrggbbaa)，默认 transparent，
+    /// 格式转换的输出不保留透明通道时默认 white；blur 用放大并模糊的原图铺满背景
+    pub background: Option<String>,
+    /// background 为 blur 时的模糊半径（输出像素），默认 20
+    pub background_blur: Option<f32>,
+    /// 缩放前先从原图裁出的区域（原图坐标，如 smart_crop 的结果），其余选项作用于裁剪后的图片
+    pub crop: Option<CropRect>,
 }
 
 /// 格式转换结果
-#[wasm_bindgen]
+#[wasm_bindgen(getter_with_clone)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FormatConversionResult {
     /// 转换后的数据
@@ -55,6 +91,14 @@ pub struct FormatConversionResult {
     pub original_height: u32,
     /// 转换时间 (毫秒)
     pub conversion_time: f64,
+    /// 转换后数据的 git blob SHA-1，可用于判断仓库中是否已存在相同文件
+    pub git_blob_sha: String,
+    /// 按 naming 模板生成的文件路径
+    pub file_name: Option<String>,
+    /// cover 裁剪或指定 crop 时原图中被保留的区域
+    pub crop: Option<CropRect>,
+    /// 目标格式放不下而被丢弃的元数据 JSON string，如 ["EXIF dropped: ..."]
+    pub metadata_warnings_json: String,
 }
 
 /// 支持的图片格式
@@ -105,6 +149,11 @@ impl SupportedFormat {
             SupportedFormat::Tiff => "tiff",
         }
     }
+
+    /// 输出是否保留透明通道（目前 GIF / BMP / TIFF 都按 RGB 编码）
+    pub fn supports_alpha(&self) -> bool {
+        matches!(self, SupportedFormat::Png | SupportedFormat::WebP)
+    }
 }
 
 /// 转换统计信息
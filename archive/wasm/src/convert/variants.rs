@@ -0,0 +1,358 @@
+//! 响应式图片变体
+//!
+//! 解码一次，按宽度 × 格式批量生成多份输出，
+//! 并拼好可直接嵌入页面的 srcset 和 <picture> 片段
+
+use wasm_bindgen::prelude::*;
+use image::{DynamicImage, GenericImageView};
+use serde::{Deserialize, Serialize};
+
+use super::converters::{ensure_encodable, get_converter, validate_conversion_options, ConversionOptions};
+use super::naming::{render_template, FileNameOptions, NamingContext};
+use super::resize::apply_resize;
+use super::types::{ResizeOptions, SupportedFormat};
+
+/// 默认输出宽度
+const DEFAULT_WIDTHS: [u32; 5] = [320, 640, 960, 1280, 1920];
+/// 默认输出格式，最后一个作为 <img> 的回退格式
+// TODO: WebP 编码可用后改为 webp + jpeg
+const DEFAULT_FORMATS: [&str; 1] = ["jpeg"];
+/// 默认编码质量
+const DEFAULT_QUALITY: u8 = 80;
+/// 默认文件名模板
+const DEFAULT_TEMPLATE: &str = "{name}-{width}.{ext}";
+/// 默认 sizes 属性
+const DEFAULT_SIZES: &str = "100vw";
+
+/// 变体生成选项
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VariantOptions {
+    /// 输出宽度，默认 320/640/960/1280/1920，大于原图的宽度会被跳过
+    pub widths: Option<Vec<u32>>,
+    /// 输出格式，默认 jpeg，最后一个作为 <img> 的回退格式；当前构建不支持 webp
+    pub formats: Option<Vec<String>>,
+    /// 编码质量 (1-100)，默认 80
+    pub quality: Option<u8>,
+    /// 重采样滤镜，同 ResizeOptions.filter
+    pub filter: Option<String>,
+    /// 是否在线性光空间中缩放
+    pub linear_light: Option<bool>,
+    /// 文件名模板，默认 {name}-{width}.{ext}
+    pub naming: Option<FileNameOptions>,
+    /// 拼接在文件名前的 URL 前缀，如 https://cdn.example.com/blog/
+    pub base_url: Option<String>,
+    /// sizes 属性，默认 100vw
+    pub sizes: Option<String>,
+    /// <img> 的 alt 文本
+    pub alt: Option<String>,
+}
+
+/// 单个变体
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageVariant {
+    /// 编码后的数据
+    pub data: Vec<u8>,
+    /// 输出格式扩展名
+    pub format: String,
+    /// MIME 类型
+    pub mime_type: String,
+    /// 宽度
+    pub width: u32,
+    /// 高度
+    pub height: u32,
+    /// 按模板生成的文件名
+    pub file_name: String,
+    /// srcset 中使用的 URL（base_url + file_name）
+    pub url: String,
+    /// 数据字节数
+    pub size: u32,
+}
+
+/// 变体生成结果
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VariantSet {
+    /// 所有变体，按宽度升序，同宽度内按 formats 顺序
+    pub variants: Vec<ImageVariant>,
+    /// 回退格式的 srcset
+    pub srcset: String,
+    /// <picture> HTML 片段
+    pub picture: String,
+    /// 原始宽度
+    pub original_width: u32,
+    /// 原始高度
+    pub original_height: u32,
+}
+
+/// 生成响应式图片变体（WASM 导出）
+#[wasm_bindgen]
+pub fn generate_variants(image_data: &[u8], options: Option<VariantOptions>) -> Result<VariantSet, JsValue> {
+    let img = crate::image::decode_image(image_data)
+        .map_err(|e| JsValue::from_str(&e))?
+        .image;
+    create_variants(image_data, &img, &options.unwrap_or_default()).map_err(|e| JsValue::from_str(&e))
+}
+
+/// 从解码后的图片生成变体
+///
+/// 每个宽度只缩放一次，再交给各格式的转换器编码；
+/// 所有宽度都大于原图时按原图宽度输出一份
+pub fn create_variants(source: &[u8], img: &DynamicImage, options: &VariantOptions) -> Result<VariantSet, String> {
+    let mut widths = options.widths.clone().unwrap_or_else(|| DEFAULT_WIDTHS.to_vec());
+    if widths.is_empty() {
+        return Err("At least one variant width is required".to_string());
+    }
+    if widths.contains(&0) {
+        return Err("Variant widths must be greater than 0".to_string());
+    }
+    widths.sort_unstable();
+    widths.dedup();
+
+    let format_names = options
+        .formats
+        .clone()
+        .unwrap_or_else(|| DEFAULT_FORMATS.iter().map(|f| f.to_string()).collect());
+    if format_names.is_empty() {
+        return Err("At least one variant format is required".to_string());
+    }
+    let mut formats: Vec<SupportedFormat> = Vec::new();
+    for name in &format_names {
+        let format = SupportedFormat::from_string(name)
+            .ok_or_else(|| format!("Unsupported variant format: {}", name))?;
+        ensure_encodable(&format)?;
+        if !formats.contains(&format) {
+            formats.push(format);
+        }
+    }
+
+    let conversion_options = ConversionOptions {
+        quality: options.quality.unwrap_or(DEFAULT_QUALITY),
+        preserve_transparency: true,
+        lossless: false,
+    };
+    validate_conversion_options(&conversion_options)?;
+
+    let naming = options.naming.clone().unwrap_or_else(|| FileNameOptions {
+        template: DEFAULT_TEMPLATE.to_string(),
+        original_name: None,
+        fallback_date: None,
+    });
+    let base_url = options.base_url.as_deref().unwrap_or("");
+
+    let (original_width, original_height) = img.dimensions();
+    widths.retain(|&width| width <= original_width);
+    if widths.is_empty() {
+        widths.push(original_width);
+    }
+
+    let mut variants = Vec::with_capacity(widths.len() * formats.len());
+    for &width in &widths {
+        let resize = ResizeOptions {
+            width: Some(width),
+            maintain_aspect_ratio: Some(true),
+            filter: options.filter.clone(),
+            linear_light: options.linear_light,
+            ..Default::default()
+        };
+        let resized = if width == original_width {
+            img.clone()
+        } else {
+            apply_resize(img, &resize)?
+        };
+        let dimensions = resized.dimensions();
+
+        for format in &formats {
+            let data = get_converter(format).convert(&resized, &conversion_options)?;
+            let context = NamingContext::new(&data, source, format.extension(), dimensions, &naming);
+            let file_name = render_template(&naming.template, &context)?;
+            variants.push(ImageVariant {
+                format: format.extension().to_string(),
+                mime_type: format.mime_type().to_string(),
+                width: dimensions.0,
+                height: dimensions.1,
+                url: format!("{}{}", base_url, file_name),
+                file_name,
+                size: data.len() as u32,
+                data,
+            });
+        }
+    }
+
+    let sizes = options.sizes.as_deref().unwrap_or(DEFAULT_SIZES);
+    let picture = build_picture(&variants, &formats, sizes, options.alt.as_deref().unwrap_or(""));
+    let srcset = build_srcset(&variants, formats.last().map(|f| f.mime_type()).unwrap_or_default());
+
+    Ok(VariantSet {
+        variants,
+        srcset,
+        picture,
+        original_width,
+        original_height,
+    })
+}
+
+/// 拼接指定 MIME 类型的 srcset，如 a-320.jpg 320w, a-640.jpg 640w
+fn build_srcset(variants: &[ImageVariant], mime_type: &str) -> String {
+    variants
+        .iter()
+        .filter(|variant| variant.mime_type == mime_type)
+        .map(|variant| format!("{} {}w", variant.url, variant.width))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// 拼接 <picture> 片段：前面的格式作为 <source>，最后一个格式作为 <img> 回退
+fn build_picture(variants: &[ImageVariant], formats: &[SupportedFormat], sizes: &str, alt: &str) -> String {
+    let Some((fallback, sources)) = formats.split_last() else {
+        return String::new();
+    };
+    let sizes = escape_attribute(sizes);
+    let mut html = String::from("<picture>\n");
+
+    for format in sources {
+        html.push_str(&format!(
+            "  <source type=\"{}\" srcset=\"{}\" sizes=\"{}\">\n",
+            format.mime_type(),
+            escape_attribute(&build_srcset(variants, format.mime_type())),
+            sizes,
+        ));
+    }
+
+    // src 使用最大的回退变体
+    if let Some(largest) = variants.iter().rev().find(|variant| variant.mime_type == fallback.mime_type()) {
+        html.push_str(&format!(
+            "  <img src=\"{}\" srcset=\"{}\" sizes=\"{}\" width=\"{}\" height=\"{}\" alt=\"{}\" loading=\"lazy\" decoding=\"async\">\n",
+            escape_attribute(&largest.url),
+            escape_attribute(&build_srcset(variants, fallback.mime_type())),
+            sizes,
+            largest.width,
+            largest.height,
+            escape_attribute(alt),
+        ));
+    }
+    html.push_str("</picture>");
+    html
+}
+
+/// 转义 HTML 属性值
+fn escape_attribute(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '"' => escaped.push_str("&quot;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{ImageBuffer, Rgb};
+
+    fn photo(width: u32, height: u32) -> DynamicImage {
+        DynamicImage::ImageRgb8(ImageBuffer::from_fn(width, height, |x, y| {
+            Rgb([(x % 256) as u8, (y % 256) as u8, ((x + y) % 256) as u8])
+        }))
+    }
+
+    fn options(widths: &[u32], formats: &[&str]) -> VariantOptions {
+        VariantOptions {
+            widths: Some(widths.to_vec()),
+            formats: Some(formats.iter().map(|f| f.to_string()).collect()),
+            naming: Some(FileNameOptions {
+                template: "{name}-{width}.{ext}".to_string(),
+                original_name: Some("hero.jpg".to_string()),
+                fallback_date: None,
+            }),
+            base_url: Some("/img/".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_skips_widths_larger_than_source() {
+        let set = create_variants(&[], &photo(1000, 500), &options(&[1920, 320, 640, 960, 1280], &["png", "jpeg"])).unwrap();
+        assert_eq!((set.original_width, set.original_height), (1000, 500));
+
+        let dims: Vec<(String, u32, u32)> = set
+            .variants
+            .iter()
+            .map(|variant| (variant.format.clone(), variant.width, variant.height))
+            .collect();
+        assert_eq!(
+            dims,
+            vec![
+                ("png".to_string(), 320, 160),
+                ("jpg".to_string(), 320, 160),
+                ("png".to_string(), 640, 320),
+                ("jpg".to_string(), 640, 320),
+                ("png".to_string(), 960, 480),
+                ("jpg".to_string(), 960, 480),
+            ]
+        );
+
+        for variant in &set.variants {
+            let decoded = image::load_from_memory(&variant.data).unwrap();
+            assert_eq!(decoded.dimensions(), (variant.width, variant.height));
+            assert_eq!(variant.size as usize, variant.data.len());
+        }
+    }
+
+    #[test]
+    fn test_small_source_keeps_original_width() {
+        let set = create_variants(&[], &photo(200, 100), &options(&[320, 640], &["jpeg"])).unwrap();
+        assert_eq!(set.variants.len(), 1);
+        assert_eq!((set.variants[0].width, set.variants[0].height), (200, 100));
+        assert_eq!(set.srcset, "/img/hero-200.jpg 200w");
+    }
+
+    #[test]
+    fn test_srcset_and_picture() {
+        let mut opts = options(&[320, 640], &["png", "jpeg"]);
+        opts.sizes = Some("(max-width: 640px) 100vw, 640px".to_string());
+        opts.alt = Some("A \"hero\" <image>".to_string());
+        let set = create_variants(&[], &photo(800, 400), &opts).unwrap();
+
+        assert_eq!(set.srcset, "/img/hero-320.jpg 320w, /img/hero-640.jpg 640w");
+        assert_eq!(
+            set.picture,
+            "<picture>\n\
+             \x20 <source type=\"image/png\" srcset=\"/img/hero-320.png 320w, /img/hero-640.png 640w\" sizes=\"(max-width: 640px) 100vw, 640px\">\n\
+             \x20 <img src=\"/img/hero-640.jpg\" srcset=\"/img/hero-320.jpg 320w, /img/hero-640.jpg 640w\" sizes=\"(max-width: 640px) 100vw, 640px\" \
+             width=\"640\" height=\"320\" alt=\"A &quot;hero&quot; &lt;image&gt;\" loading=\"lazy\" decoding=\"async\">\n\
+             </picture>"
+        );
+    }
+
+    #[test]
+    fn test_default_options() {
+        let set = create_variants(&[], &photo(1000, 500), &VariantOptions::default()).unwrap();
+        let dims: Vec<(&str, u32)> = set.variants.iter().map(|variant| (variant.format.as_str(), variant.width)).collect();
+        assert_eq!(dims, vec![("jpg", 320), ("jpg", 640), ("jpg", 960)]);
+        assert_eq!(set.srcset, "image-320.jpg 320w, image-640.jpg 640w, image-960.jpg 960w");
+        assert!(set.picture.contains("<img src=\"image-960.jpg\""));
+        assert!(!set.picture.contains("<source"));
+    }
+
+    #[test]
+    fn test_invalid_options() {
+        let img = photo(100, 100);
+        for opts in [
+            VariantOptions { widths: Some(vec![]), ..Default::default() },
+            VariantOptions { widths: Some(vec![0, 320]), ..Default::default() },
+            VariantOptions { formats: Some(vec![]), ..Default::default() },
+            VariantOptions { formats: Some(vec!["avif".to_string()]), ..Default::default() },
+            VariantOptions { formats: Some(vec!["webp".to_string(), "jpeg".to_string()]), ..Default::default() },
+            VariantOptions { quality: Some(0), ..Default::default() },
+        ] {
+            assert!(create_variants(&[], &img, &opts).is_err());
+        }
+    }
+}
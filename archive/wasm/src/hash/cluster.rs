@@ -0,0 +1,362 @@
+//! 相似图片分组
+//!
+//! 对一批图片计算感知哈希，汉明距离不超过阈值的图片用并查集合并为一组
+//! （距离具有传递性：A≈B、B≈C 时 A、B、C 在同一组），连拍和重复图片会被归到一起。
+//! 每组按清晰度和分辨率推荐一张保留。无法解码的图片不参与分组，单独列出
+
+use wasm_bindgen::prelude::*;
+use image::imageops::{self, FilterType};
+use image::{DynamicImage, GenericImageView};
+use serde::{Deserialize, Serialize};
+
+use crate::hash::perceptual::{hamming_distance, PerceptualHashKind};
+use crate::image::{bytes_list, decode_image};
+
+/// 默认距离阈值（64 位哈希）
+const DEFAULT_THRESHOLD: u32 = 10;
+/// 计算清晰度前统一缩放到的最长边，使不同分辨率的图片可比较
+/// （小图放大后细节更少，清晰度自然更低）
+const SHARPNESS_SIZE: u32 = 256;
+/// 清晰度在组内最高值的这个比例以内时视为相当，改为比较分辨率
+const SHARPNESS_TOLERANCE: f64 = 0.95;
+
+/// 分组选项
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ClusterOptions {
+  /// 距离阈值 (0-64)，默认 10
+  pub threshold: Option<u32>,
+  /// 感知哈希算法 (ahash / dhash / phash)，默认 phash
+  pub hash: Option<String>,
+  /// 是否返回只有一张图片的组，默认 false
+  pub include_singletons: Option<bool>,
+}
+
+/// 一组相似图片
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateGroup {
+  /// 组内图片在输入中的索引（升序）
+  pub indices: Vec<u32>,
+  /// 推荐保留的图片索引
+  pub best_index: u32,
+  /// 组内图片的清晰度（拉普拉斯方差），顺序与 indices 一致
+  pub sharpness: Vec<f64>,
+  /// 组内任意两张图片的最大距离
+  pub max_distance: u32,
+}
+
+/// 无法参与分组的图片
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClusterFailure {
+  /// 图片在输入中的索引
+  pub index: u32,
+  /// 错误信息
+  pub error: String,
+}
+
+/// 分组结果
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClusterResult {
+  /// 相似图片组
+  pub groups: Vec<DuplicateGroup>,
+  /// 无法解码而被跳过的图片
+  pub failed: Vec<ClusterFailure>,
+}
+
+/// 单张图片的分组特征
+#[derive(Debug, Clone)]
+pub struct ImageFingerprint {
+  /// 感知哈希
+  pub hash: u64,
+  /// 清晰度
+  pub sharpness: f64,
+  /// 像素数
+  pub pixels: u64,
+}
+
+impl ImageFingerprint {
+  /// 计算图片的分组特征
+  pub fn from_image(img: &DynamicImage, kind: PerceptualHashKind) -> Self {
+    let (width, height) = img.dimensions();
+    Self {
+      hash: kind.compute(img),
+      sharpness: sharpness(img),
+      pixels: width as u64 * height as u64,
+    }
+  }
+}
+
+/// 对一批图片按相似度分组（WASM 导出），无法解码的图片跳过并在 failed 中列出
+#[wasm_bindgen]
+pub fn cluster_similar_images(images_data: Vec<JsValue>, options: Option<ClusterOptions>) -> Result<ClusterResult, JsValue> {
+  cluster_images(&bytes_list(&images_data), &options.unwrap_or_default()).map_err(|e| JsValue::from_str(&e))
+}
+
+/// 对一批图片按相似度分组，只在选项无效时返回错误
+pub fn cluster_images(images_data: &[Vec<u8>], options: &ClusterOptions) -> Result<ClusterResult, String> {
+  let kind = PerceptualHashKind::parse_or_default(options.hash.as_deref())?;
+
+  // 解码成功的图片及其在输入中的索引
+  let mut fingerprints = Vec::new();
+  let mut positions = Vec::new();
+  let mut failed = Vec::new();
+  for (i, data) in images_data.iter().enumerate() {
+    match decode_image(data) {
+      Ok(decoded) => {
+        fingerprints.push(ImageFingerprint::from_image(&decoded.image, kind));
+        positions.push(i as u32);
+      }
+      Err(error) => failed.push(ClusterFailure { index: i as u32, error }),
+    }
+  }
+
+  let mut groups = cluster_fingerprints(
+    &fingerprints,
+    options.threshold.unwrap_or(DEFAULT_THRESHOLD),
+    options.include_singletons.unwrap_or(false),
+  );
+  // 换算回输入中的索引，位置单调递增，组内顺序和组间顺序不变
+  for group in &mut groups {
+    group.indices.iter_mut().for_each(|index| *index = positions[*index as usize]);
+    group.best_index = positions[group.best_index as usize];
+  }
+  Ok(ClusterResult { groups, failed })
+}
+
+/// 按距离阈值分组，组按最小索引排序
+pub fn cluster_fingerprints(
+  fingerprints: &[ImageFingerprint],
+  threshold: u32,
+  include_singletons: bool,
+) -> Vec<DuplicateGroup> {
+  let mut sets = DisjointSet::new(fingerprints.len());
+  for i in 0..fingerprints.len() {
+    for j in (i + 1)..fingerprints.len() {
+      if hamming_distance(fingerprints[i].hash, fingerprints[j].hash) <= threshold {
+        sets.union(i, j);
+      }
+    }
+  }
+
+  let mut members: Vec<Vec<usize>> = vec![Vec::new(); fingerprints.len()];
+  for i in 0..fingerprints.len() {
+    let root = sets.find(i);
+    members[root].push(i);
+  }
+
+  let mut groups: Vec<DuplicateGroup> = members
+    .into_iter()
+    .filter(|indices| indices.len() > 1 || (include_singletons && indices.len() == 1))
+    .map(|indices| build_group(fingerprints, &indices))
+    .collect();
+  groups.sort_by_key(|group| group.indices[0]);
+  groups
+}
+
+/// 生成分组结果并选出推荐图片
+fn build_group(fingerprints: &[ImageFingerprint], indices: &[usize]) -> DuplicateGroup {
+  let mut max_distance = 0;
+  for (n, &i) in indices.iter().enumerate() {
+    for &j in &indices[n + 1..] {
+      max_distance = max_distance.max(hamming_distance(fingerprints[i].hash, fingerprints[j].hash));
+    }
+  }
+
+  DuplicateGroup {
+    indices: indices.iter().map(|&i| i as u32).collect(),
+    best_index: best_member(fingerprints, indices) as u32,
+    sharpness: indices.iter().map(|&i| fingerprints[i].sharpness).collect(),
+    max_distance,
+  }
+}
+
+/// 选出推荐图片：清晰度接近最高值的图片中分辨率最大的一张
+pub fn best_member(fingerprints: &[ImageFingerprint], indices: &[usize]) -> usize {
+  let max_sharpness = indices
+    .iter()
+    .map(|&i| fingerprints[i].sharpness)
+    .fold(0.0f64, f64::max);
+
+  indices
+    .iter()
+    .copied()
+    .filter(|&i| fingerprints[i].sharpness >= max_sharpness * SHARPNESS_TOLERANCE)
+    .max_by(|&a, &b| {
+      fingerprints[a]
+        .pixels
+        .cmp(&fingerprints[b].pixels)
+        .then(fingerprints[a].sharpness.total_cmp(&fingerprints[b].sharpness))
+        // 完全相同时保留靠前的图片
+        .then(b.cmp(&a))
+    })
+    .unwrap_or(indices[0])
+}
+
+/// 清晰度：统一尺寸后灰度图的拉普拉斯方差，越大边缘越锐利
+pub fn sharpness(img: &DynamicImage) -> f64 {
+  let (width, height) = img.dimensions();
+  let scale = SHARPNESS_SIZE as f64 / width.max(height) as f64;
+  let target_width = ((width as f64 * scale).round() as u32).max(1);
+  let target_height = ((height as f64 * scale).round() as u32).max(1);
+  if target_width < 3 || target_height < 3 {
+    return 0.0;
+  }
+  let gray = imageops::resize(&img.to_luma8(), target_width, target_height, FilterType::Triangle);
+
+  let value = |x: u32, y: u32| gray.get_pixel(x, y).0[0] as f64;
+  let mut responses = Vec::with_capacity(((target_width - 2) * (target_height - 2)) as usize);
+  for y in 1..target_height - 1 {
+    for x in 1..target_width - 1 {
+      responses.push(
+        value(x - 1, y) + value(x + 1, y) + value(x, y - 1) + value(x, y + 1) - 4.0 * value(x, y),
+      );
+    }
+  }
+
+  let mean = responses.iter().sum::<f64>() / responses.len() as f64;
+  responses.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / responses.len() as f64
+}
+
+/// 并查集（路径压缩 + 按大小合并）
+struct DisjointSet {
+  parent: Vec<usize>,
+  size: Vec<usize>,
+}
+
+impl DisjointSet {
+  fn new(len: usize) -> Self {
+    Self {
+      parent: (0..len).collect(),
+      size: vec![1; len],
+    }
+  }
+
+  fn find(&mut self, mut i: usize) -> usize {
+    while self.parent[i] != i {
+      self.parent[i] = self.parent[self.parent[i]];
+      i = self.parent[i];
+    }
+    i
+  }
+
+  fn union(&mut self, a: usize, b: usize) {
+    let (mut a, mut b) = (self.find(a), self.find(b));
+    if a == b {
+      return;
+    }
+    if self.size[a] < self.size[b] {
+      std::mem::swap(&mut a, &mut b);
+    }
+    self.parent[b] = a;
+    self.size[a] += self.size[b];
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use image::{ImageBuffer, Rgb};
+
+  fn scene(width: u32, height: u32, seed: u32) -> DynamicImage {
+    DynamicImage::ImageRgb8(ImageBuffer::from_fn(width, height, |x, y| {
+      let cell = (x * 8 / width) * (3 + seed) + (y * 8 / height) * (5 + seed * 2);
+      let v = ((cell * 37 + seed * 91) % 256) as u8;
+      Rgb([v, 255 - v, v / 2])
+    }))
+  }
+
+  fn encode(img: &DynamicImage, format: image::ImageOutputFormat) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    img.write_to(&mut std::io::Cursor::new(&mut buffer), format).unwrap();
+    buffer
+  }
+
+  #[test]
+  fn test_groups_duplicates_and_picks_best() {
+    let original = scene(400, 300, 0);
+    let images = vec![
+      encode(&original.resize_exact(200, 150, FilterType::Triangle), image::ImageOutputFormat::Png),
+      encode(&scene(400, 300, 1), image::ImageOutputFormat::Png),
+      encode(&original, image::ImageOutputFormat::Png),
+      encode(&original.blur(3.0), image::ImageOutputFormat::Jpeg(80)),
+    ];
+
+    let groups = cluster_images(&images, &ClusterOptions::default()).unwrap().groups;
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups[0].indices, vec![0, 2, 3]);
+    assert_eq!(groups[0].best_index, 2);
+    assert_eq!(groups[0].sharpness.len(), 3);
+    assert!(groups[0].sharpness[1] > groups[0].sharpness[2]);
+
+    let options = ClusterOptions {
+      hash: Some("dhash".to_string()),
+      include_singletons: Some(true),
+      ..Default::default()
+    };
+    let groups = cluster_images(&images, &options).unwrap().groups;
+    let singletons: Vec<&DuplicateGroup> = groups.iter().filter(|g| g.indices.len() == 1).collect();
+    assert_eq!(singletons.len(), 1);
+    assert_eq!(singletons[0].indices, vec![1]);
+    assert_eq!(singletons[0].best_index, 1);
+  }
+
+  #[test]
+  fn test_clusters_are_transitive() {
+    let fingerprint = |hash: u64| ImageFingerprint { hash, sharpness: 1.0, pixels: 100 };
+    // 0 与 1 距离 4，1 与 2 距离 4，0 与 2 距离 8
+    let fingerprints = vec![fingerprint(0), fingerprint(0xF), fingerprint(0xFF), fingerprint(u64::MAX)];
+
+    let groups = cluster_fingerprints(&fingerprints, 4, false);
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups[0].indices, vec![0, 1, 2]);
+    assert_eq!(groups[0].max_distance, 8);
+    // 特征完全相同时推荐第一张
+    assert_eq!(groups[0].best_index, 0);
+
+    assert!(cluster_fingerprints(&fingerprints, 3, false).is_empty());
+  }
+
+  #[test]
+  fn test_best_member_prefers_resolution_when_equally_sharp() {
+    let fingerprints = vec![
+      ImageFingerprint { hash: 0, sharpness: 100.0, pixels: 1_000 },
+      ImageFingerprint { hash: 0, sharpness: 97.0, pixels: 4_000 },
+      ImageFingerprint { hash: 0, sharpness: 50.0, pixels: 16_000 },
+    ];
+    assert_eq!(best_member(&fingerprints, &[0, 1, 2]), 1);
+    assert_eq!(best_member(&fingerprints, &[0, 2]), 0);
+  }
+
+  #[test]
+  fn test_skips_undecodable_images() {
+    let original = scene(64, 64, 0);
+    let images = vec![
+      b"broken".to_vec(),
+      encode(&original, image::ImageOutputFormat::Png),
+      Vec::new(),
+      encode(&original, image::ImageOutputFormat::Jpeg(90)),
+    ];
+
+    let result = cluster_images(&images, &ClusterOptions::default()).unwrap();
+    assert_eq!(result.groups.len(), 1);
+    assert_eq!(result.groups[0].indices, vec![1, 3]);
+    assert!(result.groups[0].best_index == 1 || result.groups[0].best_index == 3);
+    assert_eq!(result.failed.iter().map(|f| f.index).collect::<Vec<_>>(), vec![0, 2]);
+    assert!(result.failed.iter().all(|f| !f.error.is_empty()));
+  }
+
+  #[test]
+  fn test_invalid_options() {
+    let options = ClusterOptions {
+      hash: Some("md5".to_string()),
+      ..Default::default()
+    };
+    assert!(cluster_images(&[], &options).is_err());
+
+    let result = cluster_images(&[], &ClusterOptions::default()).unwrap();
+    assert!(result.groups.is_empty() && result.failed.is_empty());
+  }
+}
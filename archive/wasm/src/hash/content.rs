@@ -0,0 +1,265 @@
+//! 内容哈希
+//!
+//! 对原始字节计算 SHA-256 / BLAKE3，可选对解码后的像素计算哈希。
+//! 像素哈希统一转换为 RGBA8 并包含尺寸，元数据不同或无损重新编码的同一图片结果相同
+
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::hash::HashEncoding;
+use crate::image::decode_image;
+
+/// 哈希算法
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HashAlgorithm {
+  Sha256,
+  Blake3,
+}
+
+impl HashAlgorithm {
+  /// 从字符串解析算法
+  pub fn from_string(algorithm_str: &str) -> Option<Self> {
+    match algorithm_str.to_lowercase().as_str() {
+      "sha256" | "sha-256" => Some(HashAlgorithm::Sha256),
+      "blake3" => Some(HashAlgorithm::Blake3),
+      _ => None,
+    }
+  }
+
+  /// 解析可选的算法字符串，未指定时使用 SHA-256
+  pub fn parse_or_default(algorithm_str: Option<&str>) -> Result<Self, String> {
+    match algorithm_str {
+      Some(s) => Self::from_string(s).ok_or_else(|| format!("Unsupported hash algorithm: {}", s)),
+      None => Ok(HashAlgorithm::Sha256),
+    }
+  }
+
+  /// 算法名称
+  pub fn name(&self) -> &'static str {
+    match self {
+      HashAlgorithm::Sha256 => "sha256",
+      HashAlgorithm::Blake3 => "blake3",
+    }
+  }
+}
+
+/// 内部哈希状态
+enum HasherState {
+  Sha256(Sha256),
+  Blake3(Box<blake3::Hasher>),
+}
+
+/// 增量哈希计算器（WASM 导出）
+///
+/// 大文件可以分块调用 `update`，避免一次性复制整个缓冲区
+#[wasm_bindgen]
+pub struct ContentHasher {
+  state: HasherState,
+}
+
+impl ContentHasher {
+  /// 使用指定算法创建计算器
+  pub fn with_algorithm(algorithm: HashAlgorithm) -> Self {
+    let state = match algorithm {
+      HashAlgorithm::Sha256 => HasherState::Sha256(Sha256::new()),
+      HashAlgorithm::Blake3 => HasherState::Blake3(Box::new(blake3::Hasher::new())),
+    };
+    Self { state }
+  }
+
+  /// 结束计算，返回原始摘要
+  pub fn finish(self) -> Vec<u8> {
+    match self.state {
+      HasherState::Sha256(hasher) => hasher.finalize().to_vec(),
+      HasherState::Blake3(hasher) => hasher.finalize().as_bytes().to_vec(),
+    }
+  }
+}
+
+#[wasm_bindgen]
+impl ContentHasher {
+  /// 创建计算器，algorithm 为 sha256 或 blake3
+  #[wasm_bindgen(constructor)]
+  pub fn new(algorithm: String) -> Result<ContentHasher, JsValue> {
+    let algorithm = HashAlgorithm::from_string(&algorithm)
+      .ok_or_else(|| JsValue::from_str(&format!("Unsupported hash algorithm: {}", algorithm)))?;
+    Ok(Self::with_algorithm(algorithm))
+  }
+
+  /// 追加数据
+  pub fn update(&mut self, chunk: &[u8]) {
+    match &mut self.state {
+      HasherState::Sha256(hasher) => hasher.update(chunk),
+      HasherState::Blake3(hasher) => {
+        hasher.update(chunk);
+      }
+    }
+  }
+
+  /// 结束计算，返回编码后的摘要（hex / base32，默认 hex）
+  pub fn finalize(self, encoding: Option<String>) -> Result<String, JsValue> {
+    let encoding = HashEncoding::parse_or_hex(encoding.as_deref()).map_err(|e| JsValue::from_str(&e))?;
+    Ok(encoding.encode(&self.finish()))
+  }
+}
+
+/// 内容哈希选项
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HashOptions {
+  /// 哈希算法 (sha256 / blake3)，默认 sha256
+  pub algorithm: Option<String>,
+  /// 输出编码 (hex / base32)，默认 hex
+  pub encoding: Option<String>,
+  /// 是否同时计算解码后像素的哈希
+  pub include_pixels: Option<bool>,
+}
+
+/// 内容哈希结果
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContentHashResult {
+  /// 使用的算法
+  pub algorithm: String,
+  /// 原始字节的哈希
+  pub content_hash: String,
+  /// 解码后像素的哈希
+  pub pixel_hash: Option<String>,
+  /// 原始大小
+  pub size: u32,
+}
+
+/// 计算图片的内容哈希（WASM 导出）
+#[wasm_bindgen]
+pub fn hash_image(image_data: &[u8], options: Option<HashOptions>) -> Result<ContentHashResult, JsValue> {
+  compute_hashes(image_data, &options.unwrap_or_default()).map_err(|e| JsValue::from_str(&e))
+}
+
+/// 按选项计算内容哈希和像素哈希
+pub fn compute_hashes(data: &[u8], options: &HashOptions) -> Result<ContentHashResult, String> {
+  let algorithm = HashAlgorithm::parse_or_default(options.algorithm.as_deref())?;
+  let encoding = HashEncoding::parse_or_hex(options.encoding.as_deref())?;
+
+  let pixel_hash = if options.include_pixels.unwrap_or(false) {
+    Some(encoding.encode(&pixel_digest(data, algorithm)?))
+  } else {
+    None
+  };
+
+  Ok(ContentHashResult {
+    algorithm: algorithm.name().to_string(),
+    content_hash: encoding.encode(&digest(data, algorithm)),
+    pixel_hash,
+    size: data.len() as u32,
+  })
+}
+
+/// 计算原始字节的摘要
+pub fn digest(data: &[u8], algorithm: HashAlgorithm) -> Vec<u8> {
+  let mut hasher = ContentHasher::with_algorithm(algorithm);
+  hasher.update(data);
+  hasher.finish()
+}
+
+/// 计算解码后像素的摘要（按 EXIF 方向校正，统一为 RGBA8）
+pub fn pixel_digest(data: &[u8], algorithm: HashAlgorithm) -> Result<Vec<u8>, String> {
+  let rgba = decode_image(data)?.image.to_rgba8();
+
+  let mut hasher = ContentHasher::with_algorithm(algorithm);
+  // 尺寸参与计算，避免像素相同但宽高不同的图片冲突
+  hasher.update(b"rgba8");
+  hasher.update(&rgba.width().to_le_bytes());
+  hasher.update(&rgba.height().to_le_bytes());
+  for row in rgba.rows() {
+    for pixel in row {
+      hasher.update(&pixel.0);
+    }
+  }
+  Ok(hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use image::{DynamicImage, ImageBuffer, Rgb, RgbImage};
+
+  fn encode(img: &RgbImage, format: image::ImageOutputFormat) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    DynamicImage::ImageRgb8(img.clone())
+      .write_to(&mut std::io::Cursor::new(&mut buffer), format)
+      .unwrap();
+    buffer
+  }
+
+  #[test]
+  fn test_known_digests() {
+    assert_eq!(
+      HashEncoding::Hex.encode(&digest(b"abc", HashAlgorithm::Sha256)),
+      "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+    assert_eq!(
+      HashEncoding::Hex.encode(&digest(b"", HashAlgorithm::Blake3)),
+      "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262"
+    );
+  }
+
+  #[test]
+  fn test_incremental_matches_single_pass() {
+    let data: Vec<u8> = (0..100_000u32).map(|i| (i % 253) as u8).collect();
+
+    for algorithm in [HashAlgorithm::Sha256, HashAlgorithm::Blake3] {
+      let mut hasher = ContentHasher::with_algorithm(algorithm);
+      for chunk in data.chunks(4096) {
+        hasher.update(chunk);
+      }
+      assert_eq!(hasher.finish(), digest(&data, algorithm));
+    }
+  }
+
+  #[test]
+  fn test_pixel_hash_matches_reencoded_copy() {
+    let img: RgbImage = ImageBuffer::from_fn(16, 8, |x, y| Rgb([x as u8 * 10, y as u8 * 20, 99]));
+    let png = encode(&img, image::ImageOutputFormat::Png);
+    let bmp = encode(&img, image::ImageOutputFormat::Bmp);
+
+    let options = HashOptions {
+      algorithm: Some("blake3".to_string()),
+      encoding: Some("base32".to_string()),
+      include_pixels: Some(true),
+    };
+    let png_hash = compute_hashes(&png, &options).unwrap();
+    let bmp_hash = compute_hashes(&bmp, &options).unwrap();
+
+    assert_eq!(png_hash.algorithm, "blake3");
+    assert_ne!(png_hash.content_hash, bmp_hash.content_hash);
+    assert_eq!(png_hash.pixel_hash, bmp_hash.pixel_hash);
+    // 32 字节摘要的 Base32 长度
+    assert_eq!(png_hash.content_hash.len(), 52);
+
+    let other: RgbImage = ImageBuffer::from_fn(8, 16, |x, y| Rgb([y as u8 * 10, x as u8 * 20, 99]));
+    let other_hash = compute_hashes(&encode(&other, image::ImageOutputFormat::Png), &options).unwrap();
+    assert_ne!(png_hash.pixel_hash, other_hash.pixel_hash);
+  }
+
+  #[test]
+  fn test_hash_options() {
+    let result = compute_hashes(b"not an image", &HashOptions::default()).unwrap();
+    assert_eq!(result.algorithm, "sha256");
+    assert_eq!(result.content_hash.len(), 64);
+    assert!(result.pixel_hash.is_none());
+    assert_eq!(result.size, 12);
+
+    let with_pixels = HashOptions {
+      include_pixels: Some(true),
+      ..Default::default()
+    };
+    assert!(compute_hashes(b"not an image", &with_pixels).is_err());
+
+    let invalid = HashOptions {
+      algorithm: Some("md5".to_string()),
+      ..Default::default()
+    };
+    assert_eq!(compute_hashes(b"", &invalid).unwrap_err(), "Unsupported hash algorithm: md5");
+  }
+}
@@ -0,0 +1,57 @@
+//! git blob 哈希
+//!
+//! GitHub / Gitee 的树对象以 blob SHA-1 标识文件：`sha1("blob <len>\0" + bytes)`。
+//! 对转换后的数据在本地计算同样的值，就能在上传前判断仓库中是否已存在相同文件
+
+use wasm_bindgen::prelude::*;
+use sha1::{Digest, Sha1};
+
+use crate::hash::to_hex;
+use crate::image::bytes_list;
+
+/// 计算 git blob SHA-1 摘要
+pub fn git_blob_digest(data: &[u8]) -> [u8; 20] {
+  let mut hasher = Sha1::new();
+  hasher.update(format!("blob {}\0", data.len()).as_bytes());
+  hasher.update(data);
+  hasher.finalize().into()
+}
+
+/// 计算 git blob SHA-1，返回 40 位十六进制字符串（WASM 导出）
+#[wasm_bindgen]
+pub fn git_blob_sha(data: &[u8]) -> String {
+  to_hex(&git_blob_digest(data))
+}
+
+/// 批量计算 git blob SHA-1（WASM 导出），结果顺序与输入一致
+#[wasm_bindgen]
+pub fn batch_git_blob_sha(outputs: Vec<JsValue>) -> Vec<String> {
+  git_blob_shas(&bytes_list(&outputs))
+}
+
+/// 批量计算 git blob SHA-1，结果顺序与输入一致
+pub fn git_blob_shas(outputs: &[Vec<u8>]) -> Vec<String> {
+  outputs.iter().map(|data| git_blob_sha(data)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_git_blob_sha() {
+    // 与 `git hash-object` 的结果一致
+    assert_eq!(git_blob_sha(b""), "e69de29bb2d1d6434b8b29ae775ad8c2e48c5391");
+    assert_eq!(git_blob_sha(b"hello world\n"), "3b18e512dba79e4c8300dd08aeb37f8e728b8dad");
+  }
+
+  #[test]
+  fn test_batch_git_blob_sha() {
+    let hashes = git_blob_shas(&[b"hello world\n".to_vec(), Vec::new()]);
+    assert_eq!(
+      hashes,
+      vec!["3b18e512dba79e4c8300dd08aeb37f8e728b8dad", "e69de29bb2d1d6434b8b29ae775ad8c2e48c5391"]
+    );
+    assert!(git_blob_shas(&[]).is_empty());
+  }
+}
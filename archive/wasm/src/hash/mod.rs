@@ -0,0 +1,103 @@
+//! 图片哈希
+//!
+//! 提供用于去重和确定性文件名的哈希功能，包括：
+//! - 原始字节的 SHA-256 / BLAKE3 内容哈希（支持增量计算）
+//! - 解码后像素的哈希，重新编码的相同图片也能匹配
+//! - 十六进制 / Base32 编码输出，可直接用作文件名
+//! - git blob SHA-1，用于和远程仓库中的文件对比去重
+//! - aHash / dHash / pHash 感知哈希，用于发现缩放或重新压缩后的相似图片
+//! - 按感知哈希对批量图片分组，并推荐每组保留的图片
+
+pub mod cluster;
+pub mod content;
+pub mod git;
+pub mod perceptual;
+
+// 重新导出主要类型和函数
+pub use cluster::{cluster_images, cluster_similar_images, ClusterFailure, ClusterOptions, ClusterResult, DuplicateGroup};
+pub use content::{hash_image, ContentHashResult, ContentHasher, HashAlgorithm, HashOptions};
+pub use git::{batch_git_blob_sha, git_blob_sha, git_blob_shas};
+pub use perceptual::{
+  compute_perceptual_hashes, hamming_distance, perceptual_hash_distance, PerceptualHashes,
+};
+
+/// 摘要的文本编码
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HashEncoding {
+  /// 小写十六进制
+  Hex,
+  /// 小写 Base32 (RFC 4648)，不带填充
+  Base32,
+}
+
+impl HashEncoding {
+  /// 从字符串解析编码
+  pub fn from_string(encoding_str: &str) -> Option<Self> {
+    match encoding_str.to_lowercase().as_str() {
+      "hex" => Some(HashEncoding::Hex),
+      "base32" => Some(HashEncoding::Base32),
+      _ => None,
+    }
+  }
+
+  /// 解析可选的编码字符串，未指定时使用十六进制
+  pub fn parse_or_hex(encoding_str: Option<&str>) -> Result<Self, String> {
+    match encoding_str {
+      Some(s) => Self::from_string(s).ok_or_else(|| format!("Unsupported hash encoding: {}", s)),
+      None => Ok(HashEncoding::Hex),
+    }
+  }
+
+  /// 编码摘要
+  pub fn encode(&self, digest: &[u8]) -> String {
+    match self {
+      HashEncoding::Hex => to_hex(digest),
+      HashEncoding::Base32 => to_base32(digest),
+    }
+  }
+}
+
+/// 小写十六进制编码
+pub fn to_hex(bytes: &[u8]) -> String {
+  bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// 小写 Base32 编码（RFC 4648 字母表，不带填充，适合大小写不敏感的文件系统）
+pub fn to_base32(bytes: &[u8]) -> String {
+  const ALPHABET: &[u8; 32] = b"abcdefghijklmnopqrstuvwxyz234567";
+
+  let mut output = String::with_capacity(bytes.len().div_ceil(5) * 8);
+  let mut buffer = 0u32;
+  let mut bits = 0;
+  for &byte in bytes {
+    buffer = (buffer << 8) | byte as u32;
+    bits += 8;
+    while bits >= 5 {
+      bits -= 5;
+      output.push(ALPHABET[((buffer >> bits) & 0x1F) as usize] as char);
+    }
+  }
+  if bits > 0 {
+    output.push(ALPHABET[((buffer << (5 - bits)) & 0x1F) as usize] as char);
+  }
+  output
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_encodings() {
+    assert_eq!(to_hex(&[0x00, 0xAB, 0xFF]), "00abff");
+    // RFC 4648 测试向量（小写、无填充）
+    assert_eq!(to_base32(b""), "");
+    assert_eq!(to_base32(b"f"), "my");
+    assert_eq!(to_base32(b"fo"), "mzxq");
+    assert_eq!(to_base32(b"foobar"), "mzxw6ytboi");
+
+    assert_eq!(HashEncoding::parse_or_hex(None).unwrap(), HashEncoding::Hex);
+    assert_eq!(HashEncoding::parse_or_hex(Some("BASE32")).unwrap(), HashEncoding::Base32);
+    assert!(HashEncoding::parse_or_hex(Some("base64")).is_err());
+  }
+}
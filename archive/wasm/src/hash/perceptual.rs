@@ -0,0 +1,259 @@
+//! 感知哈希
+//!
+//! 缩放、重新压缩后的同一图片哈希值相近，用汉明距离衡量相似度。
+//! 三种算法均输出 64 位哈希，以 16 位十六进制字符串返回给 JS：
+//! - aHash：8x8 灰度图与平均值比较
+//! - dHash：9x8 灰度图相邻像素比较
+//! - pHash：32x32 灰度图 DCT 后取左上角 8x8 低频系数与中位数比较
+
+use wasm_bindgen::prelude::*;
+use image::imageops::{self, FilterType};
+use image::{DynamicImage, GrayImage};
+use serde::{Deserialize, Serialize};
+
+use crate::image::decode_image;
+
+/// pHash 缩放尺寸
+const DCT_SIZE: usize = 32;
+/// pHash 保留的低频系数边长
+const DCT_KEEP: usize = 8;
+
+/// 感知哈希算法
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PerceptualHashKind {
+  /// 均值哈希 (aHash)
+  Average,
+  /// 差值哈希 (dHash)
+  Difference,
+  /// DCT 感知哈希 (pHash)
+  Dct,
+}
+
+impl PerceptualHashKind {
+  /// 从字符串解析算法
+  pub fn from_string(kind_str: &str) -> Option<Self> {
+    match kind_str.to_lowercase().as_str() {
+      "ahash" | "average" => Some(PerceptualHashKind::Average),
+      "dhash" | "difference" => Some(PerceptualHashKind::Difference),
+      "phash" | "dct" => Some(PerceptualHashKind::Dct),
+      _ => None,
+    }
+  }
+
+  /// 解析可选的算法字符串，未指定时使用 pHash
+  pub fn parse_or_default(kind_str: Option<&str>) -> Result<Self, String> {
+    match kind_str {
+      Some(s) => Self::from_string(s).ok_or_else(|| format!("Unsupported perceptual hash: {}", s)),
+      None => Ok(PerceptualHashKind::Dct),
+    }
+  }
+
+  /// 计算图片的哈希
+  pub fn compute(&self, img: &DynamicImage) -> u64 {
+    match self {
+      PerceptualHashKind::Average => average_hash(img),
+      PerceptualHashKind::Difference => difference_hash(img),
+      PerceptualHashKind::Dct => perceptual_hash(img),
+    }
+  }
+}
+
+/// 感知哈希结果
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PerceptualHashes {
+  /// 均值哈希 (aHash)
+  pub average_hash: String,
+  /// 差值哈希 (dHash)
+  pub difference_hash: String,
+  /// DCT 感知哈希 (pHash)
+  pub perceptual_hash: String,
+}
+
+impl PerceptualHashes {
+  /// 计算图片的全部感知哈希
+  pub fn from_image(img: &DynamicImage) -> Self {
+    Self {
+      average_hash: format_hash(average_hash(img)),
+      difference_hash: format_hash(difference_hash(img)),
+      perceptual_hash: format_hash(perceptual_hash(img)),
+    }
+  }
+}
+
+/// 计算图片的感知哈希（WASM 导出）
+#[wasm_bindgen]
+pub fn compute_perceptual_hashes(image_data: &[u8]) -> Result<PerceptualHashes, JsValue> {
+  let img = decode_image(image_data).map_err(|e| JsValue::from_str(&e))?.image;
+  Ok(PerceptualHashes::from_image(&img))
+}
+
+/// 计算两个感知哈希的汉明距离（WASM 导出）
+#[wasm_bindgen]
+pub fn perceptual_hash_distance(a: String, b: String) -> Result<u32, JsValue> {
+  let a = parse_hash(&a).map_err(|e| JsValue::from_str(&e))?;
+  let b = parse_hash(&b).map_err(|e| JsValue::from_str(&e))?;
+  Ok(hamming_distance(a, b))
+}
+
+/// 均值哈希：8x8 灰度图中高于平均亮度的像素记为 1
+pub fn average_hash(img: &DynamicImage) -> u64 {
+  let gray = grayscale(img, 8, 8);
+  let mean = gray.pixels().map(|p| p.0[0] as u32).sum::<u32>() / 64;
+  bits_from(gray.pixels().map(|p| p.0[0] as u32 > mean))
+}
+
+/// 差值哈希：9x8 灰度图中每个像素比右侧像素亮则记为 1
+pub fn difference_hash(img: &DynamicImage) -> u64 {
+  let gray = grayscale(img, 9, 8);
+  bits_from((0..8).flat_map(|y| {
+    let gray = &gray;
+    (0..8).map(move |x| gray.get_pixel(x, y).0[0] > gray.get_pixel(x + 1, y).0[0])
+  }))
+}
+
+/// DCT 感知哈希：低频系数（不含直流分量）高于中位数记为 1
+pub fn perceptual_hash(img: &DynamicImage) -> u64 {
+  let gray = grayscale(img, DCT_SIZE as u32, DCT_SIZE as u32);
+  let coefficients = low_frequency_dct(&gray);
+
+  let mut sorted: Vec<f64> = coefficients[1..].to_vec();
+  sorted.sort_by(|a, b| a.total_cmp(b));
+  let median = sorted[sorted.len() / 2];
+
+  bits_from(coefficients.iter().map(|&c| c > median))
+}
+
+/// 两个哈希的汉明距离（不同的位数，0-64）
+pub fn hamming_distance(a: u64, b: u64) -> u32 {
+  (a ^ b).count_ones()
+}
+
+/// 哈希格式化为 16 位小写十六进制
+pub fn format_hash(hash: u64) -> String {
+  format!("{:016x}", hash)
+}
+
+/// 解析 16 位十六进制哈希
+pub fn parse_hash(hash: &str) -> Result<u64, String> {
+  if hash.len() != 16 {
+    return Err(format!("Invalid perceptual hash: {}", hash));
+  }
+  u64::from_str_radix(hash, 16).map_err(|_| format!("Invalid perceptual hash: {}", hash))
+}
+
+/// 转为灰度并缩放到指定尺寸
+fn grayscale(img: &DynamicImage, width: u32, height: u32) -> GrayImage {
+  imageops::resize(&img.to_luma8(), width, height, FilterType::Triangle)
+}
+
+/// 按顺序把布尔值打包为 64 位哈希，第一个值为最高位
+fn bits_from(bits: impl Iterator<Item = bool>) -> u64 {
+  bits.take(64).fold(0u64, |hash, bit| (hash << 1) | bit as u64)
+}
+
+/// 二维 DCT-II，只计算左上角 8x8 的低频系数（行优先）
+fn low_frequency_dct(gray: &GrayImage) -> Vec<f64> {
+  let cosines: Vec<f64> = (0..DCT_KEEP)
+    .flat_map(|u| {
+      (0..DCT_SIZE).map(move |x| {
+        (((2 * x + 1) * u) as f64 * std::f64::consts::PI / (2 * DCT_SIZE) as f64).cos()
+      })
+    })
+    .collect();
+
+  // 先对每一行做一维 DCT，再对结果的每一列做一维 DCT
+  let mut rows = vec![0.0f64; DCT_SIZE * DCT_KEEP];
+  for y in 0..DCT_SIZE {
+    for u in 0..DCT_KEEP {
+      rows[y * DCT_KEEP + u] = (0..DCT_SIZE)
+        .map(|x| gray.get_pixel(x as u32, y as u32).0[0] as f64 * cosines[u * DCT_SIZE + x])
+        .sum();
+    }
+  }
+
+  let mut coefficients = vec![0.0f64; DCT_KEEP * DCT_KEEP];
+  for v in 0..DCT_KEEP {
+    for u in 0..DCT_KEEP {
+      coefficients[v * DCT_KEEP + u] = (0..DCT_SIZE)
+        .map(|y| rows[y * DCT_KEEP + u] * cosines[v * DCT_SIZE + y])
+        .sum();
+    }
+  }
+  coefficients
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use image::{ImageBuffer, Rgb, RgbImage};
+
+  /// 带有明暗块和渐变的测试图
+  fn scene(width: u32, height: u32) -> DynamicImage {
+    DynamicImage::ImageRgb8(ImageBuffer::from_fn(width, height, |x, y| {
+      let fx = x as f64 / width as f64;
+      let fy = y as f64 / height as f64;
+      if (0.2..0.5).contains(&fx) && (0.3..0.8).contains(&fy) {
+        Rgb([230, 220, 40])
+      } else {
+        let v = (fx * 160.0 + fy * 60.0) as u8;
+        Rgb([v, v / 2, 255 - v])
+      }
+    }))
+  }
+
+  #[test]
+  fn test_resized_copy_is_similar() {
+    let original = scene(400, 300);
+    let resized = original.resize_exact(120, 90, FilterType::Lanczos3);
+
+    let mut jpeg = Vec::new();
+    resized
+      .write_to(&mut std::io::Cursor::new(&mut jpeg), image::ImageOutputFormat::Jpeg(40))
+      .unwrap();
+    let recompressed = image::load_from_memory(&jpeg).unwrap();
+
+    for hash in [average_hash, difference_hash, perceptual_hash] {
+      assert!(hamming_distance(hash(&original), hash(&recompressed)) <= 6);
+    }
+  }
+
+  #[test]
+  fn test_different_images_are_far_apart() {
+    let original = scene(200, 200);
+    let flipped = original.fliph();
+
+    let stripes: RgbImage = ImageBuffer::from_fn(200, 200, |_, y| {
+      if (y / 25) % 2 == 0 { Rgb([255, 255, 255]) } else { Rgb([0, 0, 0]) }
+    });
+    let stripes = DynamicImage::ImageRgb8(stripes);
+
+    assert!(hamming_distance(perceptual_hash(&original), perceptual_hash(&flipped)) > 10);
+    assert!(hamming_distance(perceptual_hash(&original), perceptual_hash(&stripes)) > 10);
+    assert!(hamming_distance(difference_hash(&original), difference_hash(&flipped)) > 10);
+  }
+
+  #[test]
+  fn test_hash_format_and_distance() {
+    assert_eq!(hamming_distance(0, u64::MAX), 64);
+    assert_eq!(hamming_distance(0b1011, 0b0001), 2);
+    assert_eq!(format_hash(0xAB), "00000000000000ab");
+    assert_eq!(parse_hash("00000000000000ab").unwrap(), 0xAB);
+    assert!(parse_hash("ab").is_err());
+    assert!(parse_hash("zzzzzzzzzzzzzzzz").is_err());
+
+    let data = {
+      let mut buffer = Vec::new();
+      scene(64, 64)
+        .write_to(&mut std::io::Cursor::new(&mut buffer), image::ImageOutputFormat::Png)
+        .unwrap();
+      buffer
+    };
+    let hashes = compute_perceptual_hashes(&data).unwrap();
+    assert_eq!(hashes.perceptual_hash.len(), 16);
+    assert_eq!(
+      perceptual_hash_distance(hashes.average_hash.clone(), hashes.average_hash).unwrap(),
+      0
+    );
+  }
+}
@@ -6,33 +6,77 @@ use wasm_bindgen::prelude::*;
 use image::{DynamicImage, GenericImageView};
 use serde_json;
 
+use crate::info::{color_type_name, container_format_name};
+use crate::metadata;
+use crate::placeholder;
+
+/// 解码后的图片
+pub struct DecodedImage {
+  /// 已按 EXIF 方向校正的图片
+  pub image: DynamicImage,
+  /// 原始 EXIF 方向 (1-8)，没有 EXIF 时为 1
+  pub orientation: u16,
+}
+
+/// 解码图片并按 EXIF 方向校正
+///
+/// 所有需要处理像素的功能都应使用此函数加载图片，保证尺寸调整等操作基于正确的方向
+pub fn decode_image(image_data: &[u8]) -> Result<DecodedImage, String> {
+  let img = image::load_from_memory(image_data)
+    .map_err(|e| format!("Failed to load image: {}", e))?;
+
+  let orientation = metadata::read_exif(image_data)
+    .ok()
+    .flatten()
+    .and_then(|exif| exif.orientation)
+    .unwrap_or(1);
+
+  Ok(DecodedImage {
+    image: metadata::apply_orientation(img, orientation),
+    orientation,
+  })
+}
+
+/// 把 JS 传入的图片数据列表（Uint8Array 或数字数组）转换为字节数组
+///
+/// wasm-bindgen 不支持 `Vec<Vec<u8>>` 参数，批量接口统一用此函数转换
+pub fn bytes_list(values: &[JsValue]) -> Vec<Vec<u8>> {
+  values.iter().map(|value| js_sys::Uint8Array::new(value).to_vec()).collect()
+}
+
 /// 获取图片信息
 #[wasm_bindgen]
 pub fn get_image_info(image_data: &[u8]) -> Result<String, JsValue> {
-  let img = image::load_from_memory(image_data)
-    .map_err(|e| JsValue::from_str(&format!("Failed to load image: {}", e)))?;
+  let DecodedImage { image: img, orientation } = decode_image(image_data)
+    .map_err(|e| JsValue::from_str(&e))?;
 
+  // 宽高为方向校正后的尺寸
   let (width, height) = img.dimensions();
-  let format = match img {
-    DynamicImage::ImageRgb8(_) => "RGB8",
-    DynamicImage::ImageRgba8(_) => "RGBA8",
-    DynamicImage::ImageLuma8(_) => "Luma8",
-    DynamicImage::ImageLumaA8(_) => "LumaA8",
-    _ => "Unknown",
+  let color = img.color();
+
+  // EXIF 解析失败不影响基础信息，错误单独返回
+  let (exif, exif_error) = match metadata::read_exif(image_data) {
+    Ok(exif) => (exif, None),
+    Err(e) => (None, Some(e)),
   };
 
+  // 默认分量数的占位符，用于缩略图加载前显示
+  let placeholders = placeholder::create_placeholders(&img, &Default::default()).ok();
+
   let info = serde_json::json!({
     "width": width,
     "height": height,
-    "format": format,
+    "format": color_type_name(color),
     "size": image_data.len(),
-    "channels": match img {
-      DynamicImage::ImageRgb8(_) => 3,
-      DynamicImage::ImageRgba8(_) => 4,
-      DynamicImage::ImageLuma8(_) => 1,
-      DynamicImage::ImageLumaA8(_) => 2,
-      _ => 0,
-    }
+    "channels": color.channel_count(),
+    "bit_depth": color.bits_per_pixel() / color.channel_count() as u16,
+    "container": container_format_name(image_data),
+    "orientation": orientation,
+    "exif": exif,
+    "exif_error": exif_error,
+    "iptc": metadata::read_iptc(image_data),
+    "blurhash": placeholders.as_ref().map(|p| &p.blurhash),
+    "thumbhash": placeholders.as_ref().map(|p| &p.thumbhash),
   });
 
   Ok(info.to_string())
@@ -83,7 +127,7 @@ pub fn analyze_dominant_colors(img: &DynamicImage) -> Vec<(u8, u8, u8)> {
 
   // 获取最常见的颜色
   let mut colors: Vec<_> = color_counts.into_iter().collect();
-  colors.sort_by(|a, b| b.1.cmp(&a.1));
+  colors.sort_by_key(|c| std::cmp::Reverse(c.1));
 
   colors.into_iter().take(3).map(|(color, _)| color).collect()
 }
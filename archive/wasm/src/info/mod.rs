@@ -0,0 +1,235 @@
+//! 图片结构信息
+//!
+//! 提供比 `get_image_info` 更完整的类型化信息，包括：
+//! - 真实的颜色类型、位深和通道数（含 16 位和浮点图片）
+//! - 是否有透明通道以及透明度是否实际被使用
+//! - 动画帧数和循环次数
+//! - DPI 和物理尺寸
+//! - 隔行扫描 / 渐进式编码和容器格式
+//! - 只解析文件头的快速探测（尺寸、格式、颜色类型、方向）
+
+pub mod probe;
+pub mod structure;
+
+pub use probe::{batch_probe_images, probe_all, probe_image, ImageProbe};
+pub use structure::{read_structure, ContainerStructure};
+
+use wasm_bindgen::prelude::*;
+use image::{ColorType, DynamicImage, GenericImageView, ImageFormat};
+use serde::{Deserialize, Serialize};
+
+use crate::image::decode_image;
+
+/// 图片结构信息
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageDetails {
+  /// 容器格式 (JPEG / PNG / GIF / WebP / BMP / TIFF)
+  pub format: String,
+  /// 宽度（按 EXIF 方向校正后）
+  pub width: u32,
+  /// 高度（按 EXIF 方向校正后）
+  pub height: u32,
+  /// 颜色类型，如 RGB8、RGBA16、RGB32F
+  pub color_type: String,
+  /// 通道数
+  pub channels: u8,
+  /// 每个通道的位深
+  pub bit_depth: u8,
+  /// 是否有透明通道
+  pub has_alpha: bool,
+  /// 是否存在非完全不透明的像素
+  pub alpha_used: bool,
+  /// 是否为动画
+  pub is_animated: bool,
+  /// 帧数（静态图片为 1）
+  pub frame_count: u32,
+  /// 循环次数，0 表示无限循环
+  pub loop_count: Option<u32>,
+  /// 水平 DPI
+  pub dpi_x: Option<f64>,
+  /// 垂直 DPI
+  pub dpi_y: Option<f64>,
+  /// 物理宽度（英寸）
+  pub physical_width: Option<f64>,
+  /// 物理高度（英寸）
+  pub physical_height: Option<f64>,
+  /// 是否隔行扫描或渐进式编码
+  pub interlaced: bool,
+}
+
+/// 获取图片结构信息（WASM 导出）
+#[wasm_bindgen]
+pub fn get_image_details(image_data: &[u8]) -> Result<ImageDetails, JsValue> {
+  read_image_details(image_data).map_err(|e| JsValue::from_str(&e))
+}
+
+/// 解码图片并读取容器结构，生成结构信息
+pub fn read_image_details(image_data: &[u8]) -> Result<ImageDetails, String> {
+  let decoded = decode_image(image_data)?;
+  let img = decoded.image;
+  let (width, height) = img.dimensions();
+  let color = img.color();
+  let structure = read_structure(image_data);
+  // 方向 5-8 旋转了 90 度，DPI 与宽高一起交换
+  let dpi = structure.dpi.map(|(x, y)| if decoded.orientation >= 5 { (y, x) } else { (x, y) });
+
+  Ok(ImageDetails {
+    format: container_format_name(image_data).to_string(),
+    width,
+    height,
+    color_type: color_type_name(color).to_string(),
+    channels: color.channel_count(),
+    bit_depth: (color.bits_per_pixel() / color.channel_count() as u16) as u8,
+    has_alpha: color.has_alpha(),
+    alpha_used: alpha_used(&img),
+    is_animated: structure.is_animated,
+    frame_count: structure.frame_count,
+    loop_count: structure.loop_count,
+    dpi_x: dpi.map(|(x, _)| x),
+    dpi_y: dpi.map(|(_, y)| y),
+    physical_width: dpi.map(|(x, _)| width as f64 / x),
+    physical_height: dpi.map(|(_, y)| height as f64 / y),
+    interlaced: structure.interlaced,
+  })
+}
+
+/// 根据文件头识别容器格式
+pub fn container_format_name(data: &[u8]) -> &'static str {
+  match image::guess_format(data) {
+    Ok(ImageFormat::Jpeg) => "JPEG",
+    Ok(ImageFormat::Png) => "PNG",
+    Ok(ImageFormat::Gif) => "GIF",
+    Ok(ImageFormat::WebP) => "WebP",
+    Ok(ImageFormat::Bmp) => "BMP",
+    Ok(ImageFormat::Tiff) => "TIFF",
+    _ => "Unknown",
+  }
+}
+
+/// 颜色类型名称
+pub fn color_type_name(color: ColorType) -> &'static str {
+  match color {
+    ColorType::L8 => "Luma8",
+    ColorType::La8 => "LumaA8",
+    ColorType::Rgb8 => "RGB8",
+    ColorType::Rgba8 => "RGBA8",
+    ColorType::L16 => "Luma16",
+    ColorType::La16 => "LumaA16",
+    ColorType::Rgb16 => "RGB16",
+    ColorType::Rgba16 => "RGBA16",
+    ColorType::Rgb32F => "RGB32F",
+    ColorType::Rgba32F => "RGBA32F",
+    _ => "Unknown",
+  }
+}
+
+/// 是否存在非完全不透明的像素
+pub fn alpha_used(img: &DynamicImage) -> bool {
+  match img {
+    DynamicImage::ImageLumaA8(buffer) => buffer.pixels().any(|p| p.0[1] < u8::MAX),
+    DynamicImage::ImageRgba8(buffer) => buffer.pixels().any(|p| p.0[3] < u8::MAX),
+    DynamicImage::ImageLumaA16(buffer) => buffer.pixels().any(|p| p.0[1] < u16::MAX),
+    DynamicImage::ImageRgba16(buffer) => buffer.pixels().any(|p| p.0[3] < u16::MAX),
+    DynamicImage::ImageRgba32F(buffer) => buffer.pixels().any(|p| p.0[3] < 1.0),
+    _ => false,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use image::{ImageBuffer, Luma, Rgb, Rgba};
+
+  fn encode(img: DynamicImage, format: image::ImageOutputFormat) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    img.write_to(&mut std::io::Cursor::new(&mut buffer), format).unwrap();
+    buffer
+  }
+
+  #[test]
+  fn test_sixteen_bit_png_details() {
+    let img = ImageBuffer::from_pixel(6, 4, Rgb([1000u16, 20000, 65535]));
+    let data = encode(DynamicImage::ImageRgb16(img), image::ImageOutputFormat::Png);
+
+    let details = read_image_details(&data).unwrap();
+    assert_eq!(details.format, "PNG");
+    assert_eq!((details.width, details.height), (6, 4));
+    assert_eq!(details.color_type, "RGB16");
+    assert_eq!(details.channels, 3);
+    assert_eq!(details.bit_depth, 16);
+    assert!(!details.has_alpha);
+    assert!(!details.alpha_used);
+    assert!(!details.is_animated);
+    assert_eq!(details.frame_count, 1);
+  }
+
+  #[test]
+  fn test_alpha_used() {
+    let opaque = ImageBuffer::from_pixel(4, 4, Rgba([10u8, 20, 30, 255]));
+    let data = encode(DynamicImage::ImageRgba8(opaque.clone()), image::ImageOutputFormat::Png);
+    let details = read_image_details(&data).unwrap();
+    assert!(details.has_alpha);
+    assert!(!details.alpha_used);
+
+    let mut translucent = opaque;
+    translucent.put_pixel(1, 1, Rgba([10, 20, 30, 128]));
+    let data = encode(DynamicImage::ImageRgba8(translucent), image::ImageOutputFormat::Png);
+    assert!(read_image_details(&data).unwrap().alpha_used);
+  }
+
+  #[test]
+  fn test_physical_size() {
+    use crate::metadata::container::build_png_chunk;
+
+    let img = ImageBuffer::from_pixel(600, 300, Luma([128u8]));
+    let mut data = encode(DynamicImage::ImageLuma8(img), image::ImageOutputFormat::Png);
+    // 300 DPI ≈ 11811 像素/米，插入到 IHDR 之后
+    let mut phys = 11811u32.to_be_bytes().to_vec();
+    phys.extend_from_slice(&11811u32.to_be_bytes());
+    phys.push(1);
+    data.splice(33..33, build_png_chunk(b"pHYs", &phys));
+
+    let details = read_image_details(&data).unwrap();
+    assert_eq!(details.color_type, "Luma8");
+    assert!((details.dpi_x.unwrap() - 300.0).abs() < 0.01);
+    assert!((details.physical_width.unwrap() - 2.0).abs() < 0.001);
+    assert!((details.physical_height.unwrap() - 1.0).abs() < 0.001);
+  }
+
+  #[test]
+  fn test_rotated_physical_size() {
+    use crate::metadata::container::build_png_chunk;
+    use crate::metadata::exif::tests::sample_tiff;
+
+    // 存储为 600x300，水平 300 DPI、垂直 150 DPI，EXIF 方向 6 旋转为 300x600
+    let img = ImageBuffer::from_pixel(600, 300, Luma([128u8]));
+    let mut data = encode(DynamicImage::ImageLuma8(img), image::ImageOutputFormat::Png);
+    let mut phys = 11811u32.to_be_bytes().to_vec();
+    phys.extend_from_slice(&5906u32.to_be_bytes());
+    phys.push(1);
+    let mut chunks = build_png_chunk(b"pHYs", &phys);
+    chunks.extend(build_png_chunk(b"eXIf", &sample_tiff()));
+    data.splice(33..33, chunks);
+
+    let details = read_image_details(&data).unwrap();
+    assert_eq!((details.width, details.height), (300, 600));
+    assert!((details.dpi_x.unwrap() - 150.0).abs() < 0.1);
+    assert!((details.dpi_y.unwrap() - 300.0).abs() < 0.1);
+    assert!((details.physical_width.unwrap() - 2.0).abs() < 0.01);
+    assert!((details.physical_height.unwrap() - 2.0).abs() < 0.01);
+  }
+
+  #[test]
+  fn test_color_type_name() {
+    assert_eq!(color_type_name(ColorType::La16), "LumaA16");
+    assert_eq!(color_type_name(ColorType::Rgba32F), "RGBA32F");
+    assert_eq!(container_format_name(b"II*\0\x08\0\0\0"), "TIFF");
+    assert_eq!(container_format_name(b"unknown"), "Unknown");
+  }
+
+  #[test]
+  fn test_invalid_image_details() {
+    assert!(read_image_details(b"not an image").is_err());
+  }
+}
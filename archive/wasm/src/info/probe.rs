@@ -0,0 +1,421 @@
+//! 图片头探测
+//!
+//! 只解析文件头获取尺寸、格式、颜色类型和 EXIF 方向，不解码像素。
+//! 通常只需要文件的前几 KB，数据被截断时返回已读取到的信息
+
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::image::bytes_list;
+use crate::metadata::container::{webp_bitstream_info, JPEG_EXIF_PREFIX, PNG_SIGNATURE};
+use crate::metadata::exif::{TiffReader, TAG_ORIENTATION};
+use crate::metadata::orientation::swaps_dimensions;
+
+// TIFF 图像结构标签
+const TAG_IMAGE_WIDTH: u16 = 0x0100;
+const TAG_IMAGE_LENGTH: u16 = 0x0101;
+const TAG_BITS_PER_SAMPLE: u16 = 0x0102;
+const TAG_PHOTOMETRIC: u16 = 0x0106;
+const TAG_SAMPLES_PER_PIXEL: u16 = 0x0115;
+const TAG_EXTRA_SAMPLES: u16 = 0x0152;
+
+/// 图片头探测结果
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImageProbe {
+  /// 容器格式 (JPEG / PNG / GIF / WebP / BMP / TIFF)
+  pub format: String,
+  /// 文件中存储的宽度
+  pub width: u32,
+  /// 文件中存储的高度
+  pub height: u32,
+  /// 按 EXIF 方向校正后的显示宽度
+  pub display_width: u32,
+  /// 按 EXIF 方向校正后的显示高度
+  pub display_height: u32,
+  /// 颜色类型，如 RGB8、Indexed8、CMYK8
+  pub color_type: String,
+  /// 每个通道的位深
+  pub bit_depth: u8,
+  /// 是否有透明通道
+  pub has_alpha: bool,
+  /// EXIF 方向 (1-8)，没有时为 1
+  pub orientation: u16,
+}
+
+impl ImageProbe {
+  fn new(format: &str, width: u32, height: u32, color_type: String, bit_depth: u8, has_alpha: bool) -> Self {
+    Self {
+      format: format.to_string(),
+      width,
+      height,
+      display_width: width,
+      display_height: height,
+      color_type,
+      bit_depth,
+      has_alpha,
+      orientation: 1,
+    }
+  }
+
+  /// 设置 EXIF 方向并更新显示尺寸
+  fn with_orientation(mut self, orientation: Option<u16>) -> Self {
+    self.orientation = orientation.filter(|o| (1..=8).contains(o)).unwrap_or(1);
+    if swaps_dimensions(self.orientation) {
+      self.display_width = self.height;
+      self.display_height = self.width;
+    }
+    self
+  }
+}
+
+/// 只解析文件头探测图片信息（WASM 导出）
+#[wasm_bindgen]
+pub fn probe_image(image_data: &[u8]) -> Result<ImageProbe, JsValue> {
+  probe(image_data).map_err(|e| JsValue::from_str(&e))
+}
+
+/// 批量探测图片信息（WASM 导出），任意一张失败时返回错误
+#[wasm_bindgen]
+pub fn batch_probe_images(images_data: Vec<JsValue>) -> Result<Vec<ImageProbe>, JsValue> {
+  probe_all(&bytes_list(&images_data)).map_err(|e| JsValue::from_str(&e))
+}
+
+/// 批量探测图片信息，错误信息带图片序号
+pub fn probe_all(images_data: &[Vec<u8>]) -> Result<Vec<ImageProbe>, String> {
+  images_data
+    .iter()
+    .enumerate()
+    .map(|(i, data)| probe(data).map_err(|e| format!("Image {}: {}", i, e)))
+    .collect()
+}
+
+/// 根据文件头探测图片信息
+pub fn probe(data: &[u8]) -> Result<ImageProbe, String> {
+  if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+    probe_jpeg(data)
+  } else if data.starts_with(PNG_SIGNATURE) {
+    probe_png(data)
+  } else if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+    probe_gif(data)
+  } else if data.len() >= 12 && data.starts_with(b"RIFF") && &data[8..12] == b"WEBP" {
+    probe_webp(data)
+  } else if data.starts_with(b"BM") {
+    probe_bmp(data)
+  } else if data.starts_with(b"II\x2A\x00") || data.starts_with(b"MM\x00\x2A") {
+    probe_tiff(data)
+  } else {
+    Err("Unsupported image format".to_string())
+  }
+}
+
+/// 截断错误
+fn truncated(format: &str) -> String {
+  format!("{} header is truncated", format)
+}
+
+/// 从（可能被截断的）TIFF 结构中读取方向
+fn tiff_orientation(tiff: &[u8]) -> Option<u16> {
+  let reader = TiffReader::new(tiff).ok()?;
+  let (entries, _) = reader.read_ifd(reader.first_ifd_offset().ok()?).ok()?;
+  let entry = entries.iter().find(|e| e.tag == TAG_ORIENTATION)?;
+  reader.read_uint(entry, 0).map(|v| v as u16)
+}
+
+/// JPEG：遍历段直到 SOF，途中读取 EXIF 方向
+fn probe_jpeg(data: &[u8]) -> Result<ImageProbe, String> {
+  let mut pos = 2;
+  let mut orientation = None;
+
+  while pos + 4 <= data.len() {
+    if data[pos] != 0xFF {
+      return Err(format!("Invalid JPEG marker at offset {}", pos));
+    }
+    let marker = data[pos + 1];
+    // 填充字节和无长度的标记
+    if marker == 0xFF {
+      pos += 1;
+      continue;
+    }
+    if marker == 0x01 || (0xD0..=0xD8).contains(&marker) {
+      pos += 2;
+      continue;
+    }
+
+    let length = u16::from_be_bytes([data[pos + 2], data[pos + 3]]) as usize;
+    if length < 2 {
+      return Err(format!("Invalid JPEG segment length at offset {}", pos));
+    }
+    // 段可能超出已读取的数据，只取可用部分
+    let payload = &data[pos + 4..(pos + 2 + length).min(data.len())];
+
+    match marker {
+      0xE1 if orientation.is_none() && payload.starts_with(JPEG_EXIF_PREFIX) => {
+        orientation = tiff_orientation(&payload[JPEG_EXIF_PREFIX.len()..]);
+      }
+      // SOF0-SOF15，排除 DHT (C4)、JPG (C8)、DAC (CC)
+      0xC0..=0xCF if !matches!(marker, 0xC4 | 0xC8 | 0xCC) => {
+        if payload.len() < 6 {
+          return Err(truncated("JPEG"));
+        }
+        let precision = payload[0];
+        let height = u16::from_be_bytes([payload[1], payload[2]]) as u32;
+        let width = u16::from_be_bytes([payload[3], payload[4]]) as u32;
+        let color_type = match payload[5] {
+          1 => format!("Luma{}", precision),
+          3 => format!("RGB{}", precision),
+          4 => format!("CMYK{}", precision),
+          n => return Err(format!("Unsupported JPEG component count: {}", n)),
+        };
+        return Ok(ImageProbe::new("JPEG", width, height, color_type, precision, false).with_orientation(orientation));
+      }
+      0xDA | 0xD9 => break,
+      _ => {}
+    }
+
+    pos += 2 + length;
+  }
+
+  Err(truncated("JPEG"))
+}
+
+/// PNG：IHDR 位于签名之后，eXIf 可能出现在 IDAT 之前
+fn probe_png(data: &[u8]) -> Result<ImageProbe, String> {
+  let ihdr = data.get(16..29).filter(|_| data.get(12..16) == Some(b"IHDR")).ok_or_else(|| truncated("PNG"))?;
+  let width = u32::from_be_bytes([ihdr[0], ihdr[1], ihdr[2], ihdr[3]]);
+  let height = u32::from_be_bytes([ihdr[4], ihdr[5], ihdr[6], ihdr[7]]);
+  let bit_depth = ihdr[8];
+  let (color_type, has_alpha) = match ihdr[9] {
+    0 => (format!("Luma{}", bit_depth), false),
+    2 => (format!("RGB{}", bit_depth), false),
+    3 => (format!("Indexed{}", bit_depth), false),
+    4 => (format!("LumaA{}", bit_depth), true),
+    6 => (format!("RGBA{}", bit_depth), true),
+    n => return Err(format!("Invalid PNG color type: {}", n)),
+  };
+
+  let mut pos: usize = 33;
+  let mut orientation = None;
+  let mut indexed_alpha = false;
+  while pos.checked_add(8).is_some_and(|header_end| header_end <= data.len()) {
+    let length = u32::from_be_bytes([data[pos], data[pos + 1], data[pos + 2], data[pos + 3]]) as usize;
+    let payload = &data[pos + 8..pos.saturating_add(8).saturating_add(length).min(data.len())];
+    match &data[pos + 4..pos + 8] {
+      b"eXIf" => orientation = tiff_orientation(payload),
+      // 调色板图片通过 tRNS 提供透明度
+      b"tRNS" => indexed_alpha = true,
+      b"IDAT" | b"IEND" => break,
+      _ => {}
+    }
+    pos = pos.saturating_add(12).saturating_add(length);
+  }
+
+  let has_alpha = has_alpha || indexed_alpha;
+  Ok(ImageProbe::new("PNG", width, height, color_type, bit_depth, has_alpha).with_orientation(orientation))
+}
+
+/// GIF：逻辑屏幕描述符中的画布尺寸
+fn probe_gif(data: &[u8]) -> Result<ImageProbe, String> {
+  let lsd = data.get(6..13).ok_or_else(|| truncated("GIF"))?;
+  let width = u16::from_le_bytes([lsd[0], lsd[1]]) as u32;
+  let height = u16::from_le_bytes([lsd[2], lsd[3]]) as u32;
+  Ok(ImageProbe::new("GIF", width, height, "Indexed8".to_string(), 8, false))
+}
+
+/// WebP：VP8X 画布尺寸，或简单格式中 VP8 / VP8L 的位流头
+fn probe_webp(data: &[u8]) -> Result<ImageProbe, String> {
+  let kind: [u8; 4] = data.get(12..16).and_then(|k| k.try_into().ok()).ok_or_else(|| truncated("WebP"))?;
+  let payload = data.get(20..).unwrap_or_default();
+
+  let (width, height, has_alpha) = if &kind == b"VP8X" {
+    let header = payload.get(..10).ok_or_else(|| truncated("WebP"))?;
+    let width = u32::from_le_bytes([header[4], header[5], header[6], 0]) + 1;
+    let height = u32::from_le_bytes([header[7], header[8], header[9], 0]) + 1;
+    (width, height, header[0] & 0x10 != 0)
+  } else {
+    webp_bitstream_info(&kind, payload).ok_or_else(|| truncated("WebP"))?
+  };
+
+  // EXIF 块通常位于文件末尾，只有在已读取的数据中时才解析方向
+  let mut pos: usize = 12;
+  let mut orientation = None;
+  while pos.checked_add(8).is_some_and(|header_end| header_end <= data.len()) {
+    let length = u32::from_le_bytes([data[pos + 4], data[pos + 5], data[pos + 6], data[pos + 7]]) as usize;
+    if &data[pos..pos + 4] == b"EXIF" {
+      orientation = tiff_orientation(&data[pos + 8..pos.saturating_add(8).saturating_add(length).min(data.len())]);
+      break;
+    }
+    pos = pos.saturating_add(8).saturating_add(length.saturating_add(length & 1));
+  }
+
+  let color_type = if has_alpha { "RGBA8" } else { "RGB8" };
+  Ok(ImageProbe::new("WebP", width, height, color_type.to_string(), 8, has_alpha).with_orientation(orientation))
+}
+
+/// BMP：BITMAPCOREHEADER (12 字节) 或 BITMAPINFOHEADER 及其扩展
+fn probe_bmp(data: &[u8]) -> Result<ImageProbe, String> {
+  let header_size = data
+    .get(14..18)
+    .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    .ok_or_else(|| truncated("BMP"))?;
+
+  let (width, height, bpp) = if header_size == 12 {
+    let b = data.get(18..26).ok_or_else(|| truncated("BMP"))?;
+    (u16::from_le_bytes([b[0], b[1]]) as u32, u16::from_le_bytes([b[2], b[3]]) as u32, u16::from_le_bytes([b[6], b[7]]))
+  } else {
+    let b = data.get(18..30).ok_or_else(|| truncated("BMP"))?;
+    // 高度为负表示自上而下存储
+    let width = i32::from_le_bytes([b[0], b[1], b[2], b[3]]).unsigned_abs();
+    let height = i32::from_le_bytes([b[4], b[5], b[6], b[7]]).unsigned_abs();
+    (width, height, u16::from_le_bytes([b[10], b[11]]))
+  };
+
+  let (color_type, bit_depth, has_alpha) = match bpp {
+    1 | 2 | 4 | 8 => (format!("Indexed{}", bpp), bpp as u8, false),
+    16 => ("RGB5".to_string(), 5, false),
+    24 => ("RGB8".to_string(), 8, false),
+    32 => ("RGBA8".to_string(), 8, true),
+    n => return Err(format!("Unsupported BMP bit count: {}", n)),
+  };
+  Ok(ImageProbe::new("BMP", width, height, color_type, bit_depth, has_alpha))
+}
+
+/// TIFF：IFD0 中的图像结构标签
+fn probe_tiff(data: &[u8]) -> Result<ImageProbe, String> {
+  let reader = TiffReader::new(data)?;
+  let (entries, _) = reader.read_ifd(reader.first_ifd_offset()?)?;
+  let value = |tag: u16| entries.iter().find(|e| e.tag == tag).and_then(|e| reader.read_uint(e, 0));
+
+  let width = value(TAG_IMAGE_WIDTH).ok_or_else(|| truncated("TIFF"))?;
+  let height = value(TAG_IMAGE_LENGTH).ok_or_else(|| truncated("TIFF"))?;
+  let bit_depth = value(TAG_BITS_PER_SAMPLE).unwrap_or(1) as u8;
+  let samples = value(TAG_SAMPLES_PER_PIXEL).unwrap_or(1);
+  let has_alpha = value(TAG_EXTRA_SAMPLES).is_some();
+
+  let color_type = match (value(TAG_PHOTOMETRIC).unwrap_or(1), has_alpha) {
+    (0 | 1, false) => format!("Luma{}", bit_depth),
+    (0 | 1, true) => format!("LumaA{}", bit_depth),
+    (2, false) => format!("RGB{}", bit_depth),
+    (2, true) => format!("RGBA{}", bit_depth),
+    (3, _) => format!("Indexed{}", bit_depth),
+    (5, _) if samples >= 4 => format!("CMYK{}", bit_depth),
+    (6, _) => format!("YCbCr{}", bit_depth),
+    _ => "Unknown".to_string(),
+  };
+
+  let orientation = value(TAG_ORIENTATION).map(|v| v as u16);
+  Ok(ImageProbe::new("TIFF", width, height, color_type, bit_depth, has_alpha).with_orientation(orientation))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::metadata::container::tests::insert_jpeg_app1;
+  use crate::metadata::container::{build_riff_chunk, build_webp, build_vp8x};
+  use crate::metadata::exif::tests::sample_tiff;
+  use image::{DynamicImage, ImageBuffer, Luma, Rgb, Rgba};
+
+  fn encode(img: DynamicImage, format: image::ImageOutputFormat) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    img.write_to(&mut std::io::Cursor::new(&mut buffer), format).unwrap();
+    buffer
+  }
+
+  fn rgb(width: u32, height: u32) -> DynamicImage {
+    DynamicImage::ImageRgb8(ImageBuffer::from_pixel(width, height, Rgb([10, 20, 30])))
+  }
+
+  #[test]
+  fn test_probe_jpeg_with_orientation() {
+    let jpeg = insert_jpeg_app1(&encode(rgb(40, 30), image::ImageOutputFormat::Jpeg(80)), &sample_tiff());
+
+    // 只读取前 2 KB
+    let probe = probe(&jpeg[..2048.min(jpeg.len())]).unwrap();
+    assert_eq!(probe.format, "JPEG");
+    assert_eq!((probe.width, probe.height), (40, 30));
+    assert_eq!(probe.orientation, 6);
+    assert_eq!((probe.display_width, probe.display_height), (30, 40));
+    assert_eq!(probe.color_type, "RGB8");
+
+    let gray = encode(DynamicImage::ImageLuma8(ImageBuffer::from_pixel(8, 8, Luma([1]))), image::ImageOutputFormat::Jpeg(80));
+    assert_eq!(super::probe(&gray).unwrap().color_type, "Luma8");
+  }
+
+  #[test]
+  fn test_probe_png() {
+    let img = DynamicImage::ImageRgba16(ImageBuffer::from_pixel(17, 9, Rgba([1u16, 2, 3, 4])));
+    let data = encode(img, image::ImageOutputFormat::Png);
+
+    let probe = probe(&data[..40]).unwrap();
+    assert_eq!(probe.format, "PNG");
+    assert_eq!((probe.width, probe.height), (17, 9));
+    assert_eq!(probe.color_type, "RGBA16");
+    assert_eq!(probe.bit_depth, 16);
+    assert!(probe.has_alpha);
+    assert_eq!(probe.orientation, 1);
+
+    // IHDR 之后的块长度异常时停止遍历
+    let mut malformed = data[..33].to_vec();
+    malformed.extend_from_slice(b"\xFF\xFF\xFF\xFFtEXt");
+    let probe_malformed = super::probe(&malformed).unwrap();
+    assert_eq!((probe_malformed.width, probe_malformed.height), (17, 9));
+    assert_eq!(probe_malformed.orientation, 1);
+  }
+
+  #[test]
+  fn test_probe_gif_and_bmp() {
+    let gif = encode(rgb(21, 13), image::ImageOutputFormat::Gif);
+    let probe_gif = probe(&gif[..16]).unwrap();
+    assert_eq!((probe_gif.format.as_str(), probe_gif.width, probe_gif.height), ("GIF", 21, 13));
+
+    let bmp = encode(rgb(21, 13), image::ImageOutputFormat::Bmp);
+    let probe_bmp = probe(&bmp[..64]).unwrap();
+    assert_eq!((probe_bmp.format.as_str(), probe_bmp.width, probe_bmp.height), ("BMP", 21, 13));
+    assert_eq!(probe_bmp.color_type, "RGB8");
+  }
+
+  #[test]
+  fn test_probe_webp() {
+    let bits: u32 = 99 | (49 << 14) | (1 << 28);
+    let mut vp8l = vec![0x2F];
+    vp8l.extend_from_slice(&bits.to_le_bytes());
+    let simple = build_webp(&[build_riff_chunk(b"VP8L", &vp8l)]);
+    let probe_simple = probe(&simple).unwrap();
+    assert_eq!((probe_simple.width, probe_simple.height), (100, 50));
+    assert!(probe_simple.has_alpha);
+
+    let extended = build_webp(&[
+      build_riff_chunk(b"VP8X", &build_vp8x(640, 480, 0x08)),
+      build_riff_chunk(b"VP8L", &vp8l),
+      build_riff_chunk(b"EXIF", &sample_tiff()),
+    ]);
+    let probe_extended = probe(&extended).unwrap();
+    assert_eq!((probe_extended.width, probe_extended.height), (640, 480));
+    assert!(!probe_extended.has_alpha);
+    assert_eq!(probe_extended.orientation, 6);
+    assert_eq!(probe_extended.display_width, 480);
+
+    // 块长度异常时停止查找 EXIF
+    let mut malformed = build_webp(&[build_riff_chunk(b"VP8X", &build_vp8x(640, 480, 0x08))]);
+    malformed.extend_from_slice(b"JUNK\xFF\xFF\xFF\xFF");
+    let probe_malformed = probe(&malformed).unwrap();
+    assert_eq!((probe_malformed.width, probe_malformed.height), (640, 480));
+    assert_eq!(probe_malformed.orientation, 1);
+  }
+
+  #[test]
+  fn test_probe_tiff() {
+    let data = encode(rgb(12, 7), image::ImageOutputFormat::Tiff);
+    let probe = probe(&data).unwrap();
+    assert_eq!(probe.format, "TIFF");
+    assert_eq!((probe.width, probe.height), (12, 7));
+    assert_eq!(probe.color_type, "RGB8");
+  }
+
+  #[test]
+  fn test_probe_errors() {
+    assert_eq!(probe(b"not an image").unwrap_err(), "Unsupported image format");
+    assert_eq!(probe(PNG_SIGNATURE).unwrap_err(), "PNG header is truncated");
+    assert_eq!(probe(&[0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x10]).unwrap_err(), "JPEG header is truncated");
+  }
+}
@@ -0,0 +1,364 @@
+//! 容器结构信息
+//!
+//! 直接读取容器头和块结构，获取解码器不提供的信息：
+//! 帧数和循环次数、DPI、隔行扫描 / 渐进式编码
+
+use crate::metadata::container::{jpeg_segments, png_chunks, riff_chunks, ContainerKind};
+use crate::metadata::exif::{TiffReader, TAG_RESOLUTION_UNIT, TAG_X_RESOLUTION, TAG_Y_RESOLUTION};
+use crate::metadata::find_exif;
+
+/// 每英寸的厘米数
+const CM_PER_INCH: f64 = 2.54;
+
+/// 每英寸的米数
+const METERS_PER_INCH: f64 = 0.0254;
+
+/// 容器结构信息
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContainerStructure {
+  /// 帧数（静态图片为 1）
+  pub frame_count: u32,
+  /// 是否为动画
+  pub is_animated: bool,
+  /// 循环次数，0 表示无限循环，None 表示未声明
+  pub loop_count: Option<u32>,
+  /// 水平 / 垂直 DPI
+  pub dpi: Option<(f64, f64)>,
+  /// 是否隔行扫描（PNG Adam7、GIF 隔行）或渐进式 (JPEG)
+  pub interlaced: bool,
+}
+
+impl Default for ContainerStructure {
+  fn default() -> Self {
+    Self {
+      frame_count: 1,
+      is_animated: false,
+      loop_count: None,
+      dpi: None,
+      interlaced: false,
+    }
+  }
+}
+
+/// 读取容器结构信息，结构损坏时返回已读取到的部分
+pub fn read_structure(data: &[u8]) -> ContainerStructure {
+  let mut structure = match ContainerKind::detect(data) {
+    Some(ContainerKind::Jpeg) => jpeg_structure(data),
+    Some(ContainerKind::Png) => png_structure(data),
+    Some(ContainerKind::WebP) => webp_structure(data),
+    _ if data.starts_with(b"GIF8") => gif_structure(data),
+    _ if data.starts_with(b"BM") => bmp_structure(data),
+    _ => ContainerStructure::default(),
+  };
+
+  // 容器没有声明 DPI 时使用 EXIF 中的分辨率（TIFF 文件本身就是 TIFF 结构）
+  if structure.dpi.is_none() {
+    structure.dpi = find_exif(data).and_then(exif_dpi);
+  }
+
+  structure
+}
+
+/// JPEG：SOF 标记判断渐进式，JFIF APP0 中的密度
+fn jpeg_structure(data: &[u8]) -> ContainerStructure {
+  let mut structure = ContainerStructure::default();
+  let segments = match jpeg_segments(data) {
+    Ok(segments) => segments,
+    Err(_) => return structure,
+  };
+
+  for segment in &segments {
+    let payload = segment.payload(data);
+    match segment.marker {
+      // SOF2 / SOF6 / SOF10 / SOF14 为渐进式
+      0xC2 | 0xC6 | 0xCA | 0xCE => structure.interlaced = true,
+      0xE0 if payload.starts_with(b"JFIF\0") && payload.len() >= 12 => {
+        let x = u16::from_be_bytes([payload[8], payload[9]]) as f64;
+        let y = u16::from_be_bytes([payload[10], payload[11]]) as f64;
+        // 单位 0 只表示像素宽高比
+        structure.dpi = match payload[7] {
+          1 => Some((x, y)),
+          2 => Some((x * CM_PER_INCH, y * CM_PER_INCH)),
+          _ => None,
+        }
+        .filter(|(x, y)| *x > 0.0 && *y > 0.0);
+      }
+      _ => {}
+    }
+  }
+
+  structure
+}
+
+/// PNG：IHDR 隔行标记、pHYs 物理尺寸、APNG acTL 帧数
+fn png_structure(data: &[u8]) -> ContainerStructure {
+  let mut structure = ContainerStructure::default();
+  let chunks = match png_chunks(data) {
+    Ok(chunks) => chunks,
+    Err(_) => return structure,
+  };
+
+  for chunk in &chunks {
+    let payload = chunk.payload(data);
+    match &chunk.kind {
+      b"IHDR" if payload.len() >= 13 => structure.interlaced = payload[12] == 1,
+      b"pHYs" if payload.len() >= 9 && payload[8] == 1 => {
+        let x = u32::from_be_bytes([payload[0], payload[1], payload[2], payload[3]]) as f64;
+        let y = u32::from_be_bytes([payload[4], payload[5], payload[6], payload[7]]) as f64;
+        if x > 0.0 && y > 0.0 {
+          structure.dpi = Some((x * METERS_PER_INCH, y * METERS_PER_INCH));
+        }
+      }
+      b"acTL" if payload.len() >= 8 => {
+        structure.frame_count = u32::from_be_bytes([payload[0], payload[1], payload[2], payload[3]]);
+        structure.loop_count = Some(u32::from_be_bytes([payload[4], payload[5], payload[6], payload[7]]));
+        structure.is_animated = true;
+      }
+      _ => {}
+    }
+  }
+
+  structure
+}
+
+/// WebP：VP8X 动画标记、ANIM 循环次数、ANMF 帧数
+fn webp_structure(data: &[u8]) -> ContainerStructure {
+  let mut structure = ContainerStructure::default();
+  let chunks = match riff_chunks(data) {
+    Ok(chunks) => chunks,
+    Err(_) => return structure,
+  };
+
+  let frames = chunks.iter().filter(|c| &c.kind == b"ANMF").count() as u32;
+  if let Some(anim) = chunks.iter().find(|c| &c.kind == b"ANIM") {
+    let payload = anim.payload(data);
+    if payload.len() >= 6 {
+      structure.loop_count = Some(u16::from_le_bytes([payload[4], payload[5]]) as u32);
+    }
+  }
+
+  if frames > 0 {
+    structure.frame_count = frames;
+    structure.is_animated = true;
+  }
+
+  structure
+}
+
+/// GIF：遍历数据块统计帧数，读取 NETSCAPE2.0 扩展中的循环次数
+fn gif_structure(data: &[u8]) -> ContainerStructure {
+  let mut structure = ContainerStructure {
+    frame_count: 0,
+    ..Default::default()
+  };
+  if data.len() < 13 {
+    structure.frame_count = 1;
+    return structure;
+  }
+
+  // 逻辑屏幕描述符之后是可选的全局颜色表
+  let mut pos = 13 + color_table_size(data[10]);
+
+  while let Some(&block) = data.get(pos) {
+    match block {
+      // 图像描述符：10 字节 + 局部颜色表 + LZW 最小码长 + 数据子块
+      0x2C => {
+        let flags = match data.get(pos + 9) {
+          Some(&flags) => flags,
+          None => break,
+        };
+        if structure.frame_count == 0 {
+          structure.interlaced = flags & 0x40 != 0;
+        }
+        structure.frame_count += 1;
+        pos = skip_sub_blocks(data, pos + 10 + color_table_size(flags) + 1);
+      }
+      // 扩展块
+      0x21 => {
+        let label = data.get(pos + 1).copied();
+        let block_start = pos + 2;
+        let app = data.get(block_start + 1..block_start + 12);
+        if label == Some(0xFF) && matches!(app, Some(b"NETSCAPE2.0") | Some(b"ANIMEXTS1.0")) {
+          let sub = block_start + 12;
+          if data.get(sub) == Some(&3) && data.get(sub + 1) == Some(&1) {
+            if let Some(bytes) = data.get(sub + 2..sub + 4) {
+              structure.loop_count = Some(u16::from_le_bytes([bytes[0], bytes[1]]) as u32);
+            }
+          }
+        }
+        pos = skip_sub_blocks(data, block_start);
+      }
+      _ => break,
+    }
+  }
+
+  structure.is_animated = structure.frame_count > 1;
+  structure.frame_count = structure.frame_count.max(1);
+  structure
+}
+
+/// GIF 颜色表字节数（标记字节最高位表示存在，低 3 位为大小）
+fn color_table_size(flags: u8) -> usize {
+  if flags & 0x80 != 0 {
+    3 << ((flags & 0x07) + 1)
+  } else {
+    0
+  }
+}
+
+/// 跳过 GIF 数据子块序列，返回结束后的位置
+fn skip_sub_blocks(data: &[u8], mut pos: usize) -> usize {
+  while let Some(&size) = data.get(pos) {
+    pos += 1;
+    if size == 0 {
+      return pos;
+    }
+    pos += size as usize;
+  }
+  data.len()
+}
+
+/// BMP：信息头中的每米像素数
+fn bmp_structure(data: &[u8]) -> ContainerStructure {
+  let mut structure = ContainerStructure::default();
+  let header_size = data.get(14..18).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]])).unwrap_or(0);
+
+  if header_size >= 40 {
+    if let Some(b) = data.get(38..46) {
+      let x = i32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64;
+      let y = i32::from_le_bytes([b[4], b[5], b[6], b[7]]) as f64;
+      if x > 0.0 && y > 0.0 {
+        structure.dpi = Some((x * METERS_PER_INCH, y * METERS_PER_INCH));
+      }
+    }
+  }
+
+  structure
+}
+
+/// EXIF / TIFF IFD0 中的分辨率
+fn exif_dpi(tiff: &[u8]) -> Option<(f64, f64)> {
+  let reader = TiffReader::new(tiff).ok()?;
+  let (entries, _) = reader.read_ifd(reader.first_ifd_offset().ok()?).ok()?;
+  let find = |tag: u16| entries.iter().find(|e| e.tag == tag);
+
+  let x = reader.read_rational(find(TAG_X_RESOLUTION)?, 0)?;
+  let y = find(TAG_Y_RESOLUTION).and_then(|e| reader.read_rational(e, 0)).unwrap_or(x);
+  // 单位 2 为英寸（默认），3 为厘米，1 表示无单位
+  let scale = match find(TAG_RESOLUTION_UNIT).and_then(|e| reader.read_uint(e, 0)) {
+    Some(1) => return None,
+    Some(3) => CM_PER_INCH,
+    _ => 1.0,
+  };
+
+  Some((x * scale, y * scale)).filter(|(x, y)| *x > 0.0 && *y > 0.0)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::metadata::container::{build_jpeg_segment, build_png_chunk, build_riff_chunk, build_webp, PNG_SIGNATURE};
+  use image::codecs::gif::{GifEncoder, Repeat};
+  use image::{Delay, Frame, ImageBuffer, Rgba, RgbaImage};
+
+  #[test]
+  fn test_jpeg_structure() {
+    let mut jfif = b"JFIF\0\x01\x02\x02".to_vec();
+    jfif.extend_from_slice(&[0, 100, 0, 50, 0, 0]);
+
+    let mut data = vec![0xFF, 0xD8];
+    data.extend(build_jpeg_segment(0xE0, &jfif).unwrap());
+    data.extend(build_jpeg_segment(0xC2, &[8, 0, 1, 0, 1, 1, 1, 0x11, 0]).unwrap());
+    data.extend_from_slice(&[0xFF, 0xDA, 0x00, 0x02, 0xFF, 0xD9]);
+
+    let structure = read_structure(&data);
+    assert!(structure.interlaced);
+    assert_eq!(structure.dpi, Some((254.0, 127.0)));
+    assert_eq!(structure.frame_count, 1);
+    assert!(!structure.is_animated);
+  }
+
+  #[test]
+  fn test_png_structure() {
+    let mut ihdr = [0u8; 13];
+    ihdr[12] = 1;
+    let mut phys = 11811u32.to_be_bytes().to_vec();
+    phys.extend_from_slice(&11811u32.to_be_bytes());
+    phys.push(1);
+    let mut actl = 3u32.to_be_bytes().to_vec();
+    actl.extend_from_slice(&0u32.to_be_bytes());
+
+    let mut data = PNG_SIGNATURE.to_vec();
+    data.extend(build_png_chunk(b"IHDR", &ihdr));
+    data.extend(build_png_chunk(b"pHYs", &phys));
+    data.extend(build_png_chunk(b"acTL", &actl));
+    data.extend(build_png_chunk(b"IEND", &[]));
+
+    let structure = read_structure(&data);
+    assert!(structure.interlaced);
+    assert!(structure.is_animated);
+    assert_eq!(structure.frame_count, 3);
+    assert_eq!(structure.loop_count, Some(0));
+    let (dpi_x, _) = structure.dpi.unwrap();
+    assert!((dpi_x - 300.0).abs() < 0.01);
+  }
+
+  #[test]
+  fn test_animated_gif_structure() {
+    let mut data = Vec::new();
+    {
+      let mut encoder = GifEncoder::new(&mut data);
+      encoder.set_repeat(Repeat::Finite(2)).unwrap();
+      for color in [[255, 0, 0, 255], [0, 255, 0, 255], [0, 0, 255, 255]] {
+        let img: RgbaImage = ImageBuffer::from_pixel(4, 4, Rgba(color));
+        encoder.encode_frame(Frame::from_parts(img, 0, 0, Delay::from_numer_denom_ms(100, 1))).unwrap();
+      }
+    }
+
+    let structure = read_structure(&data);
+    assert_eq!(structure.frame_count, 3);
+    assert!(structure.is_animated);
+    assert_eq!(structure.loop_count, Some(2));
+    assert!(!structure.interlaced);
+  }
+
+  #[test]
+  fn test_animated_webp_structure() {
+    let data = build_webp(&[
+      build_riff_chunk(b"VP8X", &[0x02, 0, 0, 0, 3, 0, 0, 3, 0, 0]),
+      build_riff_chunk(b"ANIM", &[0, 0, 0, 0, 5, 0]),
+      build_riff_chunk(b"ANMF", &[0; 16]),
+      build_riff_chunk(b"ANMF", &[0; 16]),
+    ]);
+
+    let structure = read_structure(&data);
+    assert_eq!(structure.frame_count, 2);
+    assert!(structure.is_animated);
+    assert_eq!(structure.loop_count, Some(5));
+  }
+
+  #[test]
+  fn test_exif_dpi_fallback() {
+    use crate::metadata::container::tests::build_jpeg_with_app1;
+    use crate::metadata::exif::tests::{build_tiff, TestValue};
+
+    let tiff = build_tiff(
+      vec![
+        (TAG_X_RESOLUTION, TestValue::Rationals(vec![(118, 1)])),
+        (TAG_Y_RESOLUTION, TestValue::Rationals(vec![(118, 1)])),
+        (TAG_RESOLUTION_UNIT, TestValue::Short(3)),
+      ],
+      vec![],
+      vec![],
+    );
+    let (dpi_x, dpi_y) = read_structure(&build_jpeg_with_app1(&tiff)).dpi.unwrap();
+    assert!((dpi_x - 299.72).abs() < 0.01);
+    assert_eq!(dpi_x, dpi_y);
+  }
+
+  #[test]
+  fn test_unknown_structure() {
+    assert_eq!(read_structure(b"not an image"), ContainerStructure::default());
+    assert_eq!(read_structure(b"GIF89a").frame_count, 1);
+  }
+}
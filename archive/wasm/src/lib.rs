@@ -16,6 +16,24 @@ use wasm_bindgen::prelude::*;
 // pub mod compress;
 // pub mod analyze;
 // pub mod edit;
+// pub mod metadata;
+// pub mod info;
+// pub mod hash;
+// pub mod placeholder;
+
+// 尚未导出的模块先在测试构建中编译，保证 cargo test / clippy 覆盖这些代码
+#[cfg(test)]
+pub mod convert;
+#[cfg(test)]
+pub mod image;
+#[cfg(test)]
+pub mod metadata;
+#[cfg(test)]
+pub mod info;
+#[cfg(test)]
+pub mod hash;
+#[cfg(test)]
+pub mod placeholder;
 
 // // 重新导出主要功能
 // pub use image::*;
@@ -0,0 +1,518 @@
+//! 元数据迁移
+//!
+//! 格式转换只根据像素生成新文件，会丢失所有元数据。
+//! 这里把源文件中选定的元数据块（EXIF / XMP / ICC / IPTC）按策略处理后写入转换结果：
+//! - JPEG：EXIF APP1、XMP APP1、ICC APP2、IPTC APP13
+//! - PNG：eXIf、iTXt (XMP)、iCCP
+//! - WebP：EXIF、XMP、ICCP（必要时补充 VP8X 头）
+//!
+//! PNG 和 WebP 没有标准的 IPTC 存放位置，IPTC 只写入 JPEG；扩展 XMP 同样只写入 JPEG
+
+use crate::metadata::container::{
+  build_jpeg_segment, build_png_chunk, build_riff_chunk, build_vp8x, build_webp, jpeg_segments, png_chunks,
+  riff_chunks, webp_bitstream_info, ContainerKind, JPEG_EXIF_PREFIX, WEBP_FLAG_ALPHA,
+};
+use crate::metadata::icc::{build_iccp, build_jpeg_icc_segments, find_icc, JPEG_ICC_PREFIX};
+use crate::metadata::iptc::{build_jpeg_photoshop_segments, find_iptc, photoshop_irb, replace_iptc_resource, JPEG_PHOTOSHOP_PREFIX};
+use crate::metadata::orientation::reset_orientation;
+use crate::metadata::strip::{apply_policy_to_exif, apply_policy_to_xmp, MetadataPolicy};
+use crate::metadata::xmp::{
+  self, build_itxt_xmp, build_jpeg_xmp_segments, find_extended_xmp, find_xmp, JPEG_EXTENDED_XMP_PREFIX, JPEG_XMP_PREFIX,
+  MAX_STANDARD_XMP_LENGTH,
+};
+use crate::metadata::find_exif;
+
+/// 可迁移的元数据块类型
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MetadataBlockKind {
+  Exif,
+  Xmp,
+  Icc,
+  Iptc,
+}
+
+impl MetadataBlockKind {
+  /// 全部类型
+  pub const ALL: [MetadataBlockKind; 4] = [
+    MetadataBlockKind::Exif,
+    MetadataBlockKind::Xmp,
+    MetadataBlockKind::Icc,
+    MetadataBlockKind::Iptc,
+  ];
+
+  /// 从字符串解析类型
+  pub fn from_string(kind_str: &str) -> Option<Self> {
+    match kind_str.trim().to_lowercase().as_str() {
+      "exif" => Some(MetadataBlockKind::Exif),
+      "xmp" => Some(MetadataBlockKind::Xmp),
+      "icc" | "iccp" => Some(MetadataBlockKind::Icc),
+      "iptc" => Some(MetadataBlockKind::Iptc),
+      _ => None,
+    }
+  }
+
+  /// 解析逗号分隔的类型列表，如 "exif,icc"，"all" 表示全部
+  pub fn parse_list(list: &str) -> Result<Vec<Self>, String> {
+    if list.trim().eq_ignore_ascii_case("all") {
+      return Ok(Self::ALL.to_vec());
+    }
+
+    list
+      .split(',')
+      .filter(|s| !s.trim().is_empty())
+      .map(|s| Self::from_string(s).ok_or_else(|| format!("Unsupported metadata block: {}", s.trim())))
+      .collect()
+  }
+}
+
+/// 需要写入目标文件的元数据
+#[derive(Debug, Clone, Default)]
+pub struct MetadataBlocks {
+  /// EXIF（TIFF 结构）
+  pub exif: Option<Vec<u8>>,
+  /// XMP 数据包
+  pub xmp: Option<Vec<u8>>,
+  /// 扩展 XMP（只写入 JPEG，GUID 按内容重新计算）
+  pub xmp_extended: Option<Vec<u8>>,
+  /// ICC 配置（未压缩）
+  pub icc: Option<Vec<u8>>,
+  /// IPTC-IIM 数据集
+  pub iptc: Option<Vec<u8>>,
+}
+
+impl MetadataBlocks {
+  /// 是否没有任何元数据
+  pub fn is_empty(&self) -> bool {
+    self.exif.is_none() && self.xmp.is_none() && self.icc.is_none() && self.iptc.is_none()
+  }
+}
+
+/// 把源文件中选定的元数据按策略写入转换后的文件，返回新文件和未能写入的元数据说明
+///
+/// 策略作用于 EXIF、XMP 和 IPTC（strip_private 保留 IPTC），ICC 不含隐私信息，选中即迁移。
+/// 转换时像素已按 EXIF 方向校正，写入前会把方向重置为 1。
+/// 目标格式不支持写入元数据时原样返回
+pub fn carry_metadata(
+  source: &[u8],
+  target: &[u8],
+  policy: MetadataPolicy,
+  kinds: &[MetadataBlockKind],
+) -> Result<(Vec<u8>, Vec<String>), String> {
+  let blocks = collect_blocks(source, policy, kinds);
+  if blocks.is_empty() {
+    return Ok((target.to_vec(), Vec::new()));
+  }
+
+  let mut warnings = Vec::new();
+  let output = embed_blocks(target, &blocks, &mut warnings)?;
+  Ok((output, warnings))
+}
+
+/// 从源文件中提取选定的元数据并按策略处理
+pub fn collect_blocks(source: &[u8], policy: MetadataPolicy, kinds: &[MetadataBlockKind]) -> MetadataBlocks {
+  let mut removed = Vec::new();
+  let mut blocks = MetadataBlocks::default();
+
+  if kinds.contains(&MetadataBlockKind::Exif) {
+    // TIFF 文件本身就是 TIFF 结构，整体复制会带上像素数据
+    blocks.exif = match ContainerKind::detect(source) {
+      Some(ContainerKind::Tiff) => None,
+      _ => find_exif(source),
+    }
+    .and_then(|tiff| apply_policy_to_exif(tiff, policy, &mut removed))
+    .map(|mut tiff| {
+      reset_orientation(&mut tiff);
+      tiff
+    });
+  }
+
+  if kinds.contains(&MetadataBlockKind::Xmp) {
+    let packet = find_xmp(source);
+    let packet = packet.as_deref();
+    // 扩展 XMP 只在 keep_all 下迁移，隐私模式会整块删除
+    if policy == MetadataPolicy::KeepAll {
+      blocks.xmp_extended = packet
+        .and_then(|packet| std::str::from_utf8(packet).ok())
+        .and_then(xmp::extended_xmp_guid)
+        .and_then(|guid| find_extended_xmp(source, guid));
+    }
+    // 指向扩展 XMP 的 GUID 在写入时重新生成
+    blocks.xmp = packet
+      .and_then(|packet| apply_policy_to_xmp(packet, policy, &mut removed))
+      .map(|packet| match String::from_utf8(packet) {
+        Ok(text) => {
+          let (text, _) = xmp::remove_properties(&text, |name| name == "tiff:Orientation" || name == xmp::HAS_EXTENDED_XMP);
+          text.into_bytes()
+        }
+        Err(e) => e.into_bytes(),
+      });
+  }
+
+  if kinds.contains(&MetadataBlockKind::Icc) {
+    blocks.icc = find_icc(source);
+  }
+
+  if kinds.contains(&MetadataBlockKind::Iptc) && policy != MetadataPolicy::StripAll {
+    blocks.iptc = find_iptc(source);
+  }
+
+  blocks
+}
+
+/// 把元数据写入目标文件，目标中已有的同类元数据会被替换
+///
+/// 目标格式放不下而被丢弃的元数据记录到 warnings
+pub fn embed_blocks(target: &[u8], blocks: &MetadataBlocks, warnings: &mut Vec<String>) -> Result<Vec<u8>, String> {
+  let kind = ContainerKind::detect(target);
+  if blocks.xmp_extended.is_some() && matches!(kind, Some(ContainerKind::Png | ContainerKind::WebP)) {
+    warnings.push("Extended XMP dropped: only supported in JPEG".to_string());
+  }
+
+  match kind {
+    Some(ContainerKind::Jpeg) => embed_jpeg(target, blocks, warnings),
+    Some(ContainerKind::Png) => embed_png(target, blocks),
+    Some(ContainerKind::WebP) => embed_webp(target, blocks),
+    _ => Ok(target.to_vec()),
+  }
+}
+
+/// JPEG APP1 段可容纳的 EXIF 字节数
+const MAX_JPEG_EXIF_LENGTH: usize = 65533 - JPEG_EXIF_PREFIX.len();
+
+/// 写入 JPEG：EXIF、XMP、ICC、IPTC 放在 SOI 和 JFIF 段之后
+fn embed_jpeg(target: &[u8], blocks: &MetadataBlocks, warnings: &mut Vec<String>) -> Result<Vec<u8>, String> {
+  let segments = jpeg_segments(target)?;
+  let mut output = vec![0xFF, 0xD8];
+  let mut rest_start = 2;
+
+  if let Some(app0) = segments.first().filter(|s| s.marker == 0xE0) {
+    output.extend_from_slice(&target[app0.offset..app0.offset + app0.length]);
+    rest_start = app0.offset + app0.length;
+  }
+
+  if let Some(tiff) = &blocks.exif {
+    // EXIF 不能跨段存放，放不下时丢弃
+    if tiff.len() > MAX_JPEG_EXIF_LENGTH {
+      warnings.push(format!("EXIF dropped: {} bytes exceeds the JPEG segment limit", tiff.len()));
+    } else {
+      output.extend(build_jpeg_segment(0xE1, &[JPEG_EXIF_PREFIX, tiff].concat())?);
+    }
+  }
+  // 写入新的扩展 XMP 时，目标中原有的扩展段失去指向，一并替换
+  let mut replaces_extended = false;
+  if let Some(packet) = &blocks.xmp {
+    replaces_extended = blocks.xmp_extended.is_some() || packet.len() > MAX_STANDARD_XMP_LENGTH;
+    output.extend(build_jpeg_xmp_segments(packet, blocks.xmp_extended.as_deref(), warnings)?);
+  }
+  if let Some(profile) = &blocks.icc {
+    output.extend(build_jpeg_icc_segments(profile)?);
+  }
+  if let Some(iim) = &blocks.iptc {
+    // 目标中其他 Photoshop 资源（分辨率、剪切路径等）保留，只替换 IPTC
+    let irb = replace_iptc_resource(&photoshop_irb(target, &segments), iim);
+    output.extend(build_jpeg_photoshop_segments(&irb)?);
+  }
+
+  // 跳过目标中已有的同类段
+  for segment in segments.iter().filter(|s| s.offset >= rest_start) {
+    let payload = segment.payload(target);
+    let replaced = match segment.marker {
+      0xE1 => {
+        (blocks.exif.is_some() && payload.starts_with(JPEG_EXIF_PREFIX))
+          || (blocks.xmp.is_some() && payload.starts_with(JPEG_XMP_PREFIX))
+          || (replaces_extended && payload.starts_with(JPEG_EXTENDED_XMP_PREFIX))
+      }
+      0xE2 => blocks.icc.is_some() && payload.starts_with(JPEG_ICC_PREFIX),
+      0xED => blocks.iptc.is_some() && payload.starts_with(JPEG_PHOTOSHOP_PREFIX),
+      _ => false,
+    };
+    if segment.marker == 0xDA {
+      output.extend_from_slice(&target[segment.offset..]);
+    } else if !replaced {
+      output.extend_from_slice(&target[segment.offset..segment.offset + segment.length]);
+    }
+  }
+
+  Ok(output)
+}
+
+/// 写入 PNG：iCCP、eXIf、iTXt 放在 IHDR 之后
+fn embed_png(target: &[u8], blocks: &MetadataBlocks) -> Result<Vec<u8>, String> {
+  let chunks = png_chunks(target)?;
+  let mut output = target[..8].to_vec();
+
+  for chunk in &chunks {
+    let replaced = match &chunk.kind {
+      b"eXIf" => blocks.exif.is_some(),
+      // 压缩存放的 XMP 也一并替换，新数据包统一写为未压缩的 iTXt
+      b"iTXt" | b"zTXt" => blocks.xmp.is_some() && chunk.payload(target).starts_with(xmp::PNG_XMP_KEYWORD),
+      // iCCP 和 sRGB 不能同时存在
+      b"iCCP" | b"sRGB" => blocks.icc.is_some(),
+      _ => false,
+    };
+    if !replaced {
+      output.extend_from_slice(&target[chunk.offset..chunk.offset + chunk.length]);
+    }
+
+    if &chunk.kind == b"IHDR" {
+      if let Some(profile) = &blocks.icc {
+        output.extend(build_png_chunk(b"iCCP", &build_iccp(profile)));
+      }
+      if let Some(tiff) = &blocks.exif {
+        output.extend(build_png_chunk(b"eXIf", tiff));
+      }
+      if let Some(packet) = &blocks.xmp {
+        output.extend(build_png_chunk(b"iTXt", &build_itxt_xmp(packet)));
+      }
+    }
+  }
+
+  Ok(output)
+}
+
+/// 写入 WebP：按扩展格式的顺序排列 VP8X、ICCP、图像数据、EXIF、XMP
+fn embed_webp(target: &[u8], blocks: &MetadataBlocks) -> Result<Vec<u8>, String> {
+  let chunks = riff_chunks(target)?;
+  let mut output = Vec::with_capacity(chunks.len() + 4);
+
+  // 简单格式（只有 VP8 / VP8L）需要补充 VP8X 头才能携带元数据
+  match chunks.first() {
+    Some(first) if &first.kind == b"VP8X" => {
+      output.push(target[first.offset..first.offset + first.length].to_vec());
+    }
+    Some(first) => {
+      let (width, height, has_alpha) = webp_bitstream_info(&first.kind, first.payload(target))
+        .ok_or_else(|| "Invalid WebP bitstream header".to_string())?;
+      let flags = if has_alpha { WEBP_FLAG_ALPHA } else { 0 };
+      output.push(build_riff_chunk(b"VP8X", &build_vp8x(width, height, flags)));
+    }
+    None => return Err("WebP has no image data".to_string()),
+  }
+
+  if let Some(profile) = &blocks.icc {
+    output.push(build_riff_chunk(b"ICCP", profile));
+  }
+
+  for chunk in &chunks {
+    let skipped = match &chunk.kind {
+      b"VP8X" => true,
+      b"ICCP" => blocks.icc.is_some(),
+      b"EXIF" => blocks.exif.is_some(),
+      b"XMP " => blocks.xmp.is_some(),
+      _ => false,
+    };
+    if !skipped {
+      output.push(target[chunk.offset..chunk.offset + chunk.length].to_vec());
+    }
+  }
+
+  if let Some(tiff) = &blocks.exif {
+    output.push(build_riff_chunk(b"EXIF", tiff));
+  }
+  if let Some(packet) = &blocks.xmp {
+    output.push(build_riff_chunk(b"XMP ", packet));
+  }
+
+  Ok(build_webp(&output))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::metadata::container::tests::insert_jpeg_app1;
+  use crate::metadata::exif::tests::sample_tiff;
+  use crate::metadata::icc::tests::sample_icc;
+  use crate::metadata::read_exif;
+  use image::{ImageBuffer, Rgb, RgbImage};
+
+  fn encode(format: image::ImageOutputFormat) -> Vec<u8> {
+    let img: RgbImage = ImageBuffer::from_pixel(8, 8, Rgb([90, 120, 150]));
+    let mut buffer = Vec::new();
+    img.write_to(&mut std::io::Cursor::new(&mut buffer), format).unwrap();
+    buffer
+  }
+
+  /// 带 EXIF、XMP 和 ICC 的测试 JPEG
+  fn generate_source_jpeg() -> Vec<u8> {
+    let mut data = insert_jpeg_app1(&encode(image::ImageOutputFormat::Jpeg(80)), &sample_tiff());
+    let mut extra = build_jpeg_segment(0xE1, &[JPEG_XMP_PREFIX, b"<x:xmpmeta tiff:Orientation=\"6\"/>"].concat()).unwrap();
+    extra.extend(build_jpeg_icc_segments(&sample_icc(500)).unwrap());
+    data.splice(2..2, extra);
+    data
+  }
+
+  #[test]
+  fn test_parse_block_list() {
+    assert_eq!(MetadataBlockKind::parse_list("all").unwrap(), MetadataBlockKind::ALL.to_vec());
+    assert_eq!(
+      MetadataBlockKind::parse_list("EXIF, icc").unwrap(),
+      vec![MetadataBlockKind::Exif, MetadataBlockKind::Icc]
+    );
+    assert!(MetadataBlockKind::parse_list("").unwrap().is_empty());
+    assert!(MetadataBlockKind::parse_list("exif,thumbnail").is_err());
+  }
+
+  #[test]
+  fn test_carry_metadata_jpeg_to_png() {
+    let source = generate_source_jpeg();
+    let target = encode(image::ImageOutputFormat::Png);
+
+    let output = carry_metadata(&source, &target, MetadataPolicy::KeepAll, &MetadataBlockKind::ALL).unwrap().0;
+    let exif = read_exif(&output).unwrap().unwrap();
+    assert_eq!(exif.make.as_deref(), Some("Canon"));
+    assert_eq!(exif.orientation, Some(1));
+    assert!(exif.gps.is_some());
+    assert_eq!(find_xmp(&output).as_deref(), Some(&b"<x:xmpmeta/>"[..]));
+    assert_eq!(find_icc(&output), Some(sample_icc(500)));
+    assert!(image::load_from_memory(&output).is_ok());
+
+    let output = carry_metadata(&source, &target, MetadataPolicy::StripPrivate, &MetadataBlockKind::ALL).unwrap().0;
+    let exif = read_exif(&output).unwrap().unwrap();
+    assert!(exif.gps.is_none());
+    assert!(exif.body_serial_number.is_none());
+
+    // 全部删除时只迁移 ICC
+    let output = carry_metadata(&source, &target, MetadataPolicy::StripAll, &MetadataBlockKind::ALL).unwrap().0;
+    assert!(read_exif(&output).unwrap().is_none());
+    assert!(find_icc(&output).is_some());
+
+    let output = carry_metadata(&source, &target, MetadataPolicy::StripAll, &[]).unwrap().0;
+    assert_eq!(output, target);
+  }
+
+  #[test]
+  fn test_carry_selected_blocks_into_jpeg() {
+    let source = generate_source_jpeg();
+    let target = encode(image::ImageOutputFormat::Jpeg(80));
+
+    let output = carry_metadata(&source, &target, MetadataPolicy::KeepAll, &[MetadataBlockKind::Exif]).unwrap().0;
+    let segments = jpeg_segments(&output).unwrap();
+    assert_eq!(segments[0].marker, 0xE0);
+    assert_eq!(segments[1].marker, 0xE1);
+    assert_eq!(read_exif(&output).unwrap().unwrap().model.as_deref(), Some("EOS R5"));
+    assert!(find_xmp(&output).is_none());
+    assert!(find_icc(&output).is_none());
+    assert!(image::load_from_memory(&output).is_ok());
+  }
+
+  #[test]
+  fn test_carry_iptc_into_jpeg_only() {
+    use crate::metadata::iptc::{build_jpeg_iptc_segment, read_iptc};
+    use crate::metadata::iptc::tests::sample_iim;
+
+    let mut source = generate_source_jpeg();
+    let mut app13 = build_jpeg_segment(0xED, &[JPEG_PHOTOSHOP_PREFIX, b"8BIM\x04\x0C\x00\x00\x00\x00\x00\x02th"].concat()).unwrap();
+    app13.extend(build_jpeg_iptc_segment(&sample_iim()).unwrap());
+    source.splice(2..2, app13);
+
+    let target = encode(image::ImageOutputFormat::Jpeg(80));
+    let output = carry_metadata(&source, &target, MetadataPolicy::StripPrivate, &[MetadataBlockKind::Iptc]).unwrap().0;
+    assert_eq!(read_iptc(&output).unwrap().keywords, vec!["日落", "beach"]);
+    // 只写入 IPTC 资源，不带其他 Photoshop 资源
+    let app13_count = jpeg_segments(&output).unwrap().iter().filter(|s| s.marker == 0xED).count();
+    assert_eq!(app13_count, 1);
+    assert!(image::load_from_memory(&output).is_ok());
+
+    let output = carry_metadata(&source, &target, MetadataPolicy::StripAll, &[MetadataBlockKind::Iptc]).unwrap().0;
+    assert_eq!(output, target);
+
+    let png = encode(image::ImageOutputFormat::Png);
+    let output = carry_metadata(&source, &png, MetadataPolicy::KeepAll, &[MetadataBlockKind::Iptc]).unwrap().0;
+    assert_eq!(output, png);
+  }
+
+  #[test]
+  fn test_carry_oversized_blocks_into_jpeg() {
+    let packet = format!(
+      r#"<x:xmpmeta xmlns:x="adobe:ns:meta/"><rdf:RDF><rdf:Description rdf:about="" dc:description="{}"/></rdf:RDF></x:xmpmeta>"#,
+      "a".repeat(100_000)
+    );
+    let mut tiff = sample_tiff();
+    tiff.resize(70_000, 0);
+    let mut source = encode(image::ImageOutputFormat::Png);
+    let mut extra = build_png_chunk(b"eXIf", &tiff);
+    extra.extend(build_png_chunk(b"iTXt", &build_itxt_xmp(packet.as_bytes())));
+    source.splice(33..33, extra);
+
+    let target = encode(image::ImageOutputFormat::Jpeg(80));
+    let kinds = [MetadataBlockKind::Exif, MetadataBlockKind::Xmp];
+    let (output, warnings) = carry_metadata(&source, &target, MetadataPolicy::KeepAll, &kinds).unwrap();
+    assert_eq!(warnings, vec!["EXIF dropped: 70000 bytes exceeds the JPEG segment limit"]);
+    assert!(find_exif(&output).is_none());
+
+    // 主数据包只保留指向扩展 XMP 的 GUID
+    let standard = String::from_utf8(find_xmp(&output).unwrap().into_owned()).unwrap();
+    let guid = xmp::extended_xmp_guid(&standard).unwrap();
+    assert_eq!(find_extended_xmp(&output, guid).unwrap(), packet.as_bytes());
+    assert!(image::load_from_memory(&output).is_ok());
+  }
+
+  #[test]
+  fn test_carry_extended_xmp() {
+    let guid = "0123456789ABCDEF0123456789ABCDEF";
+    let main = format!(
+      r#"<x:xmpmeta><rdf:RDF><rdf:Description rdf:about="" xmpNote:HasExtendedXMP="{}" dc:format="image/jpeg"/></rdf:RDF></x:xmpmeta>"#,
+      guid
+    );
+    let extended: &[u8] = br#"<x:xmpmeta><rdf:RDF><rdf:Description rdf:about="" photoshop:History="edits"/></rdf:RDF></x:xmpmeta>"#;
+
+    let mut source = encode(image::ImageOutputFormat::Jpeg(80));
+    let mut extra = build_jpeg_segment(0xE1, &[JPEG_XMP_PREFIX, main.as_bytes()].concat()).unwrap();
+    // 扩展部分拆成多个段
+    let length = (extended.len() as u32).to_be_bytes();
+    for (index, chunk) in extended.chunks(40).enumerate() {
+      let offset = (index as u32 * 40).to_be_bytes();
+      let payload = [JPEG_EXTENDED_XMP_PREFIX, guid.as_bytes(), &length, &offset, chunk].concat();
+      extra.extend(build_jpeg_segment(0xE1, &payload).unwrap());
+    }
+    source.splice(2..2, extra);
+
+    let target = encode(image::ImageOutputFormat::Jpeg(80));
+    let (output, warnings) = carry_metadata(&source, &target, MetadataPolicy::KeepAll, &[MetadataBlockKind::Xmp]).unwrap();
+    assert!(warnings.is_empty());
+    let standard = String::from_utf8(find_xmp(&output).unwrap().into_owned()).unwrap();
+    assert!(standard.contains("dc:format"));
+    assert_eq!(standard.matches(xmp::HAS_EXTENDED_XMP).count(), 1);
+    let new_guid = xmp::extended_xmp_guid(&standard).unwrap();
+    assert_ne!(new_guid, guid);
+    assert_eq!(find_extended_xmp(&output, new_guid).unwrap(), extended);
+
+    // PNG 无法存放扩展 XMP，主数据包中不留下失效的指向
+    let png = encode(image::ImageOutputFormat::Png);
+    let (output, warnings) = carry_metadata(&source, &png, MetadataPolicy::KeepAll, &[MetadataBlockKind::Xmp]).unwrap();
+    assert_eq!(warnings, vec!["Extended XMP dropped: only supported in JPEG"]);
+    let packet = String::from_utf8(find_xmp(&output).unwrap().into_owned()).unwrap();
+    assert!(packet.contains("dc:format") && !packet.contains(xmp::HAS_EXTENDED_XMP));
+  }
+
+  #[test]
+  fn test_carry_metadata_into_simple_webp() {
+    let source = generate_source_jpeg();
+    let bits: u32 = 7 | (7 << 14);
+    let mut vp8l = vec![0x2F];
+    vp8l.extend_from_slice(&bits.to_le_bytes());
+    let target = build_webp(&[build_riff_chunk(b"VP8L", &vp8l)]);
+
+    let output = carry_metadata(&source, &target, MetadataPolicy::KeepAll, &MetadataBlockKind::ALL).unwrap().0;
+    let kinds: Vec<[u8; 4]> = riff_chunks(&output).unwrap().iter().map(|c| c.kind).collect();
+    assert_eq!(kinds, vec![*b"VP8X", *b"ICCP", *b"VP8L", *b"EXIF", *b"XMP "]);
+
+    let vp8x = riff_chunks(&output).unwrap()[0].payload(&output).to_vec();
+    assert_eq!(vp8x, {
+      let mut expected = build_vp8x(8, 8, 0);
+      expected[0] = 0x20 | 0x08 | 0x04;
+      expected
+    });
+    assert_eq!(read_exif(&output).unwrap().unwrap().make.as_deref(), Some("Canon"));
+  }
+
+  #[test]
+  fn test_carry_metadata_without_source_metadata() {
+    let source = encode(image::ImageOutputFormat::Png);
+    let target = encode(image::ImageOutputFormat::Jpeg(80));
+    assert_eq!(
+      carry_metadata(&source, &target, MetadataPolicy::KeepAll, &MetadataBlockKind::ALL).unwrap().0,
+      target
+    );
+  }
+}
@@ -0,0 +1,479 @@
+//! 图片容器结构解析
+//!
+//! 按段（JPEG marker segment）或块（PNG chunk、RIFF chunk）遍历文件结构，
+//! 用于定位 EXIF 等元数据，不解码像素
+
+/// JPEG EXIF APP1 段的标识前缀
+pub const JPEG_EXIF_PREFIX: &[u8] = b"Exif\0\0";
+
+/// PNG 文件签名
+pub const PNG_SIGNATURE: &[u8] = &[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+
+/// 容器类型
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ContainerKind {
+  Jpeg,
+  Png,
+  WebP,
+  Tiff,
+}
+
+impl ContainerKind {
+  /// 根据文件头识别容器
+  pub fn detect(data: &[u8]) -> Option<Self> {
+    if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+      Some(ContainerKind::Jpeg)
+    } else if data.starts_with(PNG_SIGNATURE) {
+      Some(ContainerKind::Png)
+    } else if data.len() >= 12 && data.starts_with(b"RIFF") && &data[8..12] == b"WEBP" {
+      Some(ContainerKind::WebP)
+    } else if data.starts_with(b"II\x2A\x00") || data.starts_with(b"MM\x00\x2A") {
+      Some(ContainerKind::Tiff)
+    } else {
+      None
+    }
+  }
+}
+
+/// JPEG 段
+#[derive(Debug, Clone)]
+pub struct JpegSegment {
+  /// 标记字节（0xFF 之后的字节）
+  pub marker: u8,
+  /// 段在文件中的起始位置（指向 0xFF）
+  pub offset: usize,
+  /// 段的总长度（包含标记和长度字段）
+  pub length: usize,
+}
+
+impl JpegSegment {
+  /// 段的数据部分（不含标记和长度字段）
+  pub fn payload<'a>(&self, data: &'a [u8]) -> &'a [u8] {
+    &data[self.offset + 4..self.offset + self.length]
+  }
+}
+
+/// 遍历 JPEG 的头部段，直到 SOS（扫描数据开始）为止
+///
+/// 返回的列表不包含 SOI，SOS 作为最后一个元素返回，长度只覆盖 SOS 头
+pub fn jpeg_segments(data: &[u8]) -> Result<Vec<JpegSegment>, String> {
+  if !data.starts_with(&[0xFF, 0xD8]) {
+    return Err("Not a JPEG file".to_string());
+  }
+
+  let mut segments = Vec::new();
+  let mut pos = 2;
+
+  while pos < data.len() {
+    if data[pos] != 0xFF {
+      return Err(format!("Invalid JPEG marker at {}", pos));
+    }
+
+    // 跳过填充字节
+    let mut marker_pos = pos + 1;
+    while marker_pos < data.len() && data[marker_pos] == 0xFF {
+      marker_pos += 1;
+    }
+    let marker = *data
+      .get(marker_pos)
+      .ok_or_else(|| "JPEG ends inside a marker".to_string())?;
+    let offset = marker_pos - 1;
+
+    // 没有长度字段的独立标记
+    if marker == 0x01 || (0xD0..=0xD7).contains(&marker) {
+      pos = marker_pos + 1;
+      continue;
+    }
+    if marker == 0xD9 {
+      break;
+    }
+
+    let length = data
+      .get(marker_pos + 1..marker_pos + 3)
+      .map(|b| u16::from_be_bytes([b[0], b[1]]) as usize)
+      .ok_or_else(|| format!("JPEG segment at {} is truncated", offset))?;
+    if length < 2 || offset + 2 + length > data.len() {
+      return Err(format!("JPEG segment at {} has invalid length {}", offset, length));
+    }
+
+    segments.push(JpegSegment { marker, offset, length: length + 2 });
+    if marker == 0xDA {
+      break;
+    }
+    pos = offset + 2 + length;
+  }
+
+  Ok(segments)
+}
+
+/// 文件块（PNG chunk 或 RIFF chunk）
+#[derive(Debug, Clone)]
+pub struct Chunk {
+  /// 块类型
+  pub kind: [u8; 4],
+  /// 块在文件中的起始位置
+  pub offset: usize,
+  /// 数据部分的起始位置
+  pub data_offset: usize,
+  /// 数据部分的长度
+  pub data_length: usize,
+  /// 块的总长度（包含头部、CRC 或填充字节）
+  pub length: usize,
+}
+
+impl Chunk {
+  /// 块的数据部分
+  pub fn payload<'a>(&self, data: &'a [u8]) -> &'a [u8] {
+    &data[self.data_offset..self.data_offset + self.data_length]
+  }
+}
+
+/// 遍历 PNG 的所有块
+pub fn png_chunks(data: &[u8]) -> Result<Vec<Chunk>, String> {
+  if !data.starts_with(PNG_SIGNATURE) {
+    return Err("Not a PNG file".to_string());
+  }
+
+  let mut chunks = Vec::new();
+  let mut pos = PNG_SIGNATURE.len();
+
+  // 块长度来自文件，所有偏移都用 checked_add 计算，避免在 32 位 usize 上溢出回绕
+  while pos.checked_add(8).is_some_and(|header_end| header_end <= data.len()) {
+    let data_length = u32::from_be_bytes([data[pos], data[pos + 1], data[pos + 2], data[pos + 3]]) as usize;
+    let kind = [data[pos + 4], data[pos + 5], data[pos + 6], data[pos + 7]];
+    let length = data_length
+      .checked_add(12)
+      .filter(|&l| pos.checked_add(l).is_some_and(|chunk_end| chunk_end <= data.len()))
+      .ok_or_else(|| format!("PNG chunk at {} is truncated", pos))?;
+
+    chunks.push(Chunk { kind, offset: pos, data_offset: pos + 8, data_length, length });
+    pos += length;
+    if &kind == b"IEND" {
+      break;
+    }
+  }
+
+  Ok(chunks)
+}
+
+/// 遍历 WebP（RIFF）的所有块
+pub fn riff_chunks(data: &[u8]) -> Result<Vec<Chunk>, String> {
+  if ContainerKind::detect(data) != Some(ContainerKind::WebP) {
+    return Err("Not a WebP file".to_string());
+  }
+
+  let riff_size = u32::from_le_bytes([data[4], data[5], data[6], data[7]]) as usize;
+  let end = riff_size
+    .checked_add(8)
+    .ok_or_else(|| format!("WebP RIFF size {} is too large", riff_size))?
+    .min(data.len());
+
+  let mut chunks = Vec::new();
+  let mut pos: usize = 12;
+
+  // 块长度来自文件，所有偏移都用 checked_add 计算，避免在 32 位 usize 上溢出回绕
+  while pos.checked_add(8).is_some_and(|header_end| header_end <= end) {
+    let kind = [data[pos], data[pos + 1], data[pos + 2], data[pos + 3]];
+    let data_length = u32::from_le_bytes([data[pos + 4], data[pos + 5], data[pos + 6], data[pos + 7]]) as usize;
+    let data_offset = pos + 8;
+    let data_end = data_offset
+      .checked_add(data_length)
+      .filter(|&data_end| data_end <= end)
+      .ok_or_else(|| format!("WebP chunk at {} is truncated", pos))?;
+
+    // 块数据按偶数字节对齐，最后一个块可能缺少填充字节
+    let chunk_end = data_end
+      .checked_add(data_length & 1)
+      .ok_or_else(|| format!("WebP chunk at {} is truncated", pos))?
+      .min(end);
+    chunks.push(Chunk { kind, offset: pos, data_offset, data_length, length: chunk_end - pos });
+    pos = chunk_end;
+  }
+
+  Ok(chunks)
+}
+
+/// 构造 JPEG 段（标记 + 长度 + 数据）
+pub fn build_jpeg_segment(marker: u8, payload: &[u8]) -> Result<Vec<u8>, String> {
+  let length = payload.len() + 2;
+  if length > u16::MAX as usize {
+    return Err(format!("JPEG segment payload is too large ({} bytes)", payload.len()));
+  }
+
+  let mut segment = vec![0xFF, marker];
+  segment.extend_from_slice(&(length as u16).to_be_bytes());
+  segment.extend_from_slice(payload);
+  Ok(segment)
+}
+
+/// 构造 PNG 块（长度 + 类型 + 数据 + CRC）
+pub fn build_png_chunk(kind: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+  let mut chunk = (payload.len() as u32).to_be_bytes().to_vec();
+  chunk.extend_from_slice(kind);
+  chunk.extend_from_slice(payload);
+  let crc = crc32(&chunk[4..]);
+  chunk.extend_from_slice(&crc.to_be_bytes());
+  chunk
+}
+
+/// 构造 RIFF 块（类型 + 长度 + 数据 + 对齐填充）
+pub fn build_riff_chunk(kind: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+  let mut chunk = kind.to_vec();
+  chunk.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+  chunk.extend_from_slice(payload);
+  if payload.len() % 2 == 1 {
+    chunk.push(0);
+  }
+  chunk
+}
+
+/// 用块数据构造完整的 WebP 文件，并按实际包含的块更新 VP8X 标志位
+pub fn build_webp(chunks: &[Vec<u8>]) -> Vec<u8> {
+  let mut body: Vec<u8> = chunks.concat();
+
+  if body.starts_with(b"VP8X") && body.len() >= 9 {
+    let has = |kind: &[u8; 4]| chunks.iter().any(|c| c.starts_with(kind));
+    let mut flags = body[8] & !(WEBP_FLAG_ICC | WEBP_FLAG_EXIF | WEBP_FLAG_XMP);
+    if has(b"ICCP") {
+      flags |= WEBP_FLAG_ICC;
+    }
+    if has(b"EXIF") {
+      flags |= WEBP_FLAG_EXIF;
+    }
+    if has(b"XMP ") {
+      flags |= WEBP_FLAG_XMP;
+    }
+    body[8] = flags;
+  }
+
+  let mut data = b"RIFF".to_vec();
+  data.extend_from_slice(&((body.len() + 4) as u32).to_le_bytes());
+  data.extend_from_slice(b"WEBP");
+  data.extend_from_slice(&body);
+  data
+}
+
+/// VP8X 标志位：ICC 配置
+pub const WEBP_FLAG_ICC: u8 = 0x20;
+/// VP8X 标志位：EXIF
+pub const WEBP_FLAG_EXIF: u8 = 0x08;
+/// VP8X 标志位：XMP
+pub const WEBP_FLAG_XMP: u8 = 0x04;
+
+/// 从 WebP 图像数据块（VP8 / VP8L）中读取画布尺寸和是否含透明通道
+pub fn webp_bitstream_info(kind: &[u8; 4], payload: &[u8]) -> Option<(u32, u32, bool)> {
+  match kind {
+    b"VP8 " => {
+      // 3 字节帧标记 + 起始码 9D 01 2A + 14 位宽高
+      if payload.len() < 10 || payload[3..6] != [0x9D, 0x01, 0x2A] {
+        return None;
+      }
+      let width = u16::from_le_bytes([payload[6], payload[7]]) & 0x3FFF;
+      let height = u16::from_le_bytes([payload[8], payload[9]]) & 0x3FFF;
+      Some((width as u32, height as u32, false))
+    }
+    b"VP8L" => {
+      // 签名 0x2F + 14 位宽-1 + 14 位高-1 + 1 位透明标记
+      if payload.len() < 5 || payload[0] != 0x2F {
+        return None;
+      }
+      let bits = u32::from_le_bytes([payload[1], payload[2], payload[3], payload[4]]);
+      let width = (bits & 0x3FFF) + 1;
+      let height = ((bits >> 14) & 0x3FFF) + 1;
+      Some((width, height, (bits >> 28) & 1 == 1))
+    }
+    _ => None,
+  }
+}
+
+/// 构造 VP8X 块数据
+pub fn build_vp8x(width: u32, height: u32, flags: u8) -> Vec<u8> {
+  let mut payload = vec![flags, 0, 0, 0];
+  payload.extend_from_slice(&(width - 1).to_le_bytes()[..3]);
+  payload.extend_from_slice(&(height - 1).to_le_bytes()[..3]);
+  payload
+}
+
+/// VP8X 标志位：透明通道
+pub const WEBP_FLAG_ALPHA: u8 = 0x10;
+
+/// CRC-32 (IEEE)，用于 PNG 块校验
+pub fn crc32(bytes: &[u8]) -> u32 {
+  let mut crc = 0xFFFF_FFFFu32;
+  for &byte in bytes {
+    crc ^= byte as u32;
+    for _ in 0..8 {
+      let mask = (crc & 1).wrapping_neg();
+      crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+    }
+  }
+  !crc
+}
+
+/// 从图片文件中定位 EXIF 数据，返回 TIFF 结构部分
+///
+/// 支持 JPEG (APP1)、PNG (eXIf)、WebP (EXIF) 以及 TIFF 文件本身
+pub fn find_exif(data: &[u8]) -> Option<&[u8]> {
+  match ContainerKind::detect(data)? {
+    ContainerKind::Jpeg => jpeg_segments(data)
+      .ok()?
+      .iter()
+      .filter(|s| s.marker == 0xE1)
+      .map(|s| s.payload(data))
+      .find(|p| p.starts_with(JPEG_EXIF_PREFIX))
+      .map(|p| &p[JPEG_EXIF_PREFIX.len()..]),
+    ContainerKind::Png => png_chunks(data)
+      .ok()?
+      .iter()
+      .find(|c| &c.kind == b"eXIf")
+      .map(|c| c.payload(data)),
+    ContainerKind::WebP => riff_chunks(data)
+      .ok()?
+      .iter()
+      .find(|c| &c.kind == b"EXIF")
+      .map(|c| {
+        // 部分编码器会保留 JPEG 的 "Exif\0\0" 前缀
+        let payload = c.payload(data);
+        payload.strip_prefix(JPEG_EXIF_PREFIX).unwrap_or(payload)
+      }),
+    ContainerKind::Tiff => Some(data),
+  }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+  use super::*;
+
+  /// 构造带 APP1 EXIF 段的最小 JPEG 头
+  pub(crate) fn build_jpeg_with_app1(tiff: &[u8]) -> Vec<u8> {
+    let mut data = vec![0xFF, 0xD8];
+    data.extend_from_slice(&[0xFF, 0xE0, 0x00, 0x04, 0x00, 0x00]);
+    let length = (2 + JPEG_EXIF_PREFIX.len() + tiff.len()) as u16;
+    data.extend_from_slice(&[0xFF, 0xE1]);
+    data.extend_from_slice(&length.to_be_bytes());
+    data.extend_from_slice(JPEG_EXIF_PREFIX);
+    data.extend_from_slice(tiff);
+    data.extend_from_slice(&[0xFF, 0xDA, 0x00, 0x02, 0x00, 0xFF, 0xD9]);
+    data
+  }
+
+  /// 在已编码的 JPEG 的 SOI 之后插入 APP1 EXIF 段
+  pub(crate) fn insert_jpeg_app1(jpeg: &[u8], tiff: &[u8]) -> Vec<u8> {
+    let length = (2 + JPEG_EXIF_PREFIX.len() + tiff.len()) as u16;
+    let mut data = jpeg[..2].to_vec();
+    data.extend_from_slice(&[0xFF, 0xE1]);
+    data.extend_from_slice(&length.to_be_bytes());
+    data.extend_from_slice(JPEG_EXIF_PREFIX);
+    data.extend_from_slice(tiff);
+    data.extend_from_slice(&jpeg[2..]);
+    data
+  }
+
+  #[test]
+  fn test_detect_container() {
+    assert_eq!(ContainerKind::detect(&[0xFF, 0xD8, 0xFF, 0xE0]), Some(ContainerKind::Jpeg));
+    assert_eq!(ContainerKind::detect(PNG_SIGNATURE), Some(ContainerKind::Png));
+    assert_eq!(ContainerKind::detect(&build_webp(&[])), Some(ContainerKind::WebP));
+    assert_eq!(ContainerKind::detect(b"MM\x00\x2A\x00\x00\x00\x08"), Some(ContainerKind::Tiff));
+    assert_eq!(ContainerKind::detect(b"GIF89a"), None);
+  }
+
+  #[test]
+  fn test_find_exif_in_jpeg() {
+    let data = build_jpeg_with_app1(b"II\x2A\x00test");
+    assert_eq!(find_exif(&data), Some(&b"II\x2A\x00test"[..]));
+
+    let segments = jpeg_segments(&data).unwrap();
+    assert_eq!(segments.iter().map(|s| s.marker).collect::<Vec<_>>(), vec![0xE0, 0xE1, 0xDA]);
+  }
+
+  #[test]
+  fn test_find_exif_in_png() {
+    let mut data = PNG_SIGNATURE.to_vec();
+    data.extend(build_png_chunk(b"IHDR", &[0; 13]));
+    data.extend(build_png_chunk(b"eXIf", b"MM\x00\x2A1234"));
+    data.extend(build_png_chunk(b"IEND", &[]));
+
+    assert_eq!(find_exif(&data), Some(&b"MM\x00\x2A1234"[..]));
+  }
+
+  #[test]
+  fn test_find_exif_in_webp() {
+    let data = build_webp(&[build_riff_chunk(b"VP8X", &[0; 10]), build_riff_chunk(b"EXIF", b"Exif\0\0II\x2A\x00x")]);
+    assert_eq!(find_exif(&data), Some(&b"II\x2A\x00x"[..]));
+
+    let chunks = riff_chunks(&data).unwrap();
+    assert_eq!(chunks.len(), 2);
+    assert_eq!(chunks[1].length, 8 + 12);
+  }
+
+  #[test]
+  fn test_crc32() {
+    assert_eq!(crc32(b""), 0);
+    assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    // IEND 块的 CRC 是固定值
+    assert_eq!(build_png_chunk(b"IEND", &[]), vec![0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xAE, 0x42, 0x60, 0x82]);
+  }
+
+  #[test]
+  fn test_build_webp_updates_vp8x_flags() {
+    let vp8x = build_riff_chunk(b"VP8X", &[WEBP_FLAG_EXIF | WEBP_FLAG_XMP | 0x10, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+    let data = build_webp(&[vp8x, build_riff_chunk(b"XMP ", b"<x/>")]);
+
+    let chunks = riff_chunks(&data).unwrap();
+    assert_eq!(chunks[0].payload(&data)[0], WEBP_FLAG_XMP | 0x10);
+    assert_eq!(chunks.len(), 2);
+  }
+
+  #[test]
+  fn test_webp_bitstream_info() {
+    // VP8L：宽 300、高 200、含透明通道
+    let bits: u32 = 299 | (199 << 14) | (1 << 28);
+    let mut vp8l = vec![0x2F];
+    vp8l.extend_from_slice(&bits.to_le_bytes());
+    assert_eq!(webp_bitstream_info(b"VP8L", &vp8l), Some((300, 200, true)));
+
+    let vp8 = [0x50, 0x01, 0x00, 0x9D, 0x01, 0x2A, 0x40, 0x01, 0xF0, 0x00];
+    assert_eq!(webp_bitstream_info(b"VP8 ", &vp8), Some((320, 240, false)));
+
+    assert_eq!(build_vp8x(320, 240, WEBP_FLAG_ALPHA), vec![0x10, 0, 0, 0, 0x3F, 0x01, 0, 0xEF, 0, 0]);
+  }
+
+  #[test]
+  fn test_truncated_containers() {
+    let mut jpeg = build_jpeg_with_app1(b"II\x2A\x00");
+    jpeg.truncate(10);
+    assert!(jpeg_segments(&jpeg).is_err());
+    assert_eq!(find_exif(&jpeg), None);
+
+    let mut png = PNG_SIGNATURE.to_vec();
+    png.extend_from_slice(&[0, 0, 1, 0, b'e', b'X', b'I', b'f']);
+    assert!(png_chunks(&png).is_err());
+  }
+
+  #[test]
+  fn test_malformed_chunk_sizes() {
+    // 块长度接近 u32::MAX，在 32 位 usize 上相加会回绕
+    let mut webp = b"RIFF".to_vec();
+    webp.extend_from_slice(&u32::MAX.to_le_bytes());
+    webp.extend_from_slice(b"WEBPEXIF");
+    webp.extend_from_slice(&0xFFFF_FFF8u32.to_le_bytes());
+    assert_eq!(webp.len(), 20);
+    assert!(riff_chunks(&webp).is_err());
+    assert_eq!(find_exif(&webp), None);
+
+    let mut webp = b"RIFF".to_vec();
+    webp.extend_from_slice(&u32::MAX.to_le_bytes());
+    webp.extend_from_slice(b"WEBP");
+    assert!(riff_chunks(&webp).unwrap().is_empty());
+
+    for length in [u32::MAX, u32::MAX - 11, 0x8000_0000] {
+      let mut png = PNG_SIGNATURE.to_vec();
+      png.extend_from_slice(&length.to_be_bytes());
+      png.extend_from_slice(b"eXIf");
+      png.extend_from_slice(&[0; 8]);
+      assert!(png_chunks(&png).is_err());
+      assert_eq!(find_exif(&png), None);
+    }
+  }
+}
@@ -0,0 +1,652 @@
+//! EXIF 解析
+//!
+//! 解析 TIFF 结构的 EXIF 数据（IFD0、Exif IFD、GPS IFD），提取相机、曝光、拍摄时间和 GPS 等字段。
+//!
+//! 对损坏数据的处理约定：
+//! - TIFF 头无效（字节序标记或魔数错误、IFD0 偏移越界）时返回 `Err`
+//! - 单个 IFD 或条目越界、类型未知、IFD 循环引用时跳过该部分，并在 `warnings` 中记录原因
+
+use serde::Serialize;
+use std::collections::HashSet;
+
+// IFD0 标签
+pub const TAG_MAKE: u16 = 0x010F;
+pub const TAG_MODEL: u16 = 0x0110;
+pub const TAG_ORIENTATION: u16 = 0x0112;
+pub const TAG_X_RESOLUTION: u16 = 0x011A;
+pub const TAG_Y_RESOLUTION: u16 = 0x011B;
+pub const TAG_RESOLUTION_UNIT: u16 = 0x0128;
+pub const TAG_SOFTWARE: u16 = 0x0131;
+pub const TAG_DATE_TIME: u16 = 0x0132;
+pub const TAG_ARTIST: u16 = 0x013B;
+pub const TAG_COPYRIGHT: u16 = 0x8298;
+pub const TAG_EXIF_IFD: u16 = 0x8769;
+pub const TAG_GPS_IFD: u16 = 0x8825;
+
+// Exif IFD 标签
+pub const TAG_EXPOSURE_TIME: u16 = 0x829A;
+pub const TAG_F_NUMBER: u16 = 0x829D;
+pub const TAG_ISO: u16 = 0x8827;
+pub const TAG_DATE_TIME_ORIGINAL: u16 = 0x9003;
+pub const TAG_OFFSET_TIME_ORIGINAL: u16 = 0x9011;
+pub const TAG_FOCAL_LENGTH: u16 = 0x920A;
+pub const TAG_BODY_SERIAL_NUMBER: u16 = 0xA431;
+pub const TAG_LENS_MAKE: u16 = 0xA433;
+pub const TAG_LENS_MODEL: u16 = 0xA434;
+pub const TAG_LENS_SERIAL_NUMBER: u16 = 0xA435;
+pub const TAG_FOCAL_LENGTH_35MM: u16 = 0xA405;
+pub const TAG_MAKER_NOTE: u16 = 0x927C;
+pub const TAG_CAMERA_OWNER_NAME: u16 = 0xA430;
+pub const TAG_CAMERA_SERIAL_NUMBER: u16 = 0xC62F;
+
+// GPS IFD 标签
+pub const TAG_GPS_LATITUDE_REF: u16 = 0x0001;
+pub const TAG_GPS_LATITUDE: u16 = 0x0002;
+pub const TAG_GPS_LONGITUDE_REF: u16 = 0x0003;
+pub const TAG_GPS_LONGITUDE: u16 = 0x0004;
+pub const TAG_GPS_ALTITUDE_REF: u16 = 0x0005;
+pub const TAG_GPS_ALTITUDE: u16 = 0x0006;
+
+/// 单个 IFD 允许的最大条目数，超过视为损坏
+pub const MAX_IFD_ENTRIES: usize = 1024;
+
+/// EXIF 中提取的结构化信息
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ExifData {
+  /// 相机厂商
+  pub make: Option<String>,
+  /// 相机型号
+  pub model: Option<String>,
+  /// 镜头厂商
+  pub lens_make: Option<String>,
+  /// 镜头型号
+  pub lens_model: Option<String>,
+  /// 处理软件
+  pub software: Option<String>,
+  /// 作者
+  pub artist: Option<String>,
+  /// 版权
+  pub copyright: Option<String>,
+  /// 方向 (1-8)
+  pub orientation: Option<u16>,
+  /// 曝光时间（秒）
+  pub exposure_time: Option<f64>,
+  /// 光圈值
+  pub f_number: Option<f64>,
+  /// ISO 感光度
+  pub iso: Option<u32>,
+  /// 焦距（毫米）
+  pub focal_length: Option<f64>,
+  /// 等效 35mm 焦距（毫米）
+  pub focal_length_35mm: Option<u32>,
+  /// 拍摄时间 (YYYY:MM:DD HH:MM:SS)
+  pub date_time_original: Option<String>,
+  /// 拍摄时间的时区偏移 (+08:00)
+  pub offset_time_original: Option<String>,
+  /// 文件修改时间
+  pub date_time: Option<String>,
+  /// 机身序列号
+  pub body_serial_number: Option<String>,
+  /// 镜头序列号
+  pub lens_serial_number: Option<String>,
+  /// GPS 信息
+  pub gps: Option<GpsInfo>,
+  /// 解析过程中跳过的损坏部分
+  pub warnings: Vec<String>,
+}
+
+/// GPS 信息
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct GpsInfo {
+  /// 纬度（十进制度，南纬为负）
+  pub latitude: Option<f64>,
+  /// 经度（十进制度，西经为负）
+  pub longitude: Option<f64>,
+  /// 海拔（米，海平面以下为负）
+  pub altitude: Option<f64>,
+}
+
+/// 字节序
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ByteOrder {
+  LittleEndian,
+  BigEndian,
+}
+
+impl ByteOrder {
+  /// 按字节序编码 u16
+  pub fn u16_bytes(&self, value: u16) -> [u8; 2] {
+    match self {
+      ByteOrder::LittleEndian => value.to_le_bytes(),
+      ByteOrder::BigEndian => value.to_be_bytes(),
+    }
+  }
+
+  /// 按字节序编码 u32
+  pub fn u32_bytes(&self, value: u32) -> [u8; 4] {
+    match self {
+      ByteOrder::LittleEndian => value.to_le_bytes(),
+      ByteOrder::BigEndian => value.to_be_bytes(),
+    }
+  }
+}
+
+/// IFD 条目
+#[derive(Debug, Clone)]
+pub struct IfdEntry {
+  /// 标签
+  pub tag: u16,
+  /// 数据类型
+  pub field_type: u16,
+  /// 值的个数
+  pub count: u32,
+  /// 条目在 TIFF 数据中的起始位置
+  pub entry_offset: usize,
+  /// 值在 TIFF 数据中的起始位置（值不超过 4 字节时位于条目内部）
+  pub value_offset: usize,
+}
+
+impl IfdEntry {
+  /// 值占用的字节数
+  pub fn value_len(&self) -> usize {
+    type_size(self.field_type).unwrap_or(0) * self.count as usize
+  }
+}
+
+/// TIFF 结构读取器
+pub struct TiffReader<'a> {
+  data: &'a [u8],
+  byte_order: ByteOrder,
+}
+
+impl<'a> TiffReader<'a> {
+  /// 校验 TIFF 头并创建读取器
+  pub fn new(data: &'a [u8]) -> Result<Self, String> {
+    if data.len() < 8 {
+      return Err("TIFF header is truncated".to_string());
+    }
+
+    let byte_order = match &data[0..2] {
+      b"II" => ByteOrder::LittleEndian,
+      b"MM" => ByteOrder::BigEndian,
+      _ => return Err("Invalid TIFF byte order mark".to_string()),
+    };
+
+    let reader = Self { data, byte_order };
+    if reader.read_u16(2) != Some(42) {
+      return Err("Invalid TIFF magic number".to_string());
+    }
+
+    Ok(reader)
+  }
+
+  /// 原始数据
+  pub fn data(&self) -> &'a [u8] {
+    self.data
+  }
+
+  /// 字节序
+  pub fn byte_order(&self) -> ByteOrder {
+    self.byte_order
+  }
+
+  /// IFD0 的偏移
+  pub fn first_ifd_offset(&self) -> Result<usize, String> {
+    let offset = self.read_u32(4).unwrap_or(0) as usize;
+    if offset < 8 || offset.checked_add(2).is_none_or(|end| end > self.data.len()) {
+      return Err(format!("IFD0 offset {} is out of bounds", offset));
+    }
+    Ok(offset)
+  }
+
+  /// 读取 u16
+  pub fn read_u16(&self, offset: usize) -> Option<u16> {
+    let bytes: [u8; 2] = self.data.get(offset..offset.checked_add(2)?)?.try_into().ok()?;
+    Some(match self.byte_order {
+      ByteOrder::LittleEndian => u16::from_le_bytes(bytes),
+      ByteOrder::BigEndian => u16::from_be_bytes(bytes),
+    })
+  }
+
+  /// 读取 u32
+  pub fn read_u32(&self, offset: usize) -> Option<u32> {
+    let bytes: [u8; 4] = self.data.get(offset..offset.checked_add(4)?)?.try_into().ok()?;
+    Some(match self.byte_order {
+      ByteOrder::LittleEndian => u32::from_le_bytes(bytes),
+      ByteOrder::BigEndian => u32::from_be_bytes(bytes),
+    })
+  }
+
+  /// 读取一个 IFD，返回条目列表和下一个 IFD 的偏移（0 表示没有）
+  pub fn read_ifd(&self, offset: usize) -> Result<(Vec<IfdEntry>, usize), String> {
+    let count = self
+      .read_u16(offset)
+      .ok_or_else(|| format!("IFD at {} is out of bounds", offset))? as usize;
+
+    if count > MAX_IFD_ENTRIES {
+      return Err(format!("IFD at {} declares too many entries ({})", offset, count));
+    }
+
+    // count 不超过 MAX_IFD_ENTRIES，只有 offset 来自文件需要检查溢出
+    let entries_end = offset
+      .checked_add(2 + count * 12)
+      .filter(|&end| end <= self.data.len())
+      .ok_or_else(|| format!("IFD at {} is truncated", offset))?;
+
+    let mut entries = Vec::with_capacity(count);
+    for i in 0..count {
+      let entry_offset = offset + 2 + i * 12;
+      if let Some(entry) = self.read_entry(entry_offset) {
+        entries.push(entry);
+      }
+    }
+
+    let next = self.read_u32(entries_end).unwrap_or(0) as usize;
+    Ok((entries, next))
+  }
+
+  /// 读取单个条目，类型未知或值越界时返回 None
+  fn read_entry(&self, entry_offset: usize) -> Option<IfdEntry> {
+    let tag = self.read_u16(entry_offset)?;
+    let field_type = self.read_u16(entry_offset + 2)?;
+    let count = self.read_u32(entry_offset + 4)?;
+
+    let total = type_size(field_type)?.checked_mul(count as usize)?;
+    let value_offset = if total <= 4 {
+      entry_offset + 8
+    } else {
+      self.read_u32(entry_offset + 8)? as usize
+    };
+
+    if value_offset.checked_add(total)? > self.data.len() {
+      return None;
+    }
+
+    Some(IfdEntry { tag, field_type, count, entry_offset, value_offset })
+  }
+
+  /// 读取 ASCII 字符串（去掉结尾的 NUL 和空白）
+  pub fn read_ascii(&self, entry: &IfdEntry) -> Option<String> {
+    if entry.field_type != 2 && entry.field_type != 7 && entry.field_type != 1 {
+      return None;
+    }
+    let bytes = self.data.get(entry.value_offset..entry.value_offset + entry.count as usize)?;
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    let text = String::from_utf8_lossy(&bytes[..end]).trim().to_string();
+    if text.is_empty() {
+      None
+    } else {
+      Some(text)
+    }
+  }
+
+  /// 读取第 index 个整数值（BYTE / SHORT / LONG）
+  pub fn read_uint(&self, entry: &IfdEntry, index: usize) -> Option<u32> {
+    if index >= entry.count as usize {
+      return None;
+    }
+    match entry.field_type {
+      1 | 7 => self.data.get(entry.value_offset + index).map(|&b| b as u32),
+      3 => self.read_u16(entry.value_offset + index * 2).map(|v| v as u32),
+      4 => self.read_u32(entry.value_offset + index * 4),
+      _ => None,
+    }
+  }
+
+  /// 读取第 index 个有理数值（RATIONAL / SRATIONAL），也接受整数类型
+  pub fn read_rational(&self, entry: &IfdEntry, index: usize) -> Option<f64> {
+    if index >= entry.count as usize {
+      return None;
+    }
+    match entry.field_type {
+      5 | 10 => {
+        let at = entry.value_offset + index * 8;
+        let (num, den) = (self.read_u32(at)?, self.read_u32(at + 4)?);
+        let (num, den) = if entry.field_type == 10 {
+          (num as i32 as f64, den as i32 as f64)
+        } else {
+          (num as f64, den as f64)
+        };
+        if den == 0.0 {
+          None
+        } else {
+          Some(num / den)
+        }
+      }
+      _ => self.read_uint(entry, index).map(|v| v as f64),
+    }
+  }
+}
+
+/// 每种 TIFF 数据类型的字节数
+fn type_size(field_type: u16) -> Option<usize> {
+  match field_type {
+    1 | 2 | 6 | 7 => Some(1),
+    3 | 8 => Some(2),
+    4 | 9 | 11 => Some(4),
+    5 | 10 | 12 => Some(8),
+    _ => None,
+  }
+}
+
+/// 解析 TIFF 结构的 EXIF 数据
+pub fn parse_exif(tiff: &[u8]) -> Result<ExifData, String> {
+  let reader = TiffReader::new(tiff)?;
+  let ifd0 = reader.first_ifd_offset()?;
+
+  let mut exif = ExifData::default();
+  let mut visited = HashSet::new();
+
+  let ifd0_entries = read_ifd_checked(&reader, ifd0, "IFD0", &mut visited, &mut exif.warnings);
+  let mut exif_ifd = None;
+  let mut gps_ifd = None;
+
+  for entry in &ifd0_entries {
+    match entry.tag {
+      TAG_MAKE => exif.make = reader.read_ascii(entry),
+      TAG_MODEL => exif.model = reader.read_ascii(entry),
+      TAG_SOFTWARE => exif.software = reader.read_ascii(entry),
+      TAG_ARTIST => exif.artist = reader.read_ascii(entry),
+      TAG_COPYRIGHT => exif.copyright = reader.read_ascii(entry),
+      TAG_DATE_TIME => exif.date_time = reader.read_ascii(entry),
+      TAG_ORIENTATION => {
+        exif.orientation = reader
+          .read_uint(entry, 0)
+          .map(|v| v as u16)
+          .filter(|v| (1..=8).contains(v));
+      }
+      TAG_EXIF_IFD => exif_ifd = reader.read_uint(entry, 0),
+      TAG_GPS_IFD => gps_ifd = reader.read_uint(entry, 0),
+      _ => {}
+    }
+  }
+
+  if let Some(offset) = exif_ifd {
+    let entries = read_ifd_checked(&reader, offset as usize, "Exif IFD", &mut visited, &mut exif.warnings);
+    for entry in &entries {
+      match entry.tag {
+        TAG_EXPOSURE_TIME => exif.exposure_time = reader.read_rational(entry, 0),
+        TAG_F_NUMBER => exif.f_number = reader.read_rational(entry, 0),
+        TAG_ISO => exif.iso = reader.read_uint(entry, 0),
+        TAG_DATE_TIME_ORIGINAL => exif.date_time_original = reader.read_ascii(entry),
+        TAG_OFFSET_TIME_ORIGINAL => exif.offset_time_original = reader.read_ascii(entry),
+        TAG_FOCAL_LENGTH => exif.focal_length = reader.read_rational(entry, 0),
+        TAG_FOCAL_LENGTH_35MM => exif.focal_length_35mm = reader.read_uint(entry, 0),
+        TAG_BODY_SERIAL_NUMBER => exif.body_serial_number = reader.read_ascii(entry),
+        TAG_LENS_MAKE => exif.lens_make = reader.read_ascii(entry),
+        TAG_LENS_MODEL => exif.lens_model = reader.read_ascii(entry),
+        TAG_LENS_SERIAL_NUMBER => exif.lens_serial_number = reader.read_ascii(entry),
+        _ => {}
+      }
+    }
+  }
+
+  if let Some(offset) = gps_ifd {
+    let entries = read_ifd_checked(&reader, offset as usize, "GPS IFD", &mut visited, &mut exif.warnings);
+    let gps = parse_gps(&reader, &entries);
+    if gps != GpsInfo::default() {
+      exif.gps = Some(gps);
+    }
+  }
+
+  Ok(exif)
+}
+
+/// 读取 IFD，检测循环引用，出错时记录警告并返回空列表
+fn read_ifd_checked(
+  reader: &TiffReader,
+  offset: usize,
+  name: &str,
+  visited: &mut HashSet<usize>,
+  warnings: &mut Vec<String>,
+) -> Vec<IfdEntry> {
+  if !visited.insert(offset) {
+    warnings.push(format!("{} at {} creates a loop, skipped", name, offset));
+    return Vec::new();
+  }
+
+  match reader.read_ifd(offset) {
+    Ok((entries, _)) => entries,
+    Err(e) => {
+      warnings.push(format!("{} skipped: {}", name, e));
+      Vec::new()
+    }
+  }
+}
+
+/// 解析 GPS IFD
+fn parse_gps(reader: &TiffReader, entries: &[IfdEntry]) -> GpsInfo {
+  let find = |tag: u16| entries.iter().find(|e| e.tag == tag);
+
+  let coordinate = |value_tag: u16, ref_tag: u16, negative_ref: &str| -> Option<f64> {
+    let entry = find(value_tag)?;
+    let degrees = reader.read_rational(entry, 0)?;
+    let minutes = reader.read_rational(entry, 1).unwrap_or(0.0);
+    let seconds = reader.read_rational(entry, 2).unwrap_or(0.0);
+    let value = degrees + minutes / 60.0 + seconds / 3600.0;
+    let reference = find(ref_tag).and_then(|e| reader.read_ascii(e));
+    Some(if reference.as_deref() == Some(negative_ref) { -value } else { value })
+  };
+
+  let altitude = find(TAG_GPS_ALTITUDE).and_then(|entry| {
+    let value = reader.read_rational(entry, 0)?;
+    let below_sea_level = find(TAG_GPS_ALTITUDE_REF).and_then(|e| reader.read_uint(e, 0)) == Some(1);
+    Some(if below_sea_level { -value } else { value })
+  });
+
+  GpsInfo {
+    latitude: coordinate(TAG_GPS_LATITUDE, TAG_GPS_LATITUDE_REF, "S"),
+    longitude: coordinate(TAG_GPS_LONGITUDE, TAG_GPS_LONGITUDE_REF, "W"),
+    altitude,
+  }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+  use super::*;
+
+  /// 测试用的 IFD 条目值
+  pub(crate) enum TestValue {
+    Ascii(&'static str),
+    Short(u16),
+    Long(u32),
+    Rationals(Vec<(u32, u32)>),
+  }
+
+  /// 构造小端 TIFF 数据：IFD0 + 可选的 Exif IFD 和 GPS IFD
+  pub(crate) fn build_tiff(
+    ifd0: Vec<(u16, TestValue)>,
+    exif_ifd: Vec<(u16, TestValue)>,
+    gps_ifd: Vec<(u16, TestValue)>,
+  ) -> Vec<u8> {
+    let mut ifd0 = ifd0;
+    if !exif_ifd.is_empty() {
+      ifd0.push((TAG_EXIF_IFD, TestValue::Long(0)));
+    }
+    if !gps_ifd.is_empty() {
+      ifd0.push((TAG_GPS_IFD, TestValue::Long(0)));
+    }
+
+    let mut data = b"II\x2A\x00\x08\x00\x00\x00".to_vec();
+    let ifd0_pointer_positions = write_ifd(&mut data, &ifd0);
+
+    for (tag, sub) in [(TAG_EXIF_IFD, &exif_ifd), (TAG_GPS_IFD, &gps_ifd)] {
+      if sub.is_empty() {
+        continue;
+      }
+      let offset = data.len() as u32;
+      if let Some((_, pos)) = ifd0_pointer_positions.iter().find(|(t, _)| *t == tag) {
+        data[*pos..*pos + 4].copy_from_slice(&offset.to_le_bytes());
+      }
+      write_ifd(&mut data, sub);
+    }
+
+    data
+  }
+
+  /// 写入一个 IFD，返回每个条目值字段的位置
+  fn write_ifd(data: &mut Vec<u8>, entries: &[(u16, TestValue)]) -> Vec<(u16, usize)> {
+    let start = data.len();
+    let extra_start = start + 2 + entries.len() * 12 + 4;
+    let mut extra = Vec::new();
+    let mut positions = Vec::new();
+
+    data.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+    for (tag, value) in entries {
+      let (field_type, count, bytes) = match value {
+        TestValue::Ascii(s) => {
+          let mut b = s.as_bytes().to_vec();
+          b.push(0);
+          (2u16, b.len() as u32, b)
+        }
+        TestValue::Short(v) => (3, 1, v.to_le_bytes().to_vec()),
+        TestValue::Long(v) => (4, 1, v.to_le_bytes().to_vec()),
+        TestValue::Rationals(values) => {
+          let b = values
+            .iter()
+            .flat_map(|(n, d)| n.to_le_bytes().into_iter().chain(d.to_le_bytes()))
+            .collect::<Vec<u8>>();
+          (5, values.len() as u32, b)
+        }
+      };
+
+      data.extend_from_slice(&tag.to_le_bytes());
+      data.extend_from_slice(&field_type.to_le_bytes());
+      data.extend_from_slice(&count.to_le_bytes());
+      positions.push((*tag, data.len()));
+      if bytes.len() <= 4 {
+        let mut inline = bytes.clone();
+        inline.resize(4, 0);
+        data.extend_from_slice(&inline);
+      } else {
+        let offset = (extra_start + extra.len()) as u32;
+        data.extend_from_slice(&offset.to_le_bytes());
+        extra.extend_from_slice(&bytes);
+        if extra.len() % 2 == 1 {
+          extra.push(0);
+        }
+      }
+    }
+    data.extend_from_slice(&0u32.to_le_bytes());
+    data.extend_from_slice(&extra);
+
+    positions
+  }
+
+  /// 一份包含常见字段的测试 EXIF
+  pub(crate) fn sample_tiff() -> Vec<u8> {
+    build_tiff(
+      vec![
+        (TAG_MAKE, TestValue::Ascii("Canon")),
+        (TAG_MODEL, TestValue::Ascii("EOS R5")),
+        (TAG_ORIENTATION, TestValue::Short(6)),
+      ],
+      vec![
+        (TAG_EXPOSURE_TIME, TestValue::Rationals(vec![(1, 125)])),
+        (TAG_F_NUMBER, TestValue::Rationals(vec![(28, 10)])),
+        (TAG_ISO, TestValue::Short(400)),
+        (TAG_DATE_TIME_ORIGINAL, TestValue::Ascii("2024:05:01 10:20:30")),
+        (TAG_FOCAL_LENGTH, TestValue::Rationals(vec![(50, 1)])),
+        (TAG_LENS_MODEL, TestValue::Ascii("RF50mm F1.8 STM")),
+        (TAG_BODY_SERIAL_NUMBER, TestValue::Ascii("012345678")),
+      ],
+      vec![
+        (TAG_GPS_LATITUDE_REF, TestValue::Ascii("N")),
+        (TAG_GPS_LATITUDE, TestValue::Rationals(vec![(31, 1), (14, 1), (0, 1)])),
+        (TAG_GPS_LONGITUDE_REF, TestValue::Ascii("W")),
+        (TAG_GPS_LONGITUDE, TestValue::Rationals(vec![(121, 1), (30, 1), (0, 1)])),
+      ],
+    )
+  }
+
+  #[test]
+  fn test_parse_exif_fields() {
+    let exif = parse_exif(&sample_tiff()).unwrap();
+
+    assert_eq!(exif.make.as_deref(), Some("Canon"));
+    assert_eq!(exif.model.as_deref(), Some("EOS R5"));
+    assert_eq!(exif.orientation, Some(6));
+    assert_eq!(exif.exposure_time, Some(1.0 / 125.0));
+    assert_eq!(exif.f_number, Some(2.8));
+    assert_eq!(exif.iso, Some(400));
+    assert_eq!(exif.focal_length, Some(50.0));
+    assert_eq!(exif.date_time_original.as_deref(), Some("2024:05:01 10:20:30"));
+    assert_eq!(exif.lens_model.as_deref(), Some("RF50mm F1.8 STM"));
+    assert!(exif.warnings.is_empty());
+
+    let gps = exif.gps.unwrap();
+    assert!((gps.latitude.unwrap() - 31.2333).abs() < 0.001);
+    assert!((gps.longitude.unwrap() + 121.5).abs() < 0.001);
+    assert_eq!(gps.altitude, None);
+  }
+
+  #[test]
+  fn test_parse_exif_big_endian() {
+    let mut data = b"MM\x00\x2A\x00\x00\x00\x08".to_vec();
+    data.extend_from_slice(&[0x00, 0x01]);
+    data.extend_from_slice(&[0x01, 0x12, 0x00, 0x03, 0x00, 0x00, 0x00, 0x01, 0x00, 0x08, 0x00, 0x00]);
+    data.extend_from_slice(&[0, 0, 0, 0]);
+
+    let exif = parse_exif(&data).unwrap();
+    assert_eq!(exif.orientation, Some(8));
+  }
+
+  #[test]
+  fn test_parse_exif_invalid_header() {
+    assert!(parse_exif(b"XX\x2A\x00\x08\x00\x00\x00").is_err());
+    assert!(parse_exif(b"II\x2B\x00\x08\x00\x00\x00").is_err());
+    assert!(parse_exif(b"II\x2A\x00\xFF\x00\x00\x00").is_err());
+    assert!(parse_exif(b"II").is_err());
+    // 偏移接近 u32::MAX 时不能溢出
+    assert!(parse_exif(b"II\x2A\x00\xFE\xFF\xFF\xFF").is_err());
+
+    let data = sample_tiff();
+    let reader = TiffReader::new(&data).unwrap();
+    assert!(reader.read_ifd(usize::MAX - 1).is_err());
+    assert!(reader.read_ifd(usize::MAX - 3).is_err());
+  }
+
+  #[test]
+  fn test_parse_exif_truncated_sub_ifd() {
+    let mut data = sample_tiff();
+    // 指向数据末尾之外的 Exif IFD
+    let reader = TiffReader::new(&data).unwrap();
+    let (entries, _) = reader.read_ifd(8).unwrap();
+    let pointer = entries.iter().find(|e| e.tag == TAG_EXIF_IFD).unwrap().value_offset;
+    data[pointer..pointer + 4].copy_from_slice(&0xFFFFu32.to_le_bytes());
+
+    let exif = parse_exif(&data).unwrap();
+    assert_eq!(exif.make.as_deref(), Some("Canon"));
+    assert_eq!(exif.iso, None);
+    assert_eq!(exif.warnings.len(), 1);
+  }
+
+  #[test]
+  fn test_parse_exif_ifd_loop() {
+    // Exif IFD 指针指回 IFD0
+    let data = build_tiff(
+      vec![(TAG_MAKE, TestValue::Ascii("Loop")), (TAG_EXIF_IFD, TestValue::Long(8))],
+      vec![],
+      vec![],
+    );
+
+    let exif = parse_exif(&data).unwrap();
+    assert_eq!(exif.make.as_deref(), Some("Loop"));
+    assert!(exif.warnings[0].contains("loop"));
+  }
+
+  #[test]
+  fn test_parse_exif_skips_out_of_bounds_entry() {
+    let mut data = build_tiff(
+      vec![(TAG_MAKE, TestValue::Ascii("Nikon Corporation")), (TAG_ORIENTATION, TestValue::Short(3))],
+      vec![],
+      vec![],
+    );
+    // 把 Make 的值偏移改到越界位置
+    data[18..22].copy_from_slice(&0xFFFF_FF00u32.to_le_bytes());
+
+    let exif = parse_exif(&data).unwrap();
+    assert_eq!(exif.make, None);
+    assert_eq!(exif.orientation, Some(3));
+  }
+}
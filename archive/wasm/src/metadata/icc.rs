@@ -0,0 +1,117 @@
+//! ICC 色彩配置
+//!
+//! 读取和构造 JPEG (APP2)、PNG (iCCP)、WebP (ICCP) 中的 ICC 配置数据
+
+use crate::metadata::container::{build_jpeg_segment, jpeg_segments, png_chunks, riff_chunks, ContainerKind};
+
+/// JPEG ICC APP2 段的标识前缀
+pub const JPEG_ICC_PREFIX: &[u8] = b"ICC_PROFILE\0";
+
+/// 单个 APP2 段可容纳的 ICC 数据长度（65535 - 长度字段 - 前缀 - 序号）
+const JPEG_ICC_CHUNK_SIZE: usize = 65535 - 2 - 14;
+
+/// 解压 iCCP 时允许的最大配置大小
+const MAX_ICC_SIZE: usize = 16 * 1024 * 1024;
+
+/// 从图片文件中读取 ICC 配置（原始数据，不压缩）
+pub fn find_icc(data: &[u8]) -> Option<Vec<u8>> {
+  match ContainerKind::detect(data)? {
+    ContainerKind::Jpeg => {
+      // ICC 可能拆分到多个 APP2 段，按序号拼接
+      let mut parts: Vec<(u8, &[u8])> = jpeg_segments(data)
+        .ok()?
+        .iter()
+        .filter(|s| s.marker == 0xE2)
+        .map(|s| s.payload(data))
+        .filter(|p| p.starts_with(JPEG_ICC_PREFIX) && p.len() > JPEG_ICC_PREFIX.len() + 2)
+        .map(|p| (p[JPEG_ICC_PREFIX.len()], &p[JPEG_ICC_PREFIX.len() + 2..]))
+        .collect();
+      if parts.is_empty() {
+        return None;
+      }
+      parts.sort_by_key(|(sequence, _)| *sequence);
+      Some(parts.into_iter().flat_map(|(_, p)| p.iter().copied()).collect())
+    }
+    ContainerKind::Png => {
+      let chunks = png_chunks(data).ok()?;
+      let payload = chunks.iter().find(|c| &c.kind == b"iCCP")?.payload(data);
+      let name_end = payload.iter().position(|&b| b == 0)?;
+      // 名称之后是压缩方法（只有 0 = zlib）
+      if payload.get(name_end + 1) != Some(&0) {
+        return None;
+      }
+      miniz_oxide::inflate::decompress_to_vec_zlib_with_limit(&payload[name_end + 2..], MAX_ICC_SIZE).ok()
+    }
+    ContainerKind::WebP => riff_chunks(data)
+      .ok()?
+      .iter()
+      .find(|c| &c.kind == b"ICCP")
+      .map(|c| c.payload(data).to_vec()),
+    ContainerKind::Tiff => None,
+  }
+}
+
+/// 构造存放 ICC 配置的 JPEG APP2 段（超过单段容量时拆分）
+pub fn build_jpeg_icc_segments(profile: &[u8]) -> Result<Vec<u8>, String> {
+  let parts: Vec<&[u8]> = profile.chunks(JPEG_ICC_CHUNK_SIZE).collect();
+  if parts.len() > 255 {
+    return Err("ICC profile is too large for JPEG".to_string());
+  }
+
+  let mut segments = Vec::new();
+  for (i, part) in parts.iter().enumerate() {
+    let mut payload = JPEG_ICC_PREFIX.to_vec();
+    payload.extend_from_slice(&[(i + 1) as u8, parts.len() as u8]);
+    payload.extend_from_slice(part);
+    segments.extend(build_jpeg_segment(0xE2, &payload)?);
+  }
+  Ok(segments)
+}
+
+/// 构造 PNG iCCP 块数据
+pub fn build_iccp(profile: &[u8]) -> Vec<u8> {
+  let mut payload = b"ICC Profile\0\0".to_vec();
+  payload.extend(miniz_oxide::deflate::compress_to_vec_zlib(profile, 6));
+  payload
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+  use super::*;
+  use crate::metadata::container::{build_png_chunk, build_riff_chunk, build_webp, PNG_SIGNATURE};
+
+  /// 测试用的 ICC 配置（只需要可识别的字节序列）
+  pub(crate) fn sample_icc(len: usize) -> Vec<u8> {
+    (0..len).map(|i| (i % 251) as u8).collect()
+  }
+
+  #[test]
+  fn test_jpeg_icc_round_trip() {
+    let profile = sample_icc(70_000);
+    let segments = build_jpeg_icc_segments(&profile).unwrap();
+
+    let mut data = vec![0xFF, 0xD8];
+    data.extend(segments);
+    data.extend_from_slice(&[0xFF, 0xDA, 0x00, 0x02, 0xFF, 0xD9]);
+
+    assert_eq!(jpeg_segments(&data).unwrap().len(), 3);
+    assert_eq!(find_icc(&data), Some(profile));
+  }
+
+  #[test]
+  fn test_png_icc_round_trip() {
+    let profile = sample_icc(3000);
+    let mut data = PNG_SIGNATURE.to_vec();
+    data.extend(build_png_chunk(b"IHDR", &[0; 13]));
+    data.extend(build_png_chunk(b"iCCP", &build_iccp(&profile)));
+    data.extend(build_png_chunk(b"IEND", &[]));
+
+    assert_eq!(find_icc(&data), Some(profile));
+  }
+
+  #[test]
+  fn test_webp_icc() {
+    let data = build_webp(&[build_riff_chunk(b"VP8X", &[0x20, 0, 0, 0, 0, 0, 0, 0, 0, 0]), build_riff_chunk(b"ICCP", b"icc")]);
+    assert_eq!(find_icc(&data), Some(b"icc".to_vec()));
+  }
+}
@@ -0,0 +1,628 @@
+//! IPTC-IIM 元数据
+//!
+//! 图库和新闻图片的关键词、说明、作者等信息存放在 JPEG APP13 (Photoshop 3.0) 段的
+//! 8BIM 资源 0x0404 中，格式为 IPTC-IIM 数据集序列
+
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::metadata::carry::{embed_blocks, MetadataBlocks};
+use crate::metadata::container::{build_jpeg_segment, jpeg_segments, ContainerKind, JpegSegment};
+
+/// JPEG Photoshop APP13 段的标识前缀
+pub const JPEG_PHOTOSHOP_PREFIX: &[u8] = b"Photoshop 3.0\0";
+
+/// 存放 IPTC-IIM 的 8BIM 资源 ID
+pub const IPTC_RESOURCE_ID: u16 = 0x0404;
+
+/// IPTC 摘要 (MD5) 的 8BIM 资源 ID，IIM 改变后失效
+pub const IPTC_DIGEST_RESOURCE_ID: u16 = 0x0425;
+
+/// 单个 APP13 段可容纳的图像资源字节数
+const MAX_APP13_IRB_LENGTH: usize = 65533 - JPEG_PHOTOSHOP_PREFIX.len();
+
+/// IIM 数据集标记
+const IIM_TAG_MARKER: u8 = 0x1C;
+
+/// IIM 数据集 (记录号, 数据集号)
+pub const DATASET_CODED_CHARACTER_SET: (u8, u8) = (1, 90);
+pub const DATASET_OBJECT_NAME: (u8, u8) = (2, 5);
+pub const DATASET_KEYWORDS: (u8, u8) = (2, 25);
+pub const DATASET_BY_LINE: (u8, u8) = (2, 80);
+pub const DATASET_CITY: (u8, u8) = (2, 90);
+pub const DATASET_COUNTRY: (u8, u8) = (2, 101);
+pub const DATASET_HEADLINE: (u8, u8) = (2, 105);
+pub const DATASET_CREDIT: (u8, u8) = (2, 110);
+pub const DATASET_SOURCE: (u8, u8) = (2, 115);
+pub const DATASET_COPYRIGHT_NOTICE: (u8, u8) = (2, 116);
+pub const DATASET_CAPTION: (u8, u8) = (2, 120);
+
+/// IIM 记录版本号
+const DATASET_RECORD_VERSION: (u8, u8) = (2, 0);
+
+/// 写入时由 IptcData 重新生成的数据集，其余数据集按原字节保留
+const MODELED_DATASETS: [(u8, u8); 12] = [
+  DATASET_CODED_CHARACTER_SET,
+  DATASET_RECORD_VERSION,
+  DATASET_OBJECT_NAME,
+  DATASET_KEYWORDS,
+  DATASET_BY_LINE,
+  DATASET_CITY,
+  DATASET_COUNTRY,
+  DATASET_HEADLINE,
+  DATASET_CREDIT,
+  DATASET_SOURCE,
+  DATASET_COPYRIGHT_NOTICE,
+  DATASET_CAPTION,
+];
+
+/// UTF-8 字符集声明 (ESC % G)
+const UTF8_CHARSET: &[u8] = b"\x1B%G";
+
+/// 解析后的 IPTC 信息
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct IptcData {
+  /// 标题 (Object Name)
+  pub object_name: Option<String>,
+  /// 大标题
+  pub headline: Option<String>,
+  /// 说明 (Caption/Abstract)
+  pub caption: Option<String>,
+  /// 关键词
+  pub keywords: Vec<String>,
+  /// 作者 (By-line)
+  pub by_line: Vec<String>,
+  /// 署名 (Credit)
+  pub credit: Option<String>,
+  /// 来源
+  pub source: Option<String>,
+  /// 版权声明
+  pub copyright_notice: Option<String>,
+  /// 城市
+  pub city: Option<String>,
+  /// 国家
+  pub country: Option<String>,
+  /// 解析过程中遇到的问题（数据截断等）
+  pub warnings: Vec<String>,
+}
+
+/// 需要写入的 IPTC 字段（WASM 导出）
+///
+/// 写入时未指定的字段保留文件中原有的值
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IptcFields {
+  /// 关键词 JSON string，如 ["风景", "日落"]
+  pub keywords_json: Option<String>,
+  /// 说明 (Caption/Abstract)
+  pub caption: Option<String>,
+  /// 署名 (Credit)
+  pub credit: Option<String>,
+}
+
+impl IptcData {
+  /// 用指定的字段覆盖已有的值，空字符串表示删除该字段
+  pub fn apply_fields(&mut self, fields: &IptcFields) -> Result<(), String> {
+    if let Some(json) = &fields.keywords_json {
+      self.keywords = serde_json::from_str::<Vec<String>>(json).map_err(|e| format!("Invalid keywords JSON: {}", e))?;
+    }
+    if let Some(caption) = &fields.caption {
+      self.caption = Some(caption.clone()).filter(|c| !c.is_empty());
+    }
+    if let Some(credit) = &fields.credit {
+      self.credit = Some(credit.clone()).filter(|c| !c.is_empty());
+    }
+    Ok(())
+  }
+}
+
+/// 从图片文件中定位 IPTC-IIM 数据
+///
+/// 目前只支持 JPEG，多个 APP13 段会按顺序拼接
+pub fn find_iptc(data: &[u8]) -> Option<Vec<u8>> {
+  if ContainerKind::detect(data)? != ContainerKind::Jpeg {
+    return None;
+  }
+
+  let irb = photoshop_irb(data, &jpeg_segments(data).ok()?);
+  image_resources(&irb)
+    .into_iter()
+    .find(|(id, _)| *id == IPTC_RESOURCE_ID)
+    .map(|(_, iim)| iim.to_vec())
+}
+
+/// 拼接 JPEG 中所有 Photoshop APP13 段的图像资源块
+pub fn photoshop_irb(data: &[u8], segments: &[JpegSegment]) -> Vec<u8> {
+  segments
+    .iter()
+    .filter(|s| s.marker == 0xED)
+    .map(|s| s.payload(data))
+    .filter(|p| p.starts_with(JPEG_PHOTOSHOP_PREFIX))
+    .flat_map(|p| p[JPEG_PHOTOSHOP_PREFIX.len()..].iter().copied())
+    .collect()
+}
+
+/// 读取图片文件中的 IPTC 信息
+pub fn read_iptc(data: &[u8]) -> Option<IptcData> {
+  find_iptc(data).map(|iim| parse_iptc(&iim))
+}
+
+/// 解析 Photoshop 图像资源块 (8BIM)，返回 (资源 ID, 数据) 列表
+///
+/// 遇到无效的资源头时停止
+pub fn image_resources(irb: &[u8]) -> Vec<(u16, &[u8])> {
+  resource_records(irb).into_iter().map(|r| (r.id, r.data)).collect()
+}
+
+/// 8BIM 图像资源
+struct ImageResource<'a> {
+  id: u16,
+  data: &'a [u8],
+  /// 包含资源头和补齐字节的原始字节
+  raw: &'a [u8],
+}
+
+fn resource_records(irb: &[u8]) -> Vec<ImageResource<'_>> {
+  let mut resources = Vec::new();
+  let mut pos = 0;
+
+  while pos + 6 < irb.len() && &irb[pos..pos + 4] == b"8BIM" {
+    let id = u16::from_be_bytes([irb[pos + 4], irb[pos + 5]]);
+    // Pascal 字符串名称，长度字节 + 名称，整体补齐为偶数
+    let name_len = irb[pos + 6] as usize;
+    let size_offset = pos + 6 + ((name_len + 2) & !1);
+    let size = match irb.get(size_offset..size_offset + 4) {
+      Some(bytes) => u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize,
+      None => break,
+    };
+    let data_start = size_offset + 4;
+    let data_end = match data_start.checked_add(size).filter(|&end| end <= irb.len()) {
+      Some(end) => end,
+      None => break,
+    };
+
+    let raw_end = (data_end + (size & 1)).min(irb.len());
+    resources.push(ImageResource {
+      id,
+      data: &irb[data_start..data_end],
+      raw: &irb[pos..raw_end],
+    });
+    pos = data_end + (size & 1);
+  }
+
+  resources
+}
+
+/// 解析 IPTC-IIM 数据集
+///
+/// 数据截断或格式错误时保留已解析的字段并记录警告
+pub fn parse_iptc(iim: &[u8]) -> IptcData {
+  let mut iptc = IptcData::default();
+  let (datasets, warning) = iim_datasets(iim);
+  iptc.warnings.extend(warning);
+
+  let utf8 = datasets
+    .iter()
+    .any(|d| d.dataset == DATASET_CODED_CHARACTER_SET && d.value == UTF8_CHARSET);
+
+  for d in datasets {
+    let text = decode_text(d.value, utf8);
+    if text.is_empty() {
+      continue;
+    }
+
+    match d.dataset {
+      DATASET_OBJECT_NAME => iptc.object_name = Some(text),
+      DATASET_KEYWORDS => iptc.keywords.push(text),
+      DATASET_BY_LINE => iptc.by_line.push(text),
+      DATASET_CITY => iptc.city = Some(text),
+      DATASET_COUNTRY => iptc.country = Some(text),
+      DATASET_HEADLINE => iptc.headline = Some(text),
+      DATASET_CREDIT => iptc.credit = Some(text),
+      DATASET_SOURCE => iptc.source = Some(text),
+      DATASET_COPYRIGHT_NOTICE => iptc.copyright_notice = Some(text),
+      DATASET_CAPTION => iptc.caption = Some(text),
+      _ => {}
+    }
+  }
+
+  iptc
+}
+
+/// IIM 数据集
+struct Dataset<'a> {
+  /// (记录号, 数据集号)
+  dataset: (u8, u8),
+  value: &'a [u8],
+  /// 包含标记和长度字段的原始字节
+  raw: &'a [u8],
+}
+
+/// 拆分 IIM 数据集，返回已解析的数据集和出错时的警告
+fn iim_datasets(iim: &[u8]) -> (Vec<Dataset<'_>>, Option<String>) {
+  let mut datasets = Vec::new();
+  let mut pos = 0;
+
+  while pos < iim.len() {
+    // 部分软件会在数据集之后补零
+    if iim[pos..].iter().all(|&b| b == 0) {
+      break;
+    }
+    if iim[pos] != IIM_TAG_MARKER || pos + 5 > iim.len() {
+      return (datasets, Some(format!("Invalid IPTC dataset at offset {}", pos)));
+    }
+
+    let dataset = (iim[pos + 1], iim[pos + 2]);
+    let length_field = u16::from_be_bytes([iim[pos + 3], iim[pos + 4]]) as usize;
+    let mut data_start = pos + 5;
+
+    // 扩展数据集：最高位置 1 时，低 15 位为长度字段的字节数
+    let length = if length_field & 0x8000 != 0 {
+      let count = length_field & 0x7FFF;
+      let bytes = match iim.get(data_start..data_start.saturating_add(count)).filter(|_| count <= 4) {
+        Some(bytes) => bytes,
+        None => return (datasets, Some(format!("Invalid IPTC extended length at offset {}", pos))),
+      };
+      data_start += count;
+      bytes.iter().fold(0usize, |acc, &b| (acc << 8) | b as usize)
+    } else {
+      length_field
+    };
+
+    let data_end = match data_start.checked_add(length).filter(|&end| end <= iim.len()) {
+      Some(end) => end,
+      None => return (datasets, Some(format!("IPTC dataset {}:{} is truncated", dataset.0, dataset.1))),
+    };
+    datasets.push(Dataset {
+      dataset,
+      value: &iim[data_start..data_end],
+      raw: &iim[pos..data_end],
+    });
+    pos = data_end;
+  }
+
+  (datasets, None)
+}
+
+/// 解码 IIM 文本，未声明 UTF-8 时先尝试 UTF-8，失败按 Latin-1 处理
+fn decode_text(value: &[u8], utf8: bool) -> String {
+  let text = match std::str::from_utf8(value) {
+    Ok(text) => text.to_string(),
+    Err(_) if utf8 => String::from_utf8_lossy(value).into_owned(),
+    Err(_) => value.iter().map(|&b| b as char).collect(),
+  };
+  text.trim_end_matches('\0').trim().to_string()
+}
+
+/// 构造存放 IPTC-IIM 的 JPEG APP13 段（只包含 IPTC 资源）
+pub fn build_jpeg_iptc_segment(iim: &[u8]) -> Result<Vec<u8>, String> {
+  build_jpeg_photoshop_segments(&replace_iptc_resource(&[], iim))
+}
+
+/// 把图像资源块写成 APP13 段，超出单段容量时拆分到多个段
+pub fn build_jpeg_photoshop_segments(irb: &[u8]) -> Result<Vec<u8>, String> {
+  let mut output = Vec::new();
+  for chunk in irb.chunks(MAX_APP13_IRB_LENGTH) {
+    output.extend(build_jpeg_segment(0xED, &[JPEG_PHOTOSHOP_PREFIX, chunk].concat())?);
+  }
+  Ok(output)
+}
+
+/// 替换图像资源块中的 IPTC 资源，没有时追加到末尾
+///
+/// 其他资源按原字节保留；IPTC 摘要随 IIM 改变而失效，直接删除
+pub fn replace_iptc_resource(irb: &[u8], iim: &[u8]) -> Vec<u8> {
+  let mut output = Vec::new();
+  let mut replaced = false;
+
+  for resource in resource_records(irb) {
+    match resource.id {
+      IPTC_RESOURCE_ID if !replaced => {
+        push_iptc_resource(&mut output, iim);
+        replaced = true;
+      }
+      IPTC_RESOURCE_ID | IPTC_DIGEST_RESOURCE_ID => {}
+      _ => {
+        output.extend_from_slice(resource.raw);
+        // 文件末尾的资源可能缺少补齐字节
+        if output.len() % 2 == 1 {
+          output.push(0);
+        }
+      }
+    }
+  }
+
+  if !replaced {
+    push_iptc_resource(&mut output, iim);
+  }
+  output
+}
+
+/// 追加空名称的 IPTC 资源
+fn push_iptc_resource(irb: &mut Vec<u8>, iim: &[u8]) {
+  irb.extend_from_slice(b"8BIM");
+  irb.extend_from_slice(&IPTC_RESOURCE_ID.to_be_bytes());
+  // 空名称（长度字节 + 补齐）
+  irb.extend_from_slice(&[0, 0]);
+  irb.extend_from_slice(&(iim.len() as u32).to_be_bytes());
+  irb.extend_from_slice(iim);
+  if iim.len() % 2 == 1 {
+    irb.push(0);
+  }
+}
+
+/// 把 IPTC 信息编码为 IIM 数据集序列
+///
+/// 文本统一按 UTF-8 写入并声明 1:90 字符集
+pub fn build_iim(iptc: &IptcData) -> Vec<u8> {
+  merge_iim(&[], iptc)
+}
+
+/// 用 IPTC 信息替换已有 IIM 中对应的数据集
+///
+/// IptcData 之外的数据集（如 2:55 创建日期、2:40 特别说明）按原字节保留
+pub fn merge_iim(existing: &[u8], iptc: &IptcData) -> Vec<u8> {
+  let mut datasets: Vec<((u8, u8), Vec<u8>)> = iim_datasets(existing)
+    .0
+    .into_iter()
+    .filter(|d| !MODELED_DATASETS.contains(&d.dataset))
+    .map(|d| (d.dataset, d.raw.to_vec()))
+    .collect();
+
+  datasets.push((DATASET_CODED_CHARACTER_SET, encode_dataset(DATASET_CODED_CHARACTER_SET, UTF8_CHARSET)));
+  // 记录版本号固定为 4
+  datasets.push((DATASET_RECORD_VERSION, encode_dataset(DATASET_RECORD_VERSION, &[0, 4])));
+
+  let single = [
+    (DATASET_OBJECT_NAME, &iptc.object_name),
+    (DATASET_CITY, &iptc.city),
+    (DATASET_COUNTRY, &iptc.country),
+    (DATASET_HEADLINE, &iptc.headline),
+    (DATASET_CREDIT, &iptc.credit),
+    (DATASET_SOURCE, &iptc.source),
+    (DATASET_COPYRIGHT_NOTICE, &iptc.copyright_notice),
+    (DATASET_CAPTION, &iptc.caption),
+  ];
+  let repeated = [(DATASET_KEYWORDS, &iptc.keywords), (DATASET_BY_LINE, &iptc.by_line)];
+
+  datasets.extend(
+    single
+      .iter()
+      .filter_map(|(dataset, value)| value.as_deref().map(|v| (*dataset, v)))
+      .chain(repeated.iter().flat_map(|(dataset, values)| values.iter().map(|v| (*dataset, v.as_str()))))
+      .filter(|(_, value)| !value.is_empty())
+      .map(|(dataset, value)| (dataset, encode_dataset(dataset, value.as_bytes()))),
+  );
+
+  // 按数据集号升序写入，同一数据集保持原有顺序
+  datasets.sort_by_key(|(dataset, _)| *dataset);
+  datasets.into_iter().flat_map(|(_, bytes)| bytes).collect()
+}
+
+/// 编码单个 IIM 数据集，超过 32767 字节时使用扩展长度
+fn encode_dataset(dataset: (u8, u8), value: &[u8]) -> Vec<u8> {
+  let mut bytes = vec![IIM_TAG_MARKER, dataset.0, dataset.1];
+  if value.len() <= 0x7FFF {
+    bytes.extend_from_slice(&(value.len() as u16).to_be_bytes());
+  } else {
+    bytes.extend_from_slice(&[0x80, 0x04]);
+    bytes.extend_from_slice(&(value.len() as u32).to_be_bytes());
+  }
+  bytes.extend_from_slice(value);
+  bytes
+}
+
+/// 把 IPTC 信息写入 JPEG
+///
+/// 只替换 IptcData 对应的数据集，其他数据集和 Photoshop 资源保持不变
+pub fn write_iptc(data: &[u8], iptc: &IptcData) -> Result<Vec<u8>, String> {
+  if ContainerKind::detect(data) != Some(ContainerKind::Jpeg) {
+    return Err("Writing IPTC is only supported for JPEG".to_string());
+  }
+
+  let existing = find_iptc(data).unwrap_or_default();
+  embed_blocks(
+    data,
+    &MetadataBlocks {
+      iptc: Some(merge_iim(&existing, iptc)),
+      ..Default::default()
+    },
+    // 只写入单个数据块，不会产生需要丢弃的内容
+    &mut Vec::new(),
+  )
+}
+
+/// 把关键词、说明和署名写入 JPEG 的 IPTC（WASM 导出）
+///
+/// 与文件中已有的 IPTC 合并，只改写容器结构，不重新编码像素
+#[wasm_bindgen]
+pub fn write_image_iptc(image_data: &[u8], fields: IptcFields) -> Result<Vec<u8>, JsValue> {
+  let mut iptc = read_iptc(image_data).unwrap_or_default();
+  iptc.apply_fields(&fields).map_err(|e| JsValue::from_str(&e))?;
+  write_iptc(image_data, &iptc).map_err(|e| JsValue::from_str(&e))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+  use super::*;
+
+  /// 构造单个 IIM 数据集
+  pub(crate) fn iim_dataset(dataset: (u8, u8), value: &[u8]) -> Vec<u8> {
+    let mut bytes = vec![IIM_TAG_MARKER, dataset.0, dataset.1];
+    bytes.extend_from_slice(&(value.len() as u16).to_be_bytes());
+    bytes.extend_from_slice(value);
+    bytes
+  }
+
+  /// 测试用的 IIM 数据（UTF-8）
+  pub(crate) fn sample_iim() -> Vec<u8> {
+    [
+      iim_dataset(DATASET_CODED_CHARACTER_SET, UTF8_CHARSET),
+      iim_dataset((2, 0), &[0, 4]),
+      iim_dataset(DATASET_OBJECT_NAME, b"Sunset"),
+      iim_dataset(DATASET_KEYWORDS, "日落".as_bytes()),
+      iim_dataset(DATASET_KEYWORDS, b"beach"),
+      iim_dataset(DATASET_BY_LINE, b"Alice"),
+      iim_dataset(DATASET_CREDIT, b"Pixuli Photo"),
+      iim_dataset(DATASET_COPYRIGHT_NOTICE, "© 2024 Alice".as_bytes()),
+      iim_dataset(DATASET_CAPTION, "海边的日落".as_bytes()),
+    ]
+    .concat()
+  }
+
+  /// 带 IPTC APP13 段的最小 JPEG
+  pub(crate) fn build_jpeg_with_iptc(iim: &[u8]) -> Vec<u8> {
+    let mut data = vec![0xFF, 0xD8];
+    data.extend(build_jpeg_iptc_segment(iim).unwrap());
+    data.extend_from_slice(&[0xFF, 0xDA, 0x00, 0x02, 0xFF, 0xD9]);
+    data
+  }
+
+  #[test]
+  fn test_read_iptc() {
+    let iptc = read_iptc(&build_jpeg_with_iptc(&sample_iim())).unwrap();
+    assert_eq!(iptc.object_name.as_deref(), Some("Sunset"));
+    assert_eq!(iptc.keywords, vec!["日落", "beach"]);
+    assert_eq!(iptc.by_line, vec!["Alice"]);
+    assert_eq!(iptc.credit.as_deref(), Some("Pixuli Photo"));
+    assert_eq!(iptc.copyright_notice.as_deref(), Some("© 2024 Alice"));
+    assert_eq!(iptc.caption.as_deref(), Some("海边的日落"));
+    assert!(iptc.warnings.is_empty());
+
+    assert!(read_iptc(&[0xFF, 0xD8, 0xFF, 0xDA, 0x00, 0x02, 0xFF, 0xD9]).is_none());
+  }
+
+  #[test]
+  fn test_image_resources_skips_other_blocks() {
+    // 带名称的缩略图资源（奇数长度，需要补齐）在 IPTC 之前
+    let mut irb = b"8BIM\x04\x0C\x03abc".to_vec();
+    irb.extend_from_slice(&3u32.to_be_bytes());
+    irb.extend_from_slice(&[1, 2, 3, 0]);
+    irb.extend_from_slice(b"8BIM\x04\x04\x00\x00");
+    irb.extend_from_slice(&2u32.to_be_bytes());
+    irb.extend_from_slice(&[9, 9]);
+
+    let resources = image_resources(&irb);
+    assert_eq!(resources, vec![(0x040C, &[1, 2, 3][..]), (IPTC_RESOURCE_ID, &[9, 9][..])]);
+  }
+
+  #[test]
+  fn test_parse_iptc_latin1_and_truncated() {
+    let mut iim = iim_dataset(DATASET_CITY, b"M\xFCnchen");
+    iim.extend_from_slice(&[IIM_TAG_MARKER, 2, 25, 0, 20, b'a']);
+
+    let iptc = parse_iptc(&iim);
+    assert_eq!(iptc.city.as_deref(), Some("München"));
+    assert!(iptc.keywords.is_empty());
+    assert_eq!(iptc.warnings, vec!["IPTC dataset 2:25 is truncated"]);
+  }
+
+  #[test]
+  fn test_parse_iptc_extended_length() {
+    let mut iim = vec![IIM_TAG_MARKER, 2, 120, 0x80, 0x02, 0x00, 0x05];
+    iim.extend_from_slice(b"hello\0\0");
+    assert_eq!(parse_iptc(&iim).caption.as_deref(), Some("hello"));
+
+    // 扩展长度接近 usize 上限时按截断处理
+    let iim = [IIM_TAG_MARKER, 2, 120, 0x80, 0x04, 0xFF, 0xFF, 0xFF, 0xFF, b'a'];
+    assert_eq!(parse_iptc(&iim).warnings, vec!["IPTC dataset 2:120 is truncated"]);
+
+    let mut irb = b"8BIM\x04\x04\x00\x00".to_vec();
+    irb.extend_from_slice(&u32::MAX.to_be_bytes());
+    irb.extend_from_slice(&[1, 2]);
+    assert!(image_resources(&irb).is_empty());
+  }
+
+  #[test]
+  fn test_build_iim_round_trip() {
+    let iptc = parse_iptc(&sample_iim());
+    let iim = build_iim(&iptc);
+    assert!(iim.starts_with(&iim_dataset(DATASET_CODED_CHARACTER_SET, UTF8_CHARSET)));
+    assert_eq!(parse_iptc(&iim), iptc);
+
+    // 超长说明使用扩展长度
+    let long = IptcData {
+      caption: Some("a".repeat(40000)),
+      ..Default::default()
+    };
+    assert_eq!(parse_iptc(&build_iim(&long)), long);
+  }
+
+  #[test]
+  fn test_write_iptc_fields() {
+    let jpeg = build_jpeg_with_iptc(&sample_iim());
+    let fields = IptcFields {
+      keywords_json: Some(r#"["海滩", "sunset"]"#.to_string()),
+      caption: Some("新的说明".to_string()),
+      credit: Some(String::new()),
+    };
+    let mut iptc = read_iptc(&jpeg).unwrap();
+    iptc.apply_fields(&fields).unwrap();
+    let written = write_iptc(&jpeg, &iptc).unwrap();
+
+    let updated = read_iptc(&written).unwrap();
+    assert_eq!(updated.keywords, vec!["海滩", "sunset"]);
+    assert_eq!(updated.caption.as_deref(), Some("新的说明"));
+    assert_eq!(updated.credit, None);
+    assert_eq!(updated.object_name.as_deref(), Some("Sunset"));
+    assert_eq!(updated.by_line, vec!["Alice"]);
+
+    let invalid = IptcFields {
+      keywords_json: Some("sunset".to_string()),
+      ..Default::default()
+    };
+    assert!(IptcData::default().apply_fields(&invalid).is_err());
+    assert!(write_iptc(b"\x89PNG\r\n\x1a\n", &iptc).is_err());
+  }
+
+  #[test]
+  fn test_write_iptc_keeps_unmodeled_data() {
+    let date = iim_dataset((2, 55), b"20240101");
+    let instructions = iim_dataset((2, 40), b"\xC9dition limit\xE9e");
+    let iim = [sample_iim(), date.clone(), instructions.clone()].concat();
+
+    // 分辨率信息 + IPTC + IPTC 摘要
+    let mut resolution = b"8BIM\x03\xED\x00\x00".to_vec();
+    resolution.extend_from_slice(&16u32.to_be_bytes());
+    resolution.extend_from_slice(&[0, 72, 0, 0, 0, 1, 0, 1, 0, 72, 0, 0, 0, 1, 0, 1]);
+    let mut irb = resolution.clone();
+    push_iptc_resource(&mut irb, &iim);
+    irb.extend_from_slice(b"8BIM\x04\x25\x00\x00");
+    irb.extend_from_slice(&16u32.to_be_bytes());
+    irb.extend_from_slice(&[0xAB; 16]);
+
+    let mut jpeg = vec![0xFF, 0xD8];
+    jpeg.extend(build_jpeg_photoshop_segments(&irb).unwrap());
+    jpeg.extend_from_slice(&[0xFF, 0xDA, 0x00, 0x02, 0xFF, 0xD9]);
+
+    let mut iptc = read_iptc(&jpeg).unwrap();
+    iptc.caption = Some("新的说明".to_string());
+    let written = write_iptc(&jpeg, &iptc).unwrap();
+
+    let irb = photoshop_irb(&written, &jpeg_segments(&written).unwrap());
+    assert!(irb.starts_with(&resolution));
+    let resources = image_resources(&irb);
+    assert_eq!(resources.iter().map(|(id, _)| *id).collect::<Vec<_>>(), vec![0x03ED, IPTC_RESOURCE_ID]);
+
+    let new_iim = find_iptc(&written).unwrap();
+    let find = |needle: &[u8]| new_iim.windows(needle.len()).position(|w| w == needle);
+    assert!(find(&date).is_some());
+    assert!(find(&instructions).is_some());
+    // 按数据集号排序：2:25 关键词 < 2:40 < 2:55 < 2:80 作者
+    assert!(find(&instructions) < find(&date));
+    assert!(find(&date) < find(&iim_dataset(DATASET_BY_LINE, b"Alice")));
+    assert_eq!(read_iptc(&written).unwrap().caption.as_deref(), Some("新的说明"));
+  }
+
+  #[test]
+  fn test_large_iptc_spans_app13_segments() {
+    let iptc = IptcData {
+      caption: Some("a".repeat(70000)),
+      ..Default::default()
+    };
+    let jpeg = build_jpeg_with_iptc(&build_iim(&iptc));
+    let app13_count = jpeg_segments(&jpeg).unwrap().iter().filter(|s| s.marker == 0xED).count();
+    assert_eq!(app13_count, 2);
+    assert_eq!(read_iptc(&jpeg).unwrap(), iptc);
+  }
+}
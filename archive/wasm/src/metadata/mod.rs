@@ -0,0 +1,61 @@
+//! 图片元数据
+//!
+//! 提供 EXIF 等元数据的定位和解析功能，包括：
+//! - JPEG / PNG / WebP / TIFF 容器结构遍历
+//! - EXIF (TIFF IFD) 解析，对损坏的 IFD 做容错处理
+//! - EXIF 方向校正
+//! - XMP 数据包定位和字段删除
+//! - ICC 色彩配置读取和写入
+//! - IPTC-IIM 关键词、说明和署名的解析和写入
+//! - 按策略清理元数据（全部删除 / 只删除隐私信息 / 全部保留）
+//! - 格式转换时按策略迁移选定的元数据块（EXIF / XMP / ICC / IPTC）
+//! - 标签、描述和评分的 XMP 读写
+
+pub mod carry;
+pub mod container;
+pub mod exif;
+pub mod icc;
+pub mod iptc;
+pub mod orientation;
+pub mod strip;
+pub mod tags;
+pub mod xmp;
+
+// 重新导出主要类型和函数
+pub use carry::{carry_metadata, MetadataBlockKind};
+pub use container::{find_exif, ContainerKind};
+pub use exif::{parse_exif, ExifData, GpsInfo};
+pub use icc::find_icc;
+pub use iptc::{read_iptc, write_iptc, IptcData, IptcFields};
+pub use orientation::{apply_orientation, reset_orientation};
+pub use strip::{strip_metadata, MetadataPolicy, StripMetadataResult};
+pub use tags::{read_image_tags, write_image_tags, ImageTags};
+
+/// 从图片文件中读取 EXIF 信息
+///
+/// 没有 EXIF 时返回 `Ok(None)`，TIFF 头损坏时返回错误
+pub fn read_exif(data: &[u8]) -> Result<Option<ExifData>, String> {
+  match find_exif(data) {
+    Some(tiff) => parse_exif(tiff).map(Some),
+    None => Ok(None),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::metadata::container::tests::build_jpeg_with_app1;
+  use crate::metadata::exif::tests::sample_tiff;
+
+  #[test]
+  fn test_read_exif() {
+    let jpeg = build_jpeg_with_app1(&sample_tiff());
+    let exif = read_exif(&jpeg).unwrap().unwrap();
+    assert_eq!(exif.model.as_deref(), Some("EOS R5"));
+
+    let without_exif = build_jpeg_with_app1(b"");
+    assert!(read_exif(&without_exif).is_err());
+
+    assert!(read_exif(b"GIF89a").unwrap().is_none());
+  }
+}
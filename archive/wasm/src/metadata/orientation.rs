@@ -0,0 +1,107 @@
+//! EXIF 方向处理
+//!
+//! 按 EXIF Orientation (1-8) 旋转/翻转像素，并在写回的元数据中把方向重置为 1
+
+use image::DynamicImage;
+
+use crate::metadata::exif::{TiffReader, TAG_ORIENTATION};
+
+/// 按 EXIF 方向校正图片
+///
+/// 无效的方向值按 1（不变换）处理
+pub fn apply_orientation(img: DynamicImage, orientation: u16) -> DynamicImage {
+  match orientation {
+    2 => img.fliph(),
+    3 => img.rotate180(),
+    4 => img.flipv(),
+    5 => img.rotate90().fliph(),
+    6 => img.rotate90(),
+    7 => img.rotate270().fliph(),
+    8 => img.rotate270(),
+    _ => img,
+  }
+}
+
+/// 方向是否会交换宽高
+pub fn swaps_dimensions(orientation: u16) -> bool {
+  (5..=8).contains(&orientation)
+}
+
+/// 把 TIFF 结构中 IFD0 的方向标签重置为 1（原地修改）
+///
+/// 像素已经校正过的图片在写回元数据前需要调用，避免查看器再次旋转。
+/// 返回是否找到并修改了方向标签
+pub fn reset_orientation(tiff: &mut [u8]) -> bool {
+  let target = match TiffReader::new(tiff) {
+    Ok(reader) => reader
+      .first_ifd_offset()
+      .and_then(|offset| reader.read_ifd(offset))
+      .ok()
+      .and_then(|(entries, _)| entries.into_iter().find(|e| e.tag == TAG_ORIENTATION && e.field_type == 3))
+      .map(|entry| (entry.value_offset, reader.byte_order())),
+    Err(_) => None,
+  };
+
+  match target {
+    Some((offset, byte_order)) => {
+      tiff[offset..offset + 2].copy_from_slice(&byte_order.u16_bytes(1));
+      true
+    }
+    None => false,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::metadata::exif::parse_exif;
+  use crate::metadata::exif::tests::sample_tiff;
+  use image::{GenericImageView, ImageBuffer, Rgb, RgbImage};
+
+  /// 3×2 的测试图片，每个像素颜色唯一
+  fn generate_test_image() -> DynamicImage {
+    let img: RgbImage = ImageBuffer::from_fn(3, 2, |x, y| Rgb([x as u8, y as u8, 0]));
+    DynamicImage::ImageRgb8(img)
+  }
+
+  #[test]
+  fn test_apply_orientation_all_values() {
+    // 每个方向下原图左上角像素 (0, 0) 在校正后的位置
+    let expected_corner = [
+      (1, (0, 0)),
+      (2, (2, 0)),
+      (3, (2, 1)),
+      (4, (0, 1)),
+      (5, (0, 0)),
+      (6, (1, 0)),
+      (7, (1, 2)),
+      (8, (0, 2)),
+    ];
+
+    for (orientation, (x, y)) in expected_corner {
+      let img = apply_orientation(generate_test_image(), orientation);
+      let expected_dimensions = if swaps_dimensions(orientation) { (2, 3) } else { (3, 2) };
+      assert_eq!(img.dimensions(), expected_dimensions, "orientation {}", orientation);
+      assert_eq!(img.get_pixel(x, y).0[..3], [0, 0, 0], "orientation {}", orientation);
+    }
+  }
+
+  #[test]
+  fn test_apply_orientation_invalid_value() {
+    let img = apply_orientation(generate_test_image(), 42);
+    assert_eq!(img.dimensions(), (3, 2));
+  }
+
+  #[test]
+  fn test_reset_orientation() {
+    let mut tiff = sample_tiff();
+    assert_eq!(parse_exif(&tiff).unwrap().orientation, Some(6));
+
+    assert!(reset_orientation(&mut tiff));
+    let exif = parse_exif(&tiff).unwrap();
+    assert_eq!(exif.orientation, Some(1));
+    assert_eq!(exif.make.as_deref(), Some("Canon"));
+
+    assert!(!reset_orientation(&mut b"not a tiff".to_vec()));
+  }
+}
@@ -0,0 +1,611 @@
+//! 元数据清理
+//!
+//! 按策略删除 JPEG / PNG / WebP 中的元数据，只改写容器结构，不重新编码像素。
+//! ICC 色彩配置和解码必需的段（JFIF、Adobe APP14）始终保留
+
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::metadata::container::{
+  build_jpeg_segment, build_png_chunk, build_riff_chunk, build_webp, jpeg_segments, png_chunks, riff_chunks,
+  ContainerKind, JPEG_EXIF_PREFIX, PNG_SIGNATURE,
+};
+use crate::metadata::exif::{
+  ByteOrder, TiffReader, TAG_BODY_SERIAL_NUMBER, TAG_CAMERA_OWNER_NAME, TAG_CAMERA_SERIAL_NUMBER, TAG_EXIF_IFD, TAG_GPS_IFD,
+  TAG_LENS_SERIAL_NUMBER, TAG_MAKER_NOTE, MAX_IFD_ENTRIES,
+};
+use crate::metadata::xmp::{self, JPEG_EXTENDED_XMP_PREFIX, JPEG_XMP_PREFIX, PNG_XMP_KEYWORD};
+
+/// 元数据处理策略
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MetadataPolicy {
+  /// 删除全部元数据
+  StripAll,
+  /// 只删除 GPS 位置、序列号和扩展 XMP 等隐私信息
+  StripPrivate,
+  /// 保留全部元数据
+  KeepAll,
+}
+
+impl MetadataPolicy {
+  /// 从字符串解析策略
+  pub fn from_string(policy_str: &str) -> Option<Self> {
+    match policy_str.to_lowercase().as_str() {
+      "strip_all" | "strip" => Some(MetadataPolicy::StripAll),
+      "strip_private" | "private" => Some(MetadataPolicy::StripPrivate),
+      "keep_all" | "keep" => Some(MetadataPolicy::KeepAll),
+      _ => None,
+    }
+  }
+
+  /// 解析可选的策略字符串，未指定时使用默认策略
+  pub fn parse_or(policy_str: Option<&str>, default: Self) -> Result<Self, String> {
+    match policy_str {
+      Some(s) => Self::from_string(s).ok_or_else(|| format!("Unsupported metadata policy: {}", s)),
+      None => Ok(default),
+    }
+  }
+}
+
+/// 元数据清理结果
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StripMetadataResult {
+  /// 清理后的数据
+  pub data: Vec<u8>,
+  /// 被删除的元数据 JSON string，如 ["EXIF:GPS", "XMP"]
+  pub removed_json: String,
+  /// 原始大小
+  pub original_size: u32,
+  /// 清理后大小
+  pub stripped_size: u32,
+}
+
+/// 按策略清理图片元数据（WASM 导出）
+#[wasm_bindgen]
+pub fn strip_metadata(image_data: &[u8], policy: String) -> Result<StripMetadataResult, JsValue> {
+  let policy = MetadataPolicy::from_string(&policy)
+    .ok_or_else(|| JsValue::from_str(&format!("Unsupported metadata policy: {}", policy)))?;
+
+  let (data, removed) = strip_container(image_data, policy).map_err(|e| JsValue::from_str(&e))?;
+
+  Ok(StripMetadataResult {
+    original_size: image_data.len() as u32,
+    stripped_size: data.len() as u32,
+    removed_json: serde_json::to_string(&removed).unwrap_or_else(|_| "[]".to_string()),
+    data,
+  })
+}
+
+/// 按策略改写容器，返回新数据和被删除的元数据列表
+pub fn strip_container(data: &[u8], policy: MetadataPolicy) -> Result<(Vec<u8>, Vec<String>), String> {
+  match ContainerKind::detect(data) {
+    Some(ContainerKind::Jpeg) => strip_jpeg(data, policy),
+    Some(ContainerKind::Png) => strip_png(data, policy),
+    Some(ContainerKind::WebP) => strip_webp(data, policy),
+    Some(ContainerKind::Tiff) => Err("Metadata stripping is not supported for TIFF".to_string()),
+    None => Err("Unsupported image format".to_string()),
+  }
+}
+
+/// 清理 EXIF 中的隐私信息
+///
+/// 删除 GPS IFD、序列号、相机所有者和厂商私有的 MakerNote（常含序列号），
+/// 其余字段原样保留。返回被删除的字段名
+pub fn sanitize_exif(tiff: &mut [u8]) -> Result<Vec<String>, String> {
+  let (ifd0, exif_ifd, gps_ifd) = {
+    let reader = TiffReader::new(tiff)?;
+    let ifd0 = reader.first_ifd_offset()?;
+    let (entries, _) = reader.read_ifd(ifd0)?;
+    let pointer = |tag: u16| {
+      entries
+        .iter()
+        .find(|e| e.tag == tag)
+        .and_then(|e| reader.read_uint(e, 0))
+        .map(|v| v as usize)
+    };
+    (ifd0, pointer(TAG_EXIF_IFD), pointer(TAG_GPS_IFD))
+  };
+
+  let mut removed = Vec::new();
+
+  if let Some(gps_ifd) = gps_ifd {
+    // GPS IFD 指回 IFD0 时只删除指针；无法清除的 GPS IFD 返回错误，由调用方整块删除 EXIF
+    if gps_ifd != ifd0 {
+      remove_ifd_entries(tiff, gps_ifd, |_| true).map_err(|e| format!("Failed to remove GPS IFD: {}", e))?;
+    }
+    remove_ifd_entries(tiff, ifd0, |tag| tag == TAG_GPS_IFD)?;
+    removed.push("GPS".to_string());
+  }
+
+  let ifd0_removed = remove_ifd_entries(tiff, ifd0, is_private_exif_tag)?;
+  let exif_removed = match exif_ifd {
+    Some(offset) if offset != ifd0 => remove_ifd_entries(tiff, offset, is_private_exif_tag).unwrap_or_default(),
+    _ => Vec::new(),
+  };
+  removed.extend(ifd0_removed.into_iter().chain(exif_removed).map(|tag| private_exif_tag_name(tag).to_string()));
+
+  Ok(removed)
+}
+
+/// 是否为隐私相关的 EXIF 标签
+fn is_private_exif_tag(tag: u16) -> bool {
+  matches!(
+    tag,
+    TAG_BODY_SERIAL_NUMBER | TAG_LENS_SERIAL_NUMBER | TAG_CAMERA_SERIAL_NUMBER | TAG_CAMERA_OWNER_NAME | TAG_MAKER_NOTE
+  )
+}
+
+/// 隐私相关 EXIF 标签的名称
+fn private_exif_tag_name(tag: u16) -> &'static str {
+  match tag {
+    TAG_BODY_SERIAL_NUMBER => "BodySerialNumber",
+    TAG_LENS_SERIAL_NUMBER => "LensSerialNumber",
+    TAG_CAMERA_SERIAL_NUMBER => "CameraSerialNumber",
+    TAG_CAMERA_OWNER_NAME => "CameraOwnerName",
+    TAG_MAKER_NOTE => "MakerNote",
+    _ => "Unknown",
+  }
+}
+
+/// 是否为隐私相关的 XMP 属性（GPS 和序列号）
+fn is_private_xmp_property(name: &str) -> bool {
+  let local = name.rsplit(':').next().unwrap_or(name);
+  local.starts_with("GPS")
+    || matches!(
+      local,
+      "SerialNumber" | "LensSerialNumber" | "BodySerialNumber" | "CameraSerialNumber" | "OwnerName" | "CameraOwnerName"
+    )
+}
+
+/// 从 IFD 中原地删除满足条件的条目，返回被删除的标签
+///
+/// 保留的条目前移，被删除条目的外部数据和空出的位置清零，其余数据的偏移不变
+pub fn remove_ifd_entries(
+  tiff: &mut [u8],
+  ifd_offset: usize,
+  should_remove: impl Fn(u16) -> bool,
+) -> Result<Vec<u16>, String> {
+  let (byte_order, count, next, removed_values) = {
+    let reader = TiffReader::new(tiff)?;
+    let count = reader
+      .read_u16(ifd_offset)
+      .ok_or_else(|| format!("IFD at {} is out of bounds", ifd_offset))? as usize;
+    if count > MAX_IFD_ENTRIES || ifd_offset.checked_add(2 + count * 12 + 4).is_none_or(|end| end > tiff.len()) {
+      return Err(format!("IFD at {} is truncated", ifd_offset));
+    }
+
+    let next = reader.read_u32(ifd_offset + 2 + count * 12).unwrap_or(0);
+    let (entries, _) = reader.read_ifd(ifd_offset)?;
+    let removed_values: Vec<(usize, usize)> = entries
+      .iter()
+      .filter(|e| should_remove(e.tag) && e.value_len() > 4)
+      .map(|e| (e.value_offset, e.value_len()))
+      .collect();
+    (reader.byte_order(), count, next, removed_values)
+  };
+
+  let entries_start = ifd_offset + 2;
+  let old_end = entries_start + count * 12 + 4;
+  let mut kept = Vec::with_capacity(count * 12);
+  let mut removed = Vec::new();
+
+  for i in 0..count {
+    let record = &tiff[entries_start + i * 12..entries_start + (i + 1) * 12];
+    let tag = match byte_order {
+      ByteOrder::LittleEndian => u16::from_le_bytes([record[0], record[1]]),
+      ByteOrder::BigEndian => u16::from_be_bytes([record[0], record[1]]),
+    };
+    if should_remove(tag) {
+      removed.push(tag);
+    } else {
+      kept.extend_from_slice(record);
+    }
+  }
+
+  if removed.is_empty() {
+    return Ok(removed);
+  }
+
+  for (offset, len) in removed_values {
+    tiff[offset..offset + len].fill(0);
+  }
+
+  let kept_count = kept.len() / 12;
+  tiff[ifd_offset..entries_start].copy_from_slice(&byte_order.u16_bytes(kept_count as u16));
+  tiff[entries_start..entries_start + kept.len()].copy_from_slice(&kept);
+  let next_pos = entries_start + kept.len();
+  tiff[next_pos..next_pos + 4].copy_from_slice(&byte_order.u32_bytes(next));
+  tiff[next_pos + 4..old_end].fill(0);
+
+  Ok(removed)
+}
+
+/// 按策略处理 EXIF 数据块，返回 None 表示整块删除
+pub(crate) fn apply_policy_to_exif(tiff: &[u8], policy: MetadataPolicy, removed: &mut Vec<String>) -> Option<Vec<u8>> {
+  match policy {
+    MetadataPolicy::KeepAll => Some(tiff.to_vec()),
+    MetadataPolicy::StripAll => {
+      removed.push("EXIF".to_string());
+      None
+    }
+    MetadataPolicy::StripPrivate => {
+      let mut tiff = tiff.to_vec();
+      match sanitize_exif(&mut tiff) {
+        Ok(fields) => {
+          removed.extend(fields.into_iter().map(|f| format!("EXIF:{}", f)));
+          Some(tiff)
+        }
+        // 无法解析的 EXIF 无法确认是否含隐私信息，整块删除
+        Err(_) => {
+          removed.push("EXIF".to_string());
+          None
+        }
+      }
+    }
+  }
+}
+
+/// 按策略处理 XMP 数据包，返回 None 表示整块删除
+pub(crate) fn apply_policy_to_xmp(xmp: &[u8], policy: MetadataPolicy, removed: &mut Vec<String>) -> Option<Vec<u8>> {
+  match policy {
+    MetadataPolicy::KeepAll => Some(xmp.to_vec()),
+    MetadataPolicy::StripAll => {
+      removed.push("XMP".to_string());
+      None
+    }
+    MetadataPolicy::StripPrivate => match std::str::from_utf8(xmp) {
+      Ok(text) => {
+        let (text, fields) = xmp::remove_properties(text, is_private_xmp_property);
+        // 扩展 XMP 在隐私模式下整块删除，指向它的属性一并删除
+        let (text, _) = xmp::remove_properties(&text, |name| name == xmp::HAS_EXTENDED_XMP);
+        removed.extend(fields.into_iter().map(|f| format!("XMP:{}", f)));
+        Some(text.into_bytes())
+      }
+      Err(_) => {
+        removed.push("XMP".to_string());
+        None
+      }
+    },
+  }
+}
+
+/// 清理 JPEG 元数据
+fn strip_jpeg(data: &[u8], policy: MetadataPolicy) -> Result<(Vec<u8>, Vec<String>), String> {
+  let segments = jpeg_segments(data)?;
+  let sos = segments
+    .last()
+    .filter(|s| s.marker == 0xDA)
+    .ok_or_else(|| "JPEG has no scan data".to_string())?;
+
+  let mut output = vec![0xFF, 0xD8];
+  let mut removed = Vec::new();
+  let strip_all = policy == MetadataPolicy::StripAll;
+
+  for segment in &segments[..segments.len() - 1] {
+    let payload = segment.payload(data);
+    let original = &data[segment.offset..segment.offset + segment.length];
+
+    match segment.marker {
+      0xE1 if payload.starts_with(JPEG_EXIF_PREFIX) => {
+        if let Some(tiff) = apply_policy_to_exif(&payload[JPEG_EXIF_PREFIX.len()..], policy, &mut removed) {
+          output.extend(build_jpeg_segment(0xE1, &[JPEG_EXIF_PREFIX, &tiff].concat())?);
+        }
+      }
+      0xE1 if payload.starts_with(JPEG_XMP_PREFIX) => {
+        if let Some(xmp) = apply_policy_to_xmp(&payload[JPEG_XMP_PREFIX.len()..], policy, &mut removed) {
+          output.extend(build_jpeg_segment(0xE1, &[JPEG_XMP_PREFIX, &xmp].concat())?);
+        }
+      }
+      // 扩展 XMP 常含位置、编辑历史等信息，且被拆分在多个段中无法逐项清理，隐私模式下也整块删除
+      0xE1 if payload.starts_with(JPEG_EXTENDED_XMP_PREFIX) && policy != MetadataPolicy::KeepAll => {
+        removed.push("XMP (extended)".to_string());
+      }
+      0xED if strip_all => removed.push("IPTC".to_string()),
+      0xFE if strip_all => removed.push("Comment".to_string()),
+      // APP0 (JFIF)、APP2 (ICC)、APP14 (Adobe) 影响解码和色彩，始终保留
+      0xE1 | 0xE3..=0xED | 0xEF if strip_all => removed.push(format!("APP{}", segment.marker - 0xE0)),
+      _ => output.extend_from_slice(original),
+    }
+  }
+
+  output.extend_from_slice(&data[sos.offset..]);
+  Ok((output, removed))
+}
+
+/// 清理 PNG 元数据
+fn strip_png(data: &[u8], policy: MetadataPolicy) -> Result<(Vec<u8>, Vec<String>), String> {
+  let chunks = png_chunks(data)?;
+  let mut output = PNG_SIGNATURE.to_vec();
+  let mut removed = Vec::new();
+  let strip_all = policy == MetadataPolicy::StripAll;
+
+  for chunk in &chunks {
+    let payload = chunk.payload(data);
+    let original = &data[chunk.offset..chunk.offset + chunk.length];
+
+    match &chunk.kind {
+      b"eXIf" => {
+        if let Some(tiff) = apply_policy_to_exif(payload, policy, &mut removed) {
+          output.extend(build_png_chunk(b"eXIf", &tiff));
+        }
+      }
+      b"iTXt" if payload.starts_with(PNG_XMP_KEYWORD) => match xmp::parse_itxt(payload) {
+        Some((_, false, text)) => {
+          if let Some(xmp) = apply_policy_to_xmp(text, policy, &mut removed) {
+            output.extend(build_png_chunk(b"iTXt", &xmp::build_itxt_xmp(&xmp)));
+          }
+        }
+        // 压缩的 XMP 无法逐项清理
+        _ if policy != MetadataPolicy::KeepAll => removed.push("XMP".to_string()),
+        _ => output.extend_from_slice(original),
+      },
+      b"tEXt" | b"zTXt" | b"iTXt" if strip_all || (policy == MetadataPolicy::StripPrivate && is_raw_profile(payload)) => {
+        let keyword_end = payload.iter().position(|&b| b == 0).unwrap_or(payload.len());
+        removed.push(format!("Text:{}", String::from_utf8_lossy(&payload[..keyword_end])));
+      }
+      b"tIME" if strip_all => removed.push("Timestamp".to_string()),
+      _ => output.extend_from_slice(original),
+    }
+  }
+
+  Ok((output, removed))
+}
+
+/// 是否为 ImageMagick / exiftool 写入的原始配置文本块（Raw profile type exif / APP1 / xmp / iptc 等）
+///
+/// 这些块以十六进制保存完整的 EXIF 或 XMP，无法逐项清理，隐私模式下整块删除；ICC 配置不含隐私信息，予以保留
+fn is_raw_profile(payload: &[u8]) -> bool {
+  let keyword_end = payload.iter().position(|&b| b == 0).unwrap_or(payload.len());
+  let keyword = String::from_utf8_lossy(&payload[..keyword_end]).to_lowercase();
+  match keyword.strip_prefix("raw profile type ") {
+    Some(kind) => !matches!(kind.trim(), "icc" | "icm"),
+    None => false,
+  }
+}
+
+/// 清理 WebP 元数据
+fn strip_webp(data: &[u8], policy: MetadataPolicy) -> Result<(Vec<u8>, Vec<String>), String> {
+  let chunks = riff_chunks(data)?;
+  let mut output = Vec::with_capacity(chunks.len());
+  let mut removed = Vec::new();
+
+  for chunk in &chunks {
+    let payload = chunk.payload(data);
+
+    match &chunk.kind {
+      b"EXIF" => {
+        // 保留部分编码器写入的 "Exif\0\0" 前缀
+        let prefix = if payload.starts_with(JPEG_EXIF_PREFIX) { JPEG_EXIF_PREFIX } else { &[] };
+        if let Some(tiff) = apply_policy_to_exif(&payload[prefix.len()..], policy, &mut removed) {
+          output.push(build_riff_chunk(b"EXIF", &[prefix, &tiff].concat()));
+        }
+      }
+      b"XMP " => {
+        if let Some(xmp) = apply_policy_to_xmp(payload, policy, &mut removed) {
+          output.push(build_riff_chunk(b"XMP ", &xmp));
+        }
+      }
+      _ => output.push(data[chunk.offset..chunk.offset + chunk.length].to_vec()),
+    }
+  }
+
+  Ok((build_webp(&output), removed))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::metadata::container::tests::insert_jpeg_app1;
+  use crate::metadata::exif::parse_exif;
+  use crate::metadata::exif::tests::sample_tiff;
+  use crate::metadata::xmp::{build_itxt_xmp, find_xmp};
+  use crate::metadata::{find_exif, read_exif};
+  use image::{ImageBuffer, Rgb, RgbImage};
+
+  const PRIVATE_XMP: &[u8] =
+    br#"<x:xmpmeta><rdf:Description exif:GPSLatitude="31,14N" dc:format="image/jpeg"/></x:xmpmeta>"#;
+
+  /// 生成带 EXIF、XMP 和注释的测试 JPEG
+  fn generate_test_jpeg() -> Vec<u8> {
+    let img: RgbImage = ImageBuffer::from_fn(16, 16, |x, y| Rgb([x as u8 * 16, y as u8 * 16, 128]));
+    let mut jpeg = Vec::new();
+    image::codecs::jpeg::JpegEncoder::new(&mut jpeg)
+      .encode(&img, 16, 16, image::ColorType::Rgb8)
+      .unwrap();
+
+    let mut data = insert_jpeg_app1(&jpeg, &sample_tiff());
+    let mut extra = build_jpeg_segment(0xE1, &[JPEG_XMP_PREFIX, PRIVATE_XMP].concat()).unwrap();
+    extra.extend(build_jpeg_segment(0xFE, b"shot on holiday").unwrap());
+    data.splice(2..2, extra);
+    data
+  }
+
+  /// 生成带 eXIf、XMP 和文本块的测试 PNG
+  fn generate_test_png() -> Vec<u8> {
+    let img: RgbImage = ImageBuffer::from_pixel(8, 8, Rgb([10, 20, 30]));
+    let mut png = Vec::new();
+    img.write_to(&mut std::io::Cursor::new(&mut png), image::ImageOutputFormat::Png).unwrap();
+
+    let chunks = png_chunks(&png).unwrap();
+    let ihdr_end = chunks[0].offset + chunks[0].length;
+    let mut extra = build_png_chunk(b"eXIf", &sample_tiff());
+    extra.extend(build_png_chunk(b"iTXt", &build_itxt_xmp(PRIVATE_XMP)));
+    extra.extend(build_png_chunk(b"tEXt", b"Author\0Someone"));
+    png.splice(ihdr_end..ihdr_end, extra);
+    png
+  }
+
+  #[test]
+  fn test_metadata_policy_from_string() {
+    assert_eq!(MetadataPolicy::from_string("strip_all"), Some(MetadataPolicy::StripAll));
+    assert_eq!(MetadataPolicy::from_string("STRIP_PRIVATE"), Some(MetadataPolicy::StripPrivate));
+    assert_eq!(MetadataPolicy::from_string("keep"), Some(MetadataPolicy::KeepAll));
+    assert_eq!(MetadataPolicy::from_string("unknown"), None);
+    assert!(MetadataPolicy::parse_or(Some("bad"), MetadataPolicy::StripAll).is_err());
+  }
+
+  #[test]
+  fn test_sanitize_exif() {
+    let mut tiff = sample_tiff();
+    let removed = sanitize_exif(&mut tiff).unwrap();
+    assert_eq!(removed, vec!["GPS", "BodySerialNumber"]);
+
+    let exif = parse_exif(&tiff).unwrap();
+    assert!(exif.gps.is_none());
+    assert!(exif.body_serial_number.is_none());
+    assert_eq!(exif.make.as_deref(), Some("Canon"));
+    assert_eq!(exif.lens_model.as_deref(), Some("RF50mm F1.8 STM"));
+    assert_eq!(exif.iso, Some(400));
+    assert!(exif.warnings.is_empty());
+    // 序列号的字符串数据已清零
+    assert!(!tiff.windows(9).any(|w| w == b"012345678"));
+  }
+
+  #[test]
+  fn test_sanitize_exif_broken_gps_ifd() {
+    // GPS IFD 指针指向截断的位置时不能只删除指针而保留 GPS 数据
+    let mut tiff = sample_tiff();
+    let pointer = {
+      let reader = TiffReader::new(&tiff).unwrap();
+      let (entries, _) = reader.read_ifd(8).unwrap();
+      entries.iter().find(|e| e.tag == TAG_GPS_IFD).unwrap().value_offset
+    };
+    let truncated = (tiff.len() - 4) as u32;
+    tiff[pointer..pointer + 4].copy_from_slice(&truncated.to_le_bytes());
+    assert!(sanitize_exif(&mut tiff.clone()).is_err());
+
+    let mut removed = Vec::new();
+    assert!(apply_policy_to_exif(&tiff, MetadataPolicy::StripPrivate, &mut removed).is_none());
+    assert_eq!(removed, vec!["EXIF"]);
+  }
+
+  #[test]
+  fn test_strip_jpeg_all() {
+    let data = generate_test_jpeg();
+    let (stripped, removed) = strip_container(&data, MetadataPolicy::StripAll).unwrap();
+
+    assert_eq!(removed, vec!["XMP", "Comment", "EXIF"]);
+    assert!(find_exif(&stripped).is_none());
+    assert!(find_xmp(&stripped).is_none());
+    // 像素数据未重新编码
+    assert!(data.ends_with(&stripped[stripped.len() - 100..]));
+    assert!(image::load_from_memory(&stripped).is_ok());
+  }
+
+  #[test]
+  fn test_strip_jpeg_private() {
+    let data = generate_test_jpeg();
+    let (stripped, removed) = strip_container(&data, MetadataPolicy::StripPrivate).unwrap();
+
+    assert_eq!(removed, vec!["XMP:exif:GPSLatitude", "EXIF:GPS", "EXIF:BodySerialNumber"]);
+    let exif = read_exif(&stripped).unwrap().unwrap();
+    assert!(exif.gps.is_none());
+    assert_eq!(exif.model.as_deref(), Some("EOS R5"));
+    assert_eq!(find_xmp(&stripped).as_deref(), Some(&br#"<x:xmpmeta><rdf:Description dc:format="image/jpeg"/></x:xmpmeta>"#[..]));
+    assert!(stripped.windows(15).any(|w| w == b"shot on holiday"));
+  }
+
+  #[test]
+  fn test_strip_jpeg_private_extended_xmp() {
+    let mut data = generate_test_jpeg();
+    let main = br#"<x:xmpmeta><rdf:Description dc:format="image/jpeg" xmpNote:HasExtendedXMP="0123456789ABCDEF0123456789ABCDEF"/></x:xmpmeta>"#;
+    let extended = [JPEG_EXTENDED_XMP_PREFIX, b"0123456789ABCDEF0123456789ABCDEF", &[0, 0, 0, 9, 0, 0, 0, 0], b"<GPSData>"].concat();
+    let mut extra = build_jpeg_segment(0xE1, &[JPEG_XMP_PREFIX, main].concat()).unwrap();
+    extra.extend(build_jpeg_segment(0xE1, &extended).unwrap());
+    data.splice(2..2, extra);
+
+    let (stripped, removed) = strip_container(&data, MetadataPolicy::StripPrivate).unwrap();
+    assert!(removed.contains(&"XMP (extended)".to_string()));
+    assert!(!stripped.windows(JPEG_EXTENDED_XMP_PREFIX.len()).any(|w| w == JPEG_EXTENDED_XMP_PREFIX));
+    assert!(!stripped.windows(9).any(|w| w == b"<GPSData>"));
+    assert!(!stripped.windows(14).any(|w| w == b"HasExtendedXMP"));
+    assert!(image::load_from_memory(&stripped).is_ok());
+  }
+
+  #[test]
+  fn test_strip_keep_all_is_lossless() {
+    let data = generate_test_jpeg();
+    let (kept, removed) = strip_container(&data, MetadataPolicy::KeepAll).unwrap();
+    assert_eq!(kept, data);
+    assert!(removed.is_empty());
+  }
+
+  #[test]
+  fn test_strip_png() {
+    let data = generate_test_png();
+
+    let (stripped, removed) = strip_container(&data, MetadataPolicy::StripAll).unwrap();
+    assert_eq!(removed, vec!["EXIF", "XMP", "Text:Author"]);
+    assert!(image::load_from_memory(&stripped).is_ok());
+
+    let (stripped, removed) = strip_container(&data, MetadataPolicy::StripPrivate).unwrap();
+    assert_eq!(removed, vec!["EXIF:GPS", "EXIF:BodySerialNumber", "XMP:exif:GPSLatitude"]);
+    assert!(read_exif(&stripped).unwrap().unwrap().gps.is_none());
+    assert!(image::load_from_memory(&stripped).is_ok());
+  }
+
+  #[test]
+  fn test_strip_png_raw_profile() {
+    let mut data = generate_test_png();
+    let exif_hex: String = [JPEG_EXIF_PREFIX, &sample_tiff()].concat().iter().map(|b| format!("{:02x}", b)).collect();
+    let raw_exif = format!("Raw profile type exif\0\nexif\n{:8}\n{}\n", exif_hex.len() / 2, exif_hex);
+    let mut extra = build_png_chunk(b"tEXt", raw_exif.as_bytes());
+    extra.extend(build_png_chunk(b"zTXt", b"Raw profile type APP1\0\0x"));
+    extra.extend(build_png_chunk(b"tEXt", b"Raw profile type icc\0\nicc\n0\n"));
+    let ihdr_end = png_chunks(&data).unwrap()[0].length + PNG_SIGNATURE.len();
+    data.splice(ihdr_end..ihdr_end, extra);
+
+    let (stripped, removed) = strip_container(&data, MetadataPolicy::StripPrivate).unwrap();
+    assert_eq!(
+      removed,
+      vec![
+        "Text:Raw profile type exif",
+        "Text:Raw profile type APP1",
+        "EXIF:GPS",
+        "EXIF:BodySerialNumber",
+        "XMP:exif:GPSLatitude",
+      ]
+    );
+    let kinds: Vec<String> = png_chunks(&stripped)
+      .unwrap()
+      .iter()
+      .filter(|c| &c.kind == b"tEXt")
+      .map(|c| String::from_utf8_lossy(c.payload(&stripped)).to_string())
+      .collect();
+    assert_eq!(kinds, vec!["Raw profile type icc\0\nicc\n0\n", "Author\0Someone"]);
+    assert!(image::load_from_memory(&stripped).is_ok());
+
+    let (kept, removed) = strip_container(&data, MetadataPolicy::KeepAll).unwrap();
+    assert_eq!(kept, data);
+    assert!(removed.is_empty());
+  }
+
+  #[test]
+  fn test_strip_webp() {
+    let vp8x = build_riff_chunk(b"VP8X", &[0x0C, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+    let data = build_webp(&[
+      vp8x,
+      build_riff_chunk(b"VP8L", &[0x2F, 0, 0, 0, 0]),
+      build_riff_chunk(b"EXIF", &sample_tiff()),
+      build_riff_chunk(b"XMP ", PRIVATE_XMP),
+    ]);
+
+    let (stripped, removed) = strip_container(&data, MetadataPolicy::StripAll).unwrap();
+    assert_eq!(removed, vec!["EXIF", "XMP"]);
+    let chunks = riff_chunks(&stripped).unwrap();
+    assert_eq!(chunks.len(), 2);
+    assert_eq!(chunks[0].payload(&stripped)[0], 0);
+
+    let (stripped, _) = strip_container(&data, MetadataPolicy::StripPrivate).unwrap();
+    let exif = read_exif(&stripped).unwrap().unwrap();
+    assert!(exif.gps.is_none());
+    assert_eq!(exif.make.as_deref(), Some("Canon"));
+  }
+
+  #[test]
+  fn test_strip_unsupported_format() {
+    assert!(strip_container(b"GIF89a......", MetadataPolicy::StripAll).is_err());
+    assert!(strip_container(&sample_tiff(), MetadataPolicy::StripAll).is_err());
+  }
+}
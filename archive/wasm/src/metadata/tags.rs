@@ -0,0 +1,459 @@
+//! XMP 标签
+//!
+//! 把分析得到的标签、描述和评分写入 XMP（dc:subject、dc:description、xmp:Rating），
+//! 使这些信息随文件一起传递，并提供对应的读取功能
+
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::metadata::carry::{embed_blocks, MetadataBlocks};
+use crate::metadata::container::ContainerKind;
+use crate::metadata::xmp::{self, find_xmp};
+
+/// XMP 命名空间声明
+const NS_DC: &str = "http://purl.org/dc/elements/1.1/";
+const NS_XMP: &str = "http://ns.adobe.com/xap/1.0/";
+
+/// 新建 XMP 数据包的模板，属性插入在 rdf:Description 内
+const XMP_PACKET_TEMPLATE: &str = concat!(
+  "<?xpacket begin=\"\u{feff}\" id=\"W5M0MpCehiHzreSzNTczkc9d\"?>\n",
+  "<x:xmpmeta xmlns:x=\"adobe:ns:meta/\">\n",
+  " <rdf:RDF xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\">\n",
+  "  <rdf:Description rdf:about=\"\">\n",
+  "  </rdf:Description>\n",
+  " </rdf:RDF>\n",
+  "</x:xmpmeta>\n",
+  "<?xpacket end=\"w\"?>",
+);
+
+/// 图片标签（WASM 导出）
+///
+/// 写入时未指定的字段保留文件中原有的值
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImageTags {
+  /// 标签 JSON string，格式与 AIAnalysisResult.tags_json 相同，如 ["风景", "日落"]
+  pub tags_json: Option<String>,
+  /// 描述
+  pub description: Option<String>,
+  /// 评分 (0-5)
+  pub rating: Option<u8>,
+}
+
+/// 解析后的 XMP 标签
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct XmpTags {
+  /// 标签 (dc:subject)
+  pub subject: Option<Vec<String>>,
+  /// 描述 (dc:description)
+  pub description: Option<String>,
+  /// 评分 (xmp:Rating)
+  pub rating: Option<u8>,
+}
+
+impl XmpTags {
+  /// 从 WASM 参数转换并校验
+  pub fn from_image_tags(tags: &ImageTags) -> Result<Self, String> {
+    let subject = match &tags.tags_json {
+      Some(json) => Some(serde_json::from_str::<Vec<String>>(json).map_err(|e| format!("Invalid tags JSON: {}", e))?),
+      None => None,
+    };
+
+    if tags.rating.is_some_and(|r| r > 5) {
+      return Err("Rating must be between 0 and 5".to_string());
+    }
+
+    Ok(XmpTags {
+      subject,
+      description: tags.description.clone(),
+      rating: tags.rating,
+    })
+  }
+
+  /// 转换为 WASM 结果，标签始终输出为 JSON 数组
+  pub fn to_image_tags(&self) -> ImageTags {
+    ImageTags {
+      tags_json: Some(serde_json::to_string(self.subject.as_deref().unwrap_or_default()).unwrap_or_else(|_| "[]".to_string())),
+      description: self.description.clone(),
+      rating: self.rating,
+    }
+  }
+}
+
+/// 把标签、描述和评分写入图片的 XMP（WASM 导出）
+///
+/// 支持 JPEG / PNG / WebP，只改写容器结构，不重新编码像素
+#[wasm_bindgen]
+pub fn write_image_tags(image_data: &[u8], tags: ImageTags) -> Result<Vec<u8>, JsValue> {
+  let tags = XmpTags::from_image_tags(&tags).map_err(|e| JsValue::from_str(&e))?;
+  write_tags(image_data, &tags).map_err(|e| JsValue::from_str(&e))
+}
+
+/// 读取图片 XMP 中的标签、描述和评分（WASM 导出）
+#[wasm_bindgen]
+pub fn read_image_tags(image_data: &[u8]) -> Result<ImageTags, JsValue> {
+  if ContainerKind::detect(image_data).is_none() {
+    return Err(JsValue::from_str("Unsupported image format"));
+  }
+
+  let tags = find_xmp(image_data)
+    .as_deref()
+    .and_then(|packet| std::str::from_utf8(packet).ok())
+    .map(parse_tags)
+    .unwrap_or_default();
+  Ok(tags.to_image_tags())
+}
+
+/// 把标签写入图片文件，已有的 XMP 会被合并
+pub fn write_tags(data: &[u8], tags: &XmpTags) -> Result<Vec<u8>, String> {
+  match ContainerKind::detect(data) {
+    Some(ContainerKind::Jpeg | ContainerKind::Png | ContainerKind::WebP) => {}
+    _ => return Err("Writing XMP tags is only supported for JPEG, PNG and WebP".to_string()),
+  }
+
+  let existing = find_xmp(data);
+  let existing = existing.as_deref().and_then(|packet| std::str::from_utf8(packet).ok());
+  let packet = apply_tags(existing, tags);
+
+  embed_blocks(
+    data,
+    &MetadataBlocks {
+      xmp: Some(packet.into_bytes()),
+      ..Default::default()
+    },
+    // 只写入单个数据块，不会产生需要丢弃的内容
+    &mut Vec::new(),
+  )
+}
+
+/// 把标签合并到 XMP 数据包中，替换同名属性，保留其他内容
+///
+/// 没有可用的 rdf:Description 时新建数据包
+pub fn apply_tags(existing: Option<&str>, tags: &XmpTags) -> String {
+  let mut replaced = Vec::new();
+  if tags.subject.is_some() {
+    replaced.push("dc:subject");
+  }
+  if tags.description.is_some() {
+    replaced.push("dc:description");
+  }
+  if tags.rating.is_some() {
+    replaced.push("xmp:Rating");
+  }
+
+  let mut packet = match existing {
+    Some(text) if text.contains("<rdf:Description") => xmp::remove_properties(text, |name| replaced.contains(&name)).0,
+    Some(text) if text.contains("</rdf:RDF>") => {
+      text.replacen("</rdf:RDF>", "<rdf:Description rdf:about=\"\"></rdf:Description></rdf:RDF>", 1)
+    }
+    _ => XMP_PACKET_TEMPLATE.to_string(),
+  };
+
+  // rdf:Description 开始标签的位置，自闭合时展开为成对标签
+  let description_start = packet.find("<rdf:Description").unwrap_or_default();
+  let mut open_end = description_start + packet[description_start..].find('>').unwrap_or_default();
+  if packet[..open_end].ends_with('/') {
+    packet.replace_range(open_end - 1..=open_end, "></rdf:Description>");
+    open_end -= 1;
+  }
+
+  // 命名空间声明在写入属性的元素上，其他 rdf:Description 中的声明作用不到这里
+  let open_tag = &packet[description_start..open_end];
+  let mut namespaces = String::new();
+  if !open_tag.contains("xmlns:dc=") {
+    namespaces.push_str(&format!(" xmlns:dc=\"{}\"", NS_DC));
+  }
+  if !open_tag.contains("xmlns:xmp=") {
+    namespaces.push_str(&format!(" xmlns:xmp=\"{}\"", NS_XMP));
+  }
+
+  packet.insert_str(open_end + 1, &build_properties(tags));
+  packet.insert_str(open_end, &namespaces);
+  packet
+}
+
+/// 生成标签对应的 XMP 属性元素
+fn build_properties(tags: &XmpTags) -> String {
+  let mut properties = String::new();
+
+  if let Some(subject) = &tags.subject {
+    properties.push_str("<dc:subject><rdf:Bag>");
+    for tag in subject {
+      properties.push_str(&format!("<rdf:li>{}</rdf:li>", escape_xml(tag)));
+    }
+    properties.push_str("</rdf:Bag></dc:subject>");
+  }
+  if let Some(description) = &tags.description {
+    properties.push_str(&format!(
+      "<dc:description><rdf:Alt><rdf:li xml:lang=\"x-default\">{}</rdf:li></rdf:Alt></dc:description>",
+      escape_xml(description)
+    ));
+  }
+  if let Some(rating) = tags.rating {
+    properties.push_str(&format!("<xmp:Rating>{}</xmp:Rating>", rating));
+  }
+
+  properties
+}
+
+/// 从 XMP 数据包中读取标签、描述和评分
+pub fn parse_tags(xmp: &str) -> XmpTags {
+  let subject = element_content(xmp, "dc:subject").map(|content| {
+    list_items(content)
+      .into_iter()
+      .map(|(_, value)| value)
+      .filter(|value| !value.is_empty())
+      .collect()
+  });
+
+  // 多语言描述优先取 x-default
+  let description = element_content(xmp, "dc:description").and_then(|content| {
+    let items = list_items(content);
+    let default = items.iter().position(|(lang, _)| lang.as_deref() == Some("x-default"));
+    items.into_iter().nth(default.unwrap_or(0)).map(|(_, value)| value)
+  });
+
+  // xmp:Rating 为实数，-1 表示拒绝，只接受 0-5
+  let rating = element_content(xmp, "xmp:Rating")
+    .or_else(|| attribute_value(xmp, "xmp:Rating"))
+    .and_then(|value| value.trim().parse::<f64>().ok())
+    .filter(|value| (0.0..=5.0).contains(value))
+    .map(|value| value.round() as u8);
+
+  XmpTags {
+    subject,
+    description,
+    rating,
+  }
+}
+
+/// 元素 `<name ...>content</name>` 的内容
+fn element_content<'a>(xmp: &'a str, name: &str) -> Option<&'a str> {
+  let open = format!("<{}", name);
+  let mut search_from = 0;
+
+  while let Some(found) = xmp[search_from..].find(&open) {
+    let start = search_from + found;
+    let after_name = start + open.len();
+    search_from = after_name;
+
+    // 排除名称前缀相同的其他元素，如 dc:subjectX
+    match xmp.as_bytes().get(after_name) {
+      Some(b'>') | Some(b' ') | Some(b'\t') | Some(b'\r') | Some(b'\n') => {}
+      Some(b'/') => return Some(""),
+      _ => continue,
+    }
+
+    let content_start = after_name + xmp[after_name..].find('>')? + 1;
+    if xmp[..content_start].ends_with("/>") {
+      return Some("");
+    }
+    let close = format!("</{}>", name);
+    let content_end = content_start + xmp[content_start..].find(&close)?;
+    return Some(&xmp[content_start..content_end]);
+  }
+
+  None
+}
+
+/// 属性 ` name="value"` 的值
+fn attribute_value<'a>(xmp: &'a str, name: &str) -> Option<&'a str> {
+  ['"', '\''].iter().find_map(|quote| {
+    let pattern = format!("{}={}", name, quote);
+    let start = xmp
+      .match_indices(&pattern)
+      .find(|(i, _)| xmp[..*i].ends_with(|c: char| c.is_ascii_whitespace()))?
+      .0
+      + pattern.len();
+    let end = start + xmp[start..].find(*quote)?;
+    Some(&xmp[start..end])
+  })
+}
+
+/// rdf:Bag / rdf:Seq / rdf:Alt 中的 (语言, 值) 列表
+fn list_items(content: &str) -> Vec<(Option<String>, String)> {
+  let mut items = Vec::new();
+  let mut rest = content;
+
+  while let Some(start) = rest.find("<rdf:li") {
+    let tag_end = match rest[start..].find('>') {
+      Some(i) => start + i,
+      None => break,
+    };
+    let tag = &rest[start..tag_end];
+    let lang = attribute_value(tag, "xml:lang").map(|l| l.to_string());
+
+    if tag.ends_with('/') {
+      items.push((lang, String::new()));
+      rest = &rest[tag_end + 1..];
+      continue;
+    }
+
+    let value_end = match rest[tag_end + 1..].find("</rdf:li>") {
+      Some(i) => tag_end + 1 + i,
+      None => break,
+    };
+    items.push((lang, unescape_xml(rest[tag_end + 1..value_end].trim())));
+    rest = &rest[value_end + "</rdf:li>".len()..];
+  }
+
+  items
+}
+
+/// 转义 XML 文本
+fn escape_xml(text: &str) -> String {
+  text
+    .replace('&', "&amp;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+    .replace('"', "&quot;")
+}
+
+/// 反转义 XML 文本（只处理预定义实体）
+fn unescape_xml(text: &str) -> String {
+  text
+    .replace("&lt;", "<")
+    .replace("&gt;", ">")
+    .replace("&quot;", "\"")
+    .replace("&apos;", "'")
+    .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::metadata::container::{build_png_chunk, build_riff_chunk, build_webp, png_chunks, riff_chunks};
+  use image::{ImageBuffer, Rgb, RgbImage};
+
+  fn encode(format: image::ImageOutputFormat) -> Vec<u8> {
+    let img: RgbImage = ImageBuffer::from_pixel(8, 8, Rgb([90, 120, 150]));
+    let mut buffer = Vec::new();
+    img.write_to(&mut std::io::Cursor::new(&mut buffer), format).unwrap();
+    buffer
+  }
+
+  fn sample_tags() -> XmpTags {
+    XmpTags {
+      subject: Some(vec!["风景".to_string(), "R&D <test>".to_string()]),
+      description: Some("一张横向图片".to_string()),
+      rating: Some(4),
+    }
+  }
+
+  #[test]
+  fn test_apply_and_parse_tags() {
+    let packet = apply_tags(None, &sample_tags());
+    assert!(packet.starts_with("<?xpacket begin="));
+    assert!(packet.contains("xmlns:dc=\"http://purl.org/dc/elements/1.1/\""));
+    assert!(packet.contains("<rdf:li>R&amp;D &lt;test&gt;</rdf:li>"));
+    assert_eq!(parse_tags(&packet), sample_tags());
+  }
+
+  #[test]
+  fn test_apply_tags_merges_existing_packet() {
+    let existing = r#"<x:xmpmeta><rdf:RDF><rdf:Description rdf:about="" xmlns:dc="http://purl.org/dc/elements/1.1/" xmp:Rating="1" dc:creator="Alice"><dc:subject><rdf:Bag><rdf:li>old</rdf:li></rdf:Bag></dc:subject></rdf:Description></rdf:RDF></x:xmpmeta>"#;
+    let tags = XmpTags {
+      subject: Some(vec!["new".to_string()]),
+      description: None,
+      rating: Some(5),
+    };
+
+    let packet = apply_tags(Some(existing), &tags);
+    assert!(packet.contains("dc:creator=\"Alice\""));
+    assert!(!packet.contains("old"));
+    assert_eq!(packet.matches("xmlns:dc=").count(), 1);
+    assert!(packet.contains("xmlns:xmp="));
+
+    let parsed = parse_tags(&packet);
+    assert_eq!(parsed.subject, Some(vec!["new".to_string()]));
+    assert_eq!(parsed.description, None);
+    assert_eq!(parsed.rating, Some(5));
+  }
+
+  #[test]
+  fn test_apply_tags_self_closing_description() {
+    let packet = apply_tags(Some(r#"<rdf:RDF><rdf:Description rdf:about=""/></rdf:RDF>"#), &sample_tags());
+    assert!(packet.contains("</dc:subject>"));
+    assert!(packet.ends_with("</rdf:Description></rdf:RDF>"));
+    assert_eq!(parse_tags(&packet), sample_tags());
+  }
+
+  #[test]
+  fn test_apply_tags_declares_namespace_on_target() {
+    // dc 只在第二个 rdf:Description 上声明，标签写入第一个
+    let existing = r#"<rdf:RDF><rdf:Description rdf:about="" xmlns:xmp="http://ns.adobe.com/xap/1.0/" xmp:Rating="1"/><rdf:Description rdf:about="" xmlns:dc="http://purl.org/dc/elements/1.1/" dc:creator="Alice"/></rdf:RDF>"#;
+    let packet = apply_tags(Some(existing), &sample_tags());
+    let start = packet.find("<rdf:Description").unwrap();
+    let first = &packet[start..start + packet[start..].find('>').unwrap()];
+    assert!(first.contains("xmlns:dc=\"http://purl.org/dc/elements/1.1/\""));
+    assert_eq!(first.matches("xmlns:xmp=").count(), 1);
+    assert_eq!(parse_tags(&packet), sample_tags());
+  }
+
+  #[test]
+  fn test_write_tags_merges_compressed_png_xmp() {
+    let existing = br#"<x:xmpmeta><rdf:RDF><rdf:Description rdf:about="" xmlns:dc="http://purl.org/dc/elements/1.1/" dc:creator="Alice"/></rdf:RDF></x:xmpmeta>"#;
+    let mut itxt = xmp::PNG_XMP_KEYWORD.to_vec();
+    itxt.extend_from_slice(&[0, 1, 0, 0, 0]);
+    itxt.extend(miniz_oxide::deflate::compress_to_vec_zlib(existing, 6));
+    let mut png = encode(image::ImageOutputFormat::Png);
+    png.splice(33..33, build_png_chunk(b"iTXt", &itxt));
+
+    let tagged = write_tags(&png, &sample_tags()).unwrap();
+    let packet = String::from_utf8(find_xmp(&tagged).unwrap().into_owned()).unwrap();
+    assert!(packet.contains("dc:creator=\"Alice\""));
+    assert_eq!(parse_tags(&packet), sample_tags());
+    let xmp_chunks = png_chunks(&tagged).unwrap().iter().filter(|c| &c.kind == b"iTXt").count();
+    assert_eq!(xmp_chunks, 1);
+  }
+
+  #[test]
+  fn test_parse_tags_variants() {
+    let xmp = r#"<rdf:Description xmp:Rating='3.0'><dc:description><rdf:Alt><rdf:li xml:lang="en">Sunset</rdf:li><rdf:li xml:lang="x-default">日落</rdf:li></rdf:Alt></dc:description></rdf:Description>"#;
+    let tags = parse_tags(xmp);
+    assert_eq!(tags.subject, None);
+    assert_eq!(tags.description.as_deref(), Some("日落"));
+    assert_eq!(tags.rating, Some(3));
+
+    // 拒绝标记 (-1) 不作为评分
+    assert_eq!(parse_tags("<xmp:Rating>-1</xmp:Rating>").rating, None);
+  }
+
+  #[test]
+  fn test_write_and_read_tags_in_containers() {
+    let webp = build_webp(&[build_riff_chunk(b"VP8L", &[0x2F, 7, 0xC0, 0x01, 0])]);
+
+    for data in [encode(image::ImageOutputFormat::Jpeg(80)), encode(image::ImageOutputFormat::Png), webp] {
+      let tagged = write_tags(&data, &sample_tags()).unwrap();
+      let packet = String::from_utf8(find_xmp(&tagged).unwrap().into_owned()).unwrap();
+      assert_eq!(parse_tags(&packet), sample_tags());
+
+      // 再次写入替换原有值，不产生重复的 XMP
+      let retagged = write_tags(&tagged, &XmpTags { rating: Some(2), ..Default::default() }).unwrap();
+      let parsed = parse_tags(std::str::from_utf8(&find_xmp(&retagged).unwrap()).unwrap());
+      assert_eq!(parsed.rating, Some(2));
+      assert_eq!(parsed.subject, sample_tags().subject);
+    }
+
+    let webp = write_tags(&build_webp(&[build_riff_chunk(b"VP8L", &[0x2F, 7, 0xC0, 0x01, 0])]), &sample_tags()).unwrap();
+    assert_eq!(riff_chunks(&webp).unwrap().iter().filter(|c| &c.kind == b"XMP ").count(), 1);
+
+    assert!(write_tags(b"GIF89a", &sample_tags()).is_err());
+  }
+
+  #[test]
+  fn test_image_tags_conversion() {
+    let tags = ImageTags {
+      tags_json: Some(r#"["风景","日落"]"#.to_string()),
+      description: None,
+      rating: Some(3),
+    };
+    let parsed = XmpTags::from_image_tags(&tags).unwrap();
+    assert_eq!(parsed.subject, Some(vec!["风景".to_string(), "日落".to_string()]));
+    assert_eq!(parsed.to_image_tags().tags_json.as_deref(), Some(r#"["风景","日落"]"#));
+
+    assert!(XmpTags::from_image_tags(&ImageTags { rating: Some(6), ..Default::default() }).is_err());
+    assert!(XmpTags::from_image_tags(&ImageTags { tags_json: Some("not json".to_string()), ..Default::default() }).is_err());
+    assert_eq!(XmpTags::default().to_image_tags().tags_json.as_deref(), Some("[]"));
+  }
+}
@@ -0,0 +1,352 @@
+//! XMP 元数据
+//!
+//! 定位 JPEG / PNG / WebP 中的 XMP 数据包，并提供按属性名删除字段的文本处理。
+//! JPEG 单个 APP1 段放不下的 XMP 按扩展 XMP 拆分到多个段，主数据包通过 GUID 指向扩展部分
+
+use std::borrow::Cow;
+
+use md5::{Digest, Md5};
+
+use crate::metadata::container::{build_jpeg_segment, jpeg_segments, png_chunks, riff_chunks, ContainerKind};
+
+/// JPEG XMP APP1 段的命名空间前缀
+pub const JPEG_XMP_PREFIX: &[u8] = b"http://ns.adobe.com/xap/1.0/\0";
+
+/// JPEG 扩展 XMP APP1 段的命名空间前缀
+pub const JPEG_EXTENDED_XMP_PREFIX: &[u8] = b"http://ns.adobe.com/xmp/extension/\0";
+
+/// 主数据包中指向扩展 XMP 的属性，值为扩展部分的 GUID
+pub const HAS_EXTENDED_XMP: &str = "xmpNote:HasExtendedXMP";
+
+/// xmpNote 命名空间
+const NS_XMP_NOTE: &str = "http://ns.adobe.com/xmp/note/";
+
+/// 单个 APP1 段可容纳的主 XMP 字节数
+pub const MAX_STANDARD_XMP_LENGTH: usize = 65533 - JPEG_XMP_PREFIX.len();
+
+/// 单个扩展 XMP 段可容纳的数据字节数（扣除 32 字节 GUID 以及总长度、偏移各 4 字节）
+const MAX_EXTENDED_XMP_CHUNK: usize = 65533 - JPEG_EXTENDED_XMP_PREFIX.len() - 40;
+
+/// 主数据包放不下时使用的空数据包，只写入指向扩展 XMP 的属性
+const EMPTY_XMP_PACKET: &str = concat!(
+  "<?xpacket begin=\"\u{feff}\" id=\"W5M0MpCehiHzreSzNTczkc9d\"?>\n",
+  "<x:xmpmeta xmlns:x=\"adobe:ns:meta/\">\n",
+  " <rdf:RDF xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\">\n",
+  " </rdf:RDF>\n",
+  "</x:xmpmeta>\n",
+  "<?xpacket end=\"w\"?>",
+);
+
+/// PNG 中存放 XMP 的 iTXt 关键字
+pub const PNG_XMP_KEYWORD: &[u8] = b"XML:com.adobe.xmp";
+
+/// 压缩存放的 XMP 解压后的大小上限
+const MAX_XMP_SIZE: usize = 16 * 1024 * 1024;
+
+/// 解析 PNG iTXt 块，返回 (关键字, 是否压缩, 文本)
+pub fn parse_itxt(payload: &[u8]) -> Option<(&[u8], bool, &[u8])> {
+  let keyword_end = payload.iter().position(|&b| b == 0)?;
+  let compressed = *payload.get(keyword_end + 1)? != 0;
+
+  // 跳过压缩方法、语言标签和翻译后的关键字
+  let mut pos = keyword_end + 3;
+  for _ in 0..2 {
+    pos += payload.get(pos..)?.iter().position(|&b| b == 0)? + 1;
+  }
+
+  Some((&payload[..keyword_end], compressed, &payload[pos..]))
+}
+
+/// 构造存放 XMP 的 PNG iTXt 块数据（不压缩）
+pub fn build_itxt_xmp(xmp: &[u8]) -> Vec<u8> {
+  let mut payload = PNG_XMP_KEYWORD.to_vec();
+  // 关键字结束、未压缩、压缩方法、空语言标签、空翻译关键字
+  payload.extend_from_slice(&[0, 0, 0, 0, 0]);
+  payload.extend_from_slice(xmp);
+  payload
+}
+
+/// 从图片文件中定位 XMP 数据包
+///
+/// PNG 中压缩存放的 XMP（压缩的 iTXt 或 zTXt）解压后返回
+pub fn find_xmp(data: &[u8]) -> Option<Cow<'_, [u8]>> {
+  match ContainerKind::detect(data)? {
+    ContainerKind::Jpeg => jpeg_segments(data)
+      .ok()?
+      .iter()
+      .filter(|s| s.marker == 0xE1)
+      .map(|s| s.payload(data))
+      .find(|p| p.starts_with(JPEG_XMP_PREFIX))
+      .map(|p| Cow::Borrowed(&p[JPEG_XMP_PREFIX.len()..])),
+    ContainerKind::Png => png_chunks(data)
+      .ok()?
+      .iter()
+      .find_map(|c| png_xmp(&c.kind, c.payload(data))),
+    ContainerKind::WebP => riff_chunks(data)
+      .ok()?
+      .iter()
+      .find(|c| &c.kind == b"XMP ")
+      .map(|c| Cow::Borrowed(c.payload(data))),
+    ContainerKind::Tiff => None,
+  }
+}
+
+/// 读取 PNG 文本块中的 XMP，压缩存放时先解压
+fn png_xmp<'a>(kind: &[u8; 4], payload: &'a [u8]) -> Option<Cow<'a, [u8]>> {
+  let inflate = |data| miniz_oxide::inflate::decompress_to_vec_zlib_with_limit(data, MAX_XMP_SIZE).ok();
+  match kind {
+    b"iTXt" => match parse_itxt(payload)? {
+      (PNG_XMP_KEYWORD, true, text) => inflate(text).map(Cow::Owned),
+      (PNG_XMP_KEYWORD, false, text) => Some(Cow::Borrowed(text)),
+      _ => None,
+    },
+    // zTXt：关键字、结束符、压缩方法，之后是压缩数据
+    b"zTXt" => inflate(payload.strip_prefix(PNG_XMP_KEYWORD)?.strip_prefix(&[0, 0])?).map(Cow::Owned),
+    _ => None,
+  }
+}
+
+/// 读取主数据包中指向扩展 XMP 的 GUID
+pub fn extended_xmp_guid(xmp: &str) -> Option<&str> {
+  let start = xmp.find(HAS_EXTENDED_XMP)? + HAS_EXTENDED_XMP.len();
+  let rest = xmp[start..].trim_start_matches(['=', '"', '\'', '>']);
+  rest.get(..32).filter(|guid| guid.bytes().all(|b| b.is_ascii_hexdigit()))
+}
+
+/// 拼接 JPEG 中与 GUID 对应的扩展 XMP，分段不完整时返回 None
+pub fn find_extended_xmp(data: &[u8], guid: &str) -> Option<Vec<u8>> {
+  let mut extended: Option<Vec<u8>> = None;
+  let mut received = 0;
+
+  for segment in jpeg_segments(data).ok()?.iter().filter(|s| s.marker == 0xE1) {
+    let chunk = match segment.payload(data).strip_prefix(JPEG_EXTENDED_XMP_PREFIX) {
+      Some(chunk) if chunk.len() >= 40 && &chunk[..32] == guid.as_bytes() => chunk,
+      _ => continue,
+    };
+    let full_length = u32::from_be_bytes([chunk[32], chunk[33], chunk[34], chunk[35]]) as usize;
+    let offset = u32::from_be_bytes([chunk[36], chunk[37], chunk[38], chunk[39]]) as usize;
+    let bytes = &chunk[40..];
+
+    // 总长度不会超过文件本身，避免按伪造的长度分配内存
+    if full_length > data.len() {
+      return None;
+    }
+    let buffer = extended.get_or_insert_with(|| vec![0; full_length]);
+    if buffer.len() != full_length {
+      return None;
+    }
+    let end = offset.checked_add(bytes.len()).filter(|&end| end <= full_length)?;
+    buffer[offset..end].copy_from_slice(bytes);
+    received += bytes.len();
+  }
+
+  extended.filter(|buffer| received == buffer.len())
+}
+
+/// 构造 JPEG XMP APP1 段
+///
+/// 扩展 XMP 按 GUID 拆分写入多个段；主数据包超出单段容量时整体移入扩展 XMP。
+/// 无法写入的部分记录到 warnings
+pub fn build_jpeg_xmp_segments(
+  packet: &[u8],
+  extended: Option<&[u8]>,
+  warnings: &mut Vec<String>,
+) -> Result<Vec<u8>, String> {
+  let (standard, extended) = split_extended_xmp(packet, extended, warnings);
+  let mut output = build_jpeg_segment(0xE1, &[JPEG_XMP_PREFIX, &standard].concat())?;
+
+  if let Some(extended) = extended {
+    let guid = xmp_guid(&extended);
+    let full_length = (extended.len() as u32).to_be_bytes();
+    for (index, chunk) in extended.chunks(MAX_EXTENDED_XMP_CHUNK).enumerate() {
+      let offset = ((index * MAX_EXTENDED_XMP_CHUNK) as u32).to_be_bytes();
+      let payload = [JPEG_EXTENDED_XMP_PREFIX, guid.as_bytes(), &full_length, &offset, chunk].concat();
+      output.extend(build_jpeg_segment(0xE1, &payload)?);
+    }
+  }
+  Ok(output)
+}
+
+/// 确定写入的主数据包和扩展 XMP，主数据包中补上指向扩展部分的 GUID
+fn split_extended_xmp(packet: &[u8], extended: Option<&[u8]>, warnings: &mut Vec<String>) -> (Vec<u8>, Option<Vec<u8>>) {
+  if packet.len() > MAX_STANDARD_XMP_LENGTH {
+    if extended.is_some() {
+      warnings.push("Extended XMP dropped: main XMP packet is too large for a JPEG segment".to_string());
+    }
+    let packet = match std::str::from_utf8(packet) {
+      Ok(text) => remove_properties(text, |name| name == HAS_EXTENDED_XMP).0.into_bytes(),
+      Err(_) => packet.to_vec(),
+    };
+    let standard = link_extended_xmp(EMPTY_XMP_PACKET.as_bytes(), &xmp_guid(&packet)).unwrap_or_default();
+    return (standard, Some(packet));
+  }
+
+  match extended {
+    Some(extended) => match link_extended_xmp(packet, &xmp_guid(extended)).filter(|p| p.len() <= MAX_STANDARD_XMP_LENGTH) {
+      Some(standard) => (standard, Some(extended.to_vec())),
+      None => {
+        warnings.push("Extended XMP dropped: main XMP packet cannot reference it".to_string());
+        (packet.to_vec(), None)
+      }
+    },
+    None => (packet.to_vec(), None),
+  }
+}
+
+/// 在 rdf:RDF 末尾追加指向扩展 XMP 的 rdf:Description
+fn link_extended_xmp(packet: &[u8], guid: &str) -> Option<Vec<u8>> {
+  let mut text = std::str::from_utf8(packet).ok()?.to_string();
+  let end = text.rfind("</rdf:RDF>")?;
+  let description = format!(
+    "<rdf:Description rdf:about=\"\" xmlns:xmpNote=\"{}\" {}=\"{}\"/>",
+    NS_XMP_NOTE, HAS_EXTENDED_XMP, guid
+  );
+  text.insert_str(end, &description);
+  Some(text.into_bytes())
+}
+
+/// 扩展 XMP 的 GUID：内容 MD5 的大写十六进制
+fn xmp_guid(extended: &[u8]) -> String {
+  Md5::digest(extended).iter().map(|b| format!("{:02X}", b)).collect()
+}
+
+/// 删除 XMP 中满足条件的属性，返回新的 XMP 和被删除的属性名
+///
+/// 同时处理属性写法 `exif:GPSLatitude="..."` 和元素写法 `<exif:GPSLatitude>...</exif:GPSLatitude>`
+pub fn remove_properties(xmp: &str, should_remove: impl Fn(&str) -> bool) -> (String, Vec<String>) {
+  let bytes = xmp.as_bytes();
+  let mut output = String::with_capacity(xmp.len());
+  let mut removed = Vec::new();
+  let mut copied_until = 0;
+  let mut pos = 0;
+
+  while pos < bytes.len() {
+    let name_start = pos;
+    let name_end = name_start + bytes[name_start..].iter().take_while(|&&b| is_name_byte(b)).count();
+    if name_end == name_start {
+      pos += 1;
+      continue;
+    }
+
+    let name = &xmp[name_start..name_end];
+    let before = name_start.checked_sub(1).map(|i| bytes[i]);
+    let range = if !name.contains(':') || !should_remove(name) {
+      None
+    } else if before == Some(b'<') {
+      element_range(xmp, name_start - 1, name)
+    } else if before.is_some_and(|b| b.is_ascii_whitespace()) && bytes.get(name_end) == Some(&b'=') {
+      attribute_range(bytes, name_start - 1, name_end)
+    } else {
+      None
+    };
+
+    match range {
+      Some((start, end)) => {
+        output.push_str(&xmp[copied_until..start]);
+        removed.push(name.to_string());
+        copied_until = end;
+        pos = end;
+      }
+      None => pos = name_end,
+    }
+  }
+
+  output.push_str(&xmp[copied_until..]);
+  (output, removed)
+}
+
+/// XML 名称字符（含命名空间前缀分隔符）
+fn is_name_byte(b: u8) -> bool {
+  b.is_ascii_alphanumeric() || matches!(b, b'_' | b':' | b'-' | b'.')
+}
+
+/// 元素 `<name ...>...</name>` 或 `<name .../>` 的范围
+fn element_range(xmp: &str, start: usize, name: &str) -> Option<(usize, usize)> {
+  let open_end = start + xmp[start..].find('>')? + 1;
+  if xmp[..open_end].ends_with("/>") {
+    return Some((start, open_end));
+  }
+
+  let close_tag = format!("</{}>", name);
+  let close_start = open_end + xmp[open_end..].find(&close_tag)?;
+  Some((start, close_start + close_tag.len()))
+}
+
+/// 属性 ` name="..."` 的范围（包含前导空白）
+fn attribute_range(bytes: &[u8], start: usize, name_end: usize) -> Option<(usize, usize)> {
+  let quote = *bytes.get(name_end + 1)?;
+  if quote != b'"' && quote != b'\'' {
+    return None;
+  }
+  let value_end = bytes[name_end + 2..].iter().position(|&b| b == quote)?;
+  Some((start, name_end + 2 + value_end + 1))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::metadata::container::{build_png_chunk, PNG_SIGNATURE};
+
+  const SAMPLE_XMP: &str = r#"<x:xmpmeta xmlns:x="adobe:ns:meta/"><rdf:RDF><rdf:Description rdf:about="" exif:GPSLatitude="31,14.0N" xmp:Rating="4" aux:SerialNumber='123'><exif:GPSLongitude>121,30.0W</exif:GPSLongitude><exif:GPSAltitude/><dc:title>Sunset</dc:title></rdf:Description></rdf:RDF></x:xmpmeta>"#;
+
+  #[test]
+  fn test_remove_properties() {
+    let (xmp, removed) = remove_properties(SAMPLE_XMP, |name| {
+      name.starts_with("exif:GPS") || name == "aux:SerialNumber"
+    });
+
+    assert_eq!(
+      removed,
+      vec!["exif:GPSLatitude", "aux:SerialNumber", "exif:GPSLongitude", "exif:GPSAltitude"]
+    );
+    assert!(!xmp.contains("GPS"));
+    assert!(!xmp.contains("Serial"));
+    assert!(xmp.contains(r#"<rdf:Description rdf:about="" xmp:Rating="4">"#));
+    assert!(xmp.contains("<dc:title>Sunset</dc:title>"));
+  }
+
+  #[test]
+  fn test_remove_properties_keeps_unmatched() {
+    let (xmp, removed) = remove_properties(SAMPLE_XMP, |_| false);
+    assert_eq!(xmp, SAMPLE_XMP);
+    assert!(removed.is_empty());
+  }
+
+  #[test]
+  fn test_find_xmp_in_png() {
+    let mut data = PNG_SIGNATURE.to_vec();
+    data.extend(build_png_chunk(b"IHDR", &[0; 13]));
+    data.extend(build_png_chunk(b"iTXt", &build_itxt_xmp(b"<x:xmpmeta/>")));
+    data.extend(build_png_chunk(b"IEND", &[]));
+
+    assert_eq!(find_xmp(&data).as_deref(), Some(&b"<x:xmpmeta/>"[..]));
+  }
+
+  #[test]
+  fn test_find_compressed_xmp_in_png() {
+    let compressed = miniz_oxide::deflate::compress_to_vec_zlib(b"<x:xmpmeta/>", 6);
+    let mut itxt = PNG_XMP_KEYWORD.to_vec();
+    itxt.extend_from_slice(&[0, 1, 0, 0, 0]);
+    itxt.extend_from_slice(&compressed);
+    let ztxt = [PNG_XMP_KEYWORD, &[0, 0], &compressed].concat();
+
+    for chunk in [build_png_chunk(b"iTXt", &itxt), build_png_chunk(b"zTXt", &ztxt)] {
+      let mut data = PNG_SIGNATURE.to_vec();
+      data.extend(build_png_chunk(b"IHDR", &[0; 13]));
+      data.extend(chunk);
+      data.extend(build_png_chunk(b"IEND", &[]));
+      assert_eq!(find_xmp(&data).as_deref(), Some(&b"<x:xmpmeta/>"[..]));
+    }
+  }
+
+  #[test]
+  fn test_parse_itxt() {
+    let payload = b"Title\0\0\0en\0Titel\0Hello";
+    let (keyword, compressed, text) = parse_itxt(payload).unwrap();
+    assert_eq!(keyword, b"Title");
+    assert!(!compressed);
+    assert_eq!(text, b"Hello");
+
+    assert!(parse_itxt(b"NoTerminator").is_none());
+  }
+}
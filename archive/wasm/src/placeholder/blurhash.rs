@@ -0,0 +1,210 @@
+//! BlurHash 编解码
+//!
+//! 与 woltapp/blurhash 参考实现兼容：对线性光 RGB 做二维余弦变换，
+//! 直流分量和交流分量量化后以 Base83 编码
+
+use image::{Rgba, RgbaImage};
+use std::f64::consts::PI;
+
+const BASE83: &[u8; 83] =
+  b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz#$%*+,-.:;=?@[]^_{|}~";
+
+/// 编码 BlurHash，components 为水平和垂直分量数 (1-9)
+pub fn encode(img: &RgbaImage, x_components: u32, y_components: u32) -> Result<String, String> {
+  if !(1..=9).contains(&x_components) || !(1..=9).contains(&y_components) {
+    return Err(format!(
+      "BlurHash components must be between 1 and 9, got {}x{}",
+      x_components, y_components
+    ));
+  }
+  let (width, height) = img.dimensions();
+  if width == 0 || height == 0 {
+    return Err("Cannot encode BlurHash for an empty image".to_string());
+  }
+
+  let linear: Vec<[f64; 3]> = img
+    .pixels()
+    .map(|p| [srgb_to_linear(p.0[0]), srgb_to_linear(p.0[1]), srgb_to_linear(p.0[2])])
+    .collect();
+
+  let mut factors = Vec::with_capacity((x_components * y_components) as usize);
+  for j in 0..y_components {
+    for i in 0..x_components {
+      let normalisation = if i == 0 && j == 0 { 1.0 } else { 2.0 };
+      let mut factor = [0.0f64; 3];
+      for y in 0..height {
+        let basis_y = (PI * j as f64 * y as f64 / height as f64).cos();
+        for x in 0..width {
+          let basis = basis_y * (PI * i as f64 * x as f64 / width as f64).cos();
+          let pixel = linear[(y * width + x) as usize];
+          for c in 0..3 {
+            factor[c] += basis * pixel[c];
+          }
+        }
+      }
+      let scale = normalisation / (width * height) as f64;
+      factors.push(factor.map(|v| v * scale));
+    }
+  }
+
+  let mut hash = String::with_capacity(4 + 2 * factors.len());
+  encode_base83((x_components - 1) + (y_components - 1) * 9, 1, &mut hash);
+
+  let ac = &factors[1..];
+  let maximum_value = if ac.is_empty() {
+    encode_base83(0, 1, &mut hash);
+    1.0
+  } else {
+    let actual_max = ac.iter().flatten().fold(0.0f64, |max, v| max.max(v.abs()));
+    let quantised_max = (actual_max * 166.0 - 0.5).floor().clamp(0.0, 82.0) as u32;
+    encode_base83(quantised_max, 1, &mut hash);
+    (quantised_max + 1) as f64 / 166.0
+  };
+
+  let dc = factors[0];
+  let dc_value = ((linear_to_srgb(dc[0]) as u32) << 16)
+    + ((linear_to_srgb(dc[1]) as u32) << 8)
+    + linear_to_srgb(dc[2]) as u32;
+  encode_base83(dc_value, 4, &mut hash);
+
+  for factor in ac {
+    let quantise = |v: f64| (sign_pow(v / maximum_value, 0.5) * 9.0 + 9.5).floor().clamp(0.0, 18.0) as u32;
+    encode_base83(quantise(factor[0]) * 19 * 19 + quantise(factor[1]) * 19 + quantise(factor[2]), 2, &mut hash);
+  }
+
+  Ok(hash)
+}
+
+/// 解码 BlurHash 为指定尺寸的 RGBA 图片，punch 调整对比度（通常为 1）
+pub fn decode(hash: &str, width: u32, height: u32, punch: f64) -> Result<RgbaImage, String> {
+  let invalid = || format!("Invalid BlurHash: {}", hash);
+  if hash.len() < 6 || !hash.is_ascii() {
+    return Err(invalid());
+  }
+
+  let size_flag = decode_base83(&hash[0..1]).ok_or_else(invalid)?;
+  let x_components = size_flag % 9 + 1;
+  let y_components = size_flag / 9 + 1;
+  if hash.len() != (4 + 2 * x_components * y_components) as usize {
+    return Err(invalid());
+  }
+
+  let quantised_max = decode_base83(&hash[1..2]).ok_or_else(invalid)?;
+  let maximum_value = (quantised_max + 1) as f64 / 166.0 * punch;
+
+  let mut colors = Vec::with_capacity((x_components * y_components) as usize);
+  let dc = decode_base83(&hash[2..6]).ok_or_else(invalid)?;
+  colors.push([
+    srgb_to_linear((dc >> 16) as u8),
+    srgb_to_linear((dc >> 8) as u8),
+    srgb_to_linear(dc as u8),
+  ]);
+  for i in 1..(x_components * y_components) as usize {
+    let value = decode_base83(&hash[4 + i * 2..6 + i * 2]).ok_or_else(invalid)?;
+    let unquantise = |q: u32| sign_pow((q as f64 - 9.0) / 9.0, 2.0) * maximum_value;
+    colors.push([
+      unquantise(value / (19 * 19)),
+      unquantise((value / 19) % 19),
+      unquantise(value % 19),
+    ]);
+  }
+
+  Ok(RgbaImage::from_fn(width, height, |x, y| {
+    let mut pixel = [0.0f64; 3];
+    for j in 0..y_components {
+      let basis_y = (PI * y as f64 * j as f64 / height as f64).cos();
+      for i in 0..x_components {
+        let basis = basis_y * (PI * x as f64 * i as f64 / width as f64).cos();
+        let color = colors[(i + j * x_components) as usize];
+        for c in 0..3 {
+          pixel[c] += color[c] * basis;
+        }
+      }
+    }
+    Rgba([linear_to_srgb(pixel[0]), linear_to_srgb(pixel[1]), linear_to_srgb(pixel[2]), 255])
+  }))
+}
+
+fn encode_base83(value: u32, length: u32, output: &mut String) {
+  for i in 1..=length {
+    let digit = (value / 83u32.pow(length - i)) % 83;
+    output.push(BASE83[digit as usize] as char);
+  }
+}
+
+fn decode_base83(text: &str) -> Option<u32> {
+  text.bytes().try_fold(0u32, |value, c| {
+    let digit = BASE83.iter().position(|&b| b == c)? as u32;
+    Some(value * 83 + digit)
+  })
+}
+
+fn srgb_to_linear(value: u8) -> f64 {
+  let v = value as f64 / 255.0;
+  if v <= 0.04045 {
+    v / 12.92
+  } else {
+    ((v + 0.055) / 1.055).powf(2.4)
+  }
+}
+
+fn linear_to_srgb(value: f64) -> u8 {
+  let v = value.clamp(0.0, 1.0);
+  if v <= 0.0031308 {
+    (v * 12.92 * 255.0 + 0.5) as u8
+  } else {
+    ((1.055 * v.powf(1.0 / 2.4) - 0.055) * 255.0 + 0.5) as u8
+  }
+}
+
+fn sign_pow(value: f64, exp: f64) -> f64 {
+  value.abs().powf(exp).copysign(value)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_solid_color_round_trip() {
+    let img = RgbaImage::from_pixel(20, 10, Rgba([200, 60, 30, 255]));
+    let hash = encode(&img, 4, 3).unwrap();
+    assert_eq!(hash.len(), 4 + 2 * 4 * 3);
+    // 4x3 分量的 BlurHash 以 L 开头
+    assert!(hash.starts_with('L'));
+
+    // 只有直流分量时解码结果就是平均颜色
+    let dc_only = encode(&img, 1, 1).unwrap();
+    assert_eq!(dc_only.len(), 6);
+    assert_eq!(dc_only[2..6], hash[2..6]);
+    let decoded = decode(&dc_only, 8, 8, 1.0).unwrap();
+    for pixel in decoded.pixels() {
+      assert_eq!(pixel.0, [200, 60, 30, 255]);
+    }
+  }
+
+  #[test]
+  fn test_gradient_round_trip() {
+    let img = RgbaImage::from_fn(32, 32, |x, _| Rgba([(x * 8) as u8, 0, 255 - (x * 8) as u8, 255]));
+    let hash = encode(&img, 5, 2).unwrap();
+    assert!(hash.starts_with(BASE83[4 + 9] as char));
+
+    let decoded = decode(&hash, 32, 32, 1.0).unwrap();
+    // 左侧偏蓝、右侧偏红
+    let left = decoded.get_pixel(2, 16).0;
+    let right = decoded.get_pixel(29, 16).0;
+    assert!(left[2] > left[0]);
+    assert!(right[0] > right[2]);
+  }
+
+  #[test]
+  fn test_invalid_input() {
+    let img = RgbaImage::from_pixel(4, 4, Rgba([0, 0, 0, 255]));
+    assert!(encode(&img, 0, 3).is_err());
+    assert!(encode(&img, 4, 10).is_err());
+    assert!(decode("LEHV6n", 4, 4, 1.0).is_err());
+    assert!(decode("L!!!!!", 4, 4, 1.0).is_err());
+    // 参考实现 README 中的示例 hash
+    assert!(decode("LEHV6nWB2yk8pyo0adR*.7kCMdnj", 4, 4, 1.0).is_ok());
+  }
+}
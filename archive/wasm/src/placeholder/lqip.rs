@@ -0,0 +1,198 @@
+//! 低质量图片占位符 (LQIP)
+//!
+//! 把图片缩小到 16-32 像素并模糊，编码为很小的 JPEG 后以 data URI 返回，
+//! 可以直接内联到 Markdown / HTML 中。
+//! 先按质量从高到低尝试，仍超出字节预算时逐步缩小尺寸。
+//! 原图只缩小一次，更小的尺寸都从这张小图缩放
+
+use wasm_bindgen::prelude::*;
+use image::{DynamicImage, GenericImageView};
+use serde::{Deserialize, Serialize};
+
+use crate::convert::{apply_resize, ensure_encodable, get_converter, ConversionOptions, ResizeOptions, SupportedFormat};
+use crate::image::decode_image;
+use crate::placeholder::to_base64;
+
+/// 默认最长边
+const DEFAULT_SIZE: u32 = 24;
+/// 默认模糊半径
+const DEFAULT_BLUR: f32 = 1.0;
+/// 默认 data URI 字节预算
+const DEFAULT_MAX_BYTES: u32 = 2048;
+/// 依次尝试的编码质量
+const QUALITY_STEPS: [u8; 6] = [60, 50, 40, 30, 20, 10];
+/// 超出预算时尺寸缩小的步长和下限
+const SIZE_STEP: u32 = 4;
+const MIN_SIZE: u32 = 8;
+
+/// LQIP 选项
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LqipOptions {
+  /// 最长边像素 (8-64)，默认 24
+  pub size: Option<u32>,
+  /// 高斯模糊半径，0 表示不模糊，默认 1.0
+  pub blur: Option<f32>,
+  /// 输出格式，默认 jpeg；当前构建不支持 webp
+  pub format: Option<String>,
+  /// data URI 的最大字节数，默认 2048
+  pub max_bytes: Option<u32>,
+}
+
+/// LQIP 结果
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LqipResult {
+  /// data URI，如 data:image/jpeg;base64,...
+  pub data_uri: String,
+  /// 占位图宽度
+  pub width: u32,
+  /// 占位图高度
+  pub height: u32,
+  /// 使用的编码质量
+  pub quality: u8,
+  /// data URI 字节数
+  pub byte_size: u32,
+}
+
+/// 生成 LQIP data URI（WASM 导出）
+#[wasm_bindgen]
+pub fn generate_lqip(image_data: &[u8], options: Option<LqipOptions>) -> Result<LqipResult, JsValue> {
+  let img = decode_image(image_data).map_err(|e| JsValue::from_str(&e))?.image;
+  create_lqip(&img, &options.unwrap_or_default()).map_err(|e| JsValue::from_str(&e))
+}
+
+/// 从解码后的图片生成 LQIP
+pub fn create_lqip(img: &DynamicImage, options: &LqipOptions) -> Result<LqipResult, String> {
+  let size = options.size.unwrap_or(DEFAULT_SIZE);
+  if !(MIN_SIZE..=64).contains(&size) {
+    return Err(format!("LQIP size must be between {} and 64", MIN_SIZE));
+  }
+  let blur = options.blur.unwrap_or(DEFAULT_BLUR);
+  if blur < 0.0 {
+    return Err("LQIP blur must not be negative".to_string());
+  }
+  let format_str = options.format.as_deref().unwrap_or("jpeg");
+  let format = match SupportedFormat::from_string(format_str) {
+    Some(format @ (SupportedFormat::Jpeg | SupportedFormat::WebP)) => format,
+    _ => return Err(format!("Unsupported LQIP format: {}", format_str)),
+  };
+  ensure_encodable(&format)?;
+  let max_bytes = options.max_bytes.unwrap_or(DEFAULT_MAX_BYTES);
+
+  let converter = get_converter(&format);
+  let prefix = format!("data:{};base64,", format.mime_type());
+
+  // 保持宽高比缩小到最长边为 size
+  let fit_box = |size: u32| ResizeOptions {
+    width: Some(size),
+    height: Some(size),
+    maintain_aspect_ratio: Some(true),
+    ..Default::default()
+  };
+  let base_size = size;
+  let base = apply_resize(img, &fit_box(base_size))?;
+
+  let mut size = size;
+  loop {
+    let scaled = if size == base_size { base.clone() } else { apply_resize(&base, &fit_box(size))? };
+    let (width, height) = scaled.dimensions();
+    let small = if blur > 0.0 { scaled.blur(blur) } else { scaled };
+
+    for quality in QUALITY_STEPS {
+      let conversion_options = ConversionOptions {
+        quality,
+        preserve_transparency: false,
+        lossless: false,
+      };
+      let data = converter.convert(&small, &conversion_options)?;
+      let data_uri = format!("{}{}", prefix, to_base64(&data));
+      if data_uri.len() <= max_bytes as usize {
+        return Ok(LqipResult {
+          byte_size: data_uri.len() as u32,
+          data_uri,
+          width,
+          height,
+          quality,
+        });
+      }
+    }
+
+    if size <= MIN_SIZE {
+      return Err(format!("LQIP does not fit in {} bytes", max_bytes));
+    }
+    size = size.saturating_sub(SIZE_STEP).max(MIN_SIZE);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::placeholder::from_base64;
+  use image::{ImageBuffer, Rgb};
+
+  fn photo() -> DynamicImage {
+    DynamicImage::ImageRgb8(ImageBuffer::from_fn(640, 480, |x, y| {
+      Rgb([(x / 3) as u8, (y / 2) as u8, ((x * y) % 251) as u8])
+    }))
+  }
+
+  #[test]
+  fn test_default_lqip() {
+    let result = create_lqip(&photo(), &LqipOptions::default()).unwrap();
+    assert_eq!((result.width, result.height), (24, 18));
+    assert!(result.byte_size <= DEFAULT_MAX_BYTES);
+    assert_eq!(result.byte_size as usize, result.data_uri.len());
+
+    let encoded = result.data_uri.strip_prefix("data:image/jpeg;base64,").unwrap();
+    let decoded = image::load_from_memory(&from_base64(encoded).unwrap()).unwrap();
+    assert_eq!(decoded.dimensions(), (24, 18));
+  }
+
+  #[test]
+  fn test_byte_budget() {
+    let unlimited = create_lqip(&photo(), &LqipOptions {
+      size: Some(32),
+      max_bytes: Some(100_000),
+      ..Default::default()
+    })
+    .unwrap();
+    assert_eq!(unlimited.quality, QUALITY_STEPS[0]);
+
+    // 预算较小时降低质量或尺寸
+    let budget = unlimited.byte_size - 100;
+    let limited = create_lqip(&photo(), &LqipOptions {
+      size: Some(32),
+      max_bytes: Some(budget),
+      ..Default::default()
+    })
+    .unwrap();
+    assert!(limited.byte_size <= budget);
+    assert!(limited.quality < unlimited.quality || limited.width < unlimited.width);
+
+    let impossible = LqipOptions {
+      max_bytes: Some(100),
+      ..Default::default()
+    };
+    assert_eq!(
+      create_lqip(&photo(), &impossible).unwrap_err(),
+      "LQIP does not fit in 100 bytes"
+    );
+  }
+
+  #[test]
+  fn test_invalid_options() {
+    let img = photo();
+    for options in [
+      LqipOptions { size: Some(4), ..Default::default() },
+      LqipOptions { size: Some(128), ..Default::default() },
+      LqipOptions { blur: Some(-1.0), ..Default::default() },
+      LqipOptions { format: Some("png".to_string()), ..Default::default() },
+    ] {
+      assert!(create_lqip(&img, &options).is_err());
+    }
+
+    let webp = LqipOptions { format: Some("webp".to_string()), ..Default::default() };
+    assert!(create_lqip(&img, &webp).unwrap_err().contains("WebP encoding is not available"));
+  }
+}
@@ -0,0 +1,172 @@
+//! 图片占位符
+//!
+//! 在缩略图加载完成前显示的模糊占位图，包括：
+//! - BlurHash：Base83 字符串，分量数可配置
+//! - ThumbHash：约 25 字节，保留宽高比和透明度，以 Base64 返回
+//! - LQIP：模糊的小尺寸 JPEG data URI，可直接内联
+//!
+//! BlurHash / ThumbHash 编码前先把图片缩小到最长边 100 像素以内，结果只包含低频信息，缩小不影响效果
+
+pub mod blurhash;
+pub mod lqip;
+pub mod thumbhash;
+
+pub use lqip::{generate_lqip, LqipOptions, LqipResult};
+
+use wasm_bindgen::prelude::*;
+use image::imageops::FilterType;
+use image::{DynamicImage, RgbaImage};
+use serde::{Deserialize, Serialize};
+
+use crate::image::decode_image;
+
+/// BlurHash 默认水平分量数
+pub const DEFAULT_X_COMPONENTS: u32 = 4;
+/// BlurHash 默认垂直分量数
+pub const DEFAULT_Y_COMPONENTS: u32 = 3;
+
+/// 占位符选项
+#[wasm_bindgen]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PlaceholderOptions {
+  /// BlurHash 水平分量数 (1-9)，默认 4
+  pub x_components: Option<u32>,
+  /// BlurHash 垂直分量数 (1-9)，默认 3
+  pub y_components: Option<u32>,
+}
+
+/// 占位符结果
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImagePlaceholders {
+  /// BlurHash 字符串
+  pub blurhash: String,
+  /// ThumbHash (Base64)
+  pub thumbhash: String,
+}
+
+/// 生成图片占位符（WASM 导出）
+#[wasm_bindgen]
+pub fn generate_placeholders(
+  image_data: &[u8],
+  options: Option<PlaceholderOptions>,
+) -> Result<ImagePlaceholders, JsValue> {
+  let img = decode_image(image_data).map_err(|e| JsValue::from_str(&e))?.image;
+  create_placeholders(&img, &options.unwrap_or_default()).map_err(|e| JsValue::from_str(&e))
+}
+
+/// 从解码后的图片生成占位符
+pub fn create_placeholders(img: &DynamicImage, options: &PlaceholderOptions) -> Result<ImagePlaceholders, String> {
+  let sample = placeholder_sample(img);
+  let blurhash = blurhash::encode(
+    &sample,
+    options.x_components.unwrap_or(DEFAULT_X_COMPONENTS),
+    options.y_components.unwrap_or(DEFAULT_Y_COMPONENTS),
+  )?;
+  let thumbhash = to_base64(&thumbhash::encode(&sample)?);
+
+  Ok(ImagePlaceholders { blurhash, thumbhash })
+}
+
+/// 保持宽高比缩小到最长边不超过 100 像素的 RGBA 图片
+pub fn placeholder_sample(img: &DynamicImage) -> RgbaImage {
+  let size = thumbhash::MAX_SIZE;
+  if img.width() <= size && img.height() <= size {
+    img.to_rgba8()
+  } else {
+    img.resize(size, size, FilterType::Triangle).to_rgba8()
+  }
+}
+
+/// 标准 Base64 编码（带填充）
+pub fn to_base64(bytes: &[u8]) -> String {
+  const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+  let mut output = String::with_capacity(bytes.len().div_ceil(3) * 4);
+  for chunk in bytes.chunks(3) {
+    let buffer = chunk.iter().enumerate().fold(0u32, |acc, (i, &b)| acc | ((b as u32) << (16 - i * 8)));
+    for i in 0..4 {
+      if i <= chunk.len() {
+        output.push(ALPHABET[((buffer >> (18 - i * 6)) & 0x3F) as usize] as char);
+      } else {
+        output.push('=');
+      }
+    }
+  }
+  output
+}
+
+/// 标准 Base64 解码（忽略填充）
+pub fn from_base64(text: &str) -> Result<Vec<u8>, String> {
+  let mut output = Vec::with_capacity(text.len() / 4 * 3);
+  let mut buffer = 0u32;
+  let mut bits = 0;
+  for c in text.trim_end_matches('=').bytes() {
+    let value = match c {
+      b'A'..=b'Z' => c - b'A',
+      b'a'..=b'z' => c - b'a' + 26,
+      b'0'..=b'9' => c - b'0' + 52,
+      b'+' => 62,
+      b'/' => 63,
+      _ => return Err(format!("Invalid base64 character: {}", c as char)),
+    };
+    buffer = (buffer << 6) | value as u32;
+    bits += 6;
+    if bits >= 8 {
+      bits -= 8;
+      output.push((buffer >> bits) as u8);
+    }
+  }
+  Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use image::{ImageBuffer, Rgb};
+
+  #[test]
+  fn test_base64() {
+    // RFC 4648 测试向量
+    for (input, expected) in [
+      (&b""[..], ""),
+      (b"f", "Zg=="),
+      (b"fo", "Zm8="),
+      (b"foo", "Zm9v"),
+      (b"foobar", "Zm9vYmFy"),
+    ] {
+      assert_eq!(to_base64(input), expected);
+      assert_eq!(from_base64(expected).unwrap(), input);
+    }
+    assert!(from_base64("Zm9v!").is_err());
+  }
+
+  #[test]
+  fn test_generate_placeholders() {
+    let img = ImageBuffer::from_fn(640, 480, |x, y| Rgb([(x / 3) as u8, (y / 2) as u8, 90]));
+    let mut data = Vec::new();
+    DynamicImage::ImageRgb8(img)
+      .write_to(&mut std::io::Cursor::new(&mut data), image::ImageOutputFormat::Png)
+      .unwrap();
+
+    let placeholders = generate_placeholders(&data, None).unwrap();
+    assert_eq!(placeholders.blurhash.len(), 4 + 2 * 4 * 3);
+    let hash = from_base64(&placeholders.thumbhash).unwrap();
+    assert!((thumbhash::approximate_aspect_ratio(&hash) - 4.0 / 3.0).abs() < 0.2);
+
+    let options = PlaceholderOptions {
+      x_components: Some(9),
+      y_components: Some(9),
+    };
+    let placeholders = generate_placeholders(&data, Some(options)).unwrap();
+    assert_eq!(placeholders.blurhash.len(), 4 + 2 * 9 * 9);
+    assert!(blurhash::decode(&placeholders.blurhash, 32, 24, 1.0).is_ok());
+
+    let invalid = PlaceholderOptions {
+      x_components: Some(12),
+      y_components: None,
+    };
+    let img = decode_image(&data).unwrap().image;
+    assert!(create_placeholders(&img, &invalid).is_err());
+  }
+}
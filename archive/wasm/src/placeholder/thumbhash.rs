@@ -0,0 +1,305 @@
+//! ThumbHash 编解码
+//!
+//! 与 evanw/thumbhash 参考实现兼容：把图片转换到 LPQA 颜色空间（亮度、黄蓝、红绿、透明度）
+//! 后做余弦变换，系数以 4 位量化，通常约 25 字节。
+//! 相比 BlurHash 保留了宽高比和透明度，输入不能超过 100x100
+
+use image::{Rgba, RgbaImage};
+use std::f64::consts::PI;
+
+/// 输入图片的最大边长
+pub const MAX_SIZE: u32 = 100;
+
+/// 编码 ThumbHash
+pub fn encode(img: &RgbaImage) -> Result<Vec<u8>, String> {
+  let (w, h) = img.dimensions();
+  if w == 0 || h == 0 || w > MAX_SIZE || h > MAX_SIZE {
+    return Err(format!("{}x{} doesn't fit in {}x{}", w, h, MAX_SIZE, MAX_SIZE));
+  }
+
+  // 平均颜色（按透明度加权）
+  let (mut avg_r, mut avg_g, mut avg_b, mut avg_a) = (0.0, 0.0, 0.0, 0.0);
+  for pixel in img.pixels() {
+    let alpha = pixel.0[3] as f64 / 255.0;
+    avg_r += alpha / 255.0 * pixel.0[0] as f64;
+    avg_g += alpha / 255.0 * pixel.0[1] as f64;
+    avg_b += alpha / 255.0 * pixel.0[2] as f64;
+    avg_a += alpha;
+  }
+  if avg_a > 0.0 {
+    avg_r /= avg_a;
+    avg_g /= avg_a;
+    avg_b /= avg_a;
+  }
+
+  let has_alpha = avg_a < (w * h) as f64;
+  // 有透明度时亮度使用更少的分量
+  let l_limit = if has_alpha { 5.0 } else { 7.0 };
+  let max_side = w.max(h) as f64;
+  let lx = ((l_limit * w as f64 / max_side).round() as u32).max(1);
+  let ly = ((l_limit * h as f64 / max_side).round() as u32).max(1);
+
+  // 转换到 LPQA，透明像素与平均颜色混合
+  let pixel_count = (w * h) as usize;
+  let (mut l, mut p, mut q, mut a) = (
+    Vec::with_capacity(pixel_count),
+    Vec::with_capacity(pixel_count),
+    Vec::with_capacity(pixel_count),
+    Vec::with_capacity(pixel_count),
+  );
+  for pixel in img.pixels() {
+    let alpha = pixel.0[3] as f64 / 255.0;
+    let r = avg_r * (1.0 - alpha) + alpha / 255.0 * pixel.0[0] as f64;
+    let g = avg_g * (1.0 - alpha) + alpha / 255.0 * pixel.0[1] as f64;
+    let b = avg_b * (1.0 - alpha) + alpha / 255.0 * pixel.0[2] as f64;
+    l.push((r + g + b) / 3.0);
+    p.push((r + g) / 2.0 - b);
+    q.push(r - g);
+    a.push(alpha);
+  }
+
+  let (l_dc, l_ac, l_scale) = encode_channel(&l, w, h, lx.max(3), ly.max(3));
+  let (p_dc, p_ac, p_scale) = encode_channel(&p, w, h, 3, 3);
+  let (q_dc, q_ac, q_scale) = encode_channel(&q, w, h, 3, 3);
+
+  let is_landscape = w > h;
+  let header24 = (63.0 * l_dc).round() as u32
+    | (((31.5 + 31.5 * p_dc).round() as u32) << 6)
+    | (((31.5 + 31.5 * q_dc).round() as u32) << 12)
+    | (((31.0 * l_scale).round() as u32) << 18)
+    | ((has_alpha as u32) << 23);
+  let header16 = (if is_landscape { ly } else { lx })
+    | (((63.0 * p_scale).round() as u32) << 3)
+    | (((63.0 * q_scale).round() as u32) << 9)
+    | ((is_landscape as u32) << 15);
+
+  let mut hash = vec![
+    header24 as u8,
+    (header24 >> 8) as u8,
+    (header24 >> 16) as u8,
+    header16 as u8,
+    (header16 >> 8) as u8,
+  ];
+
+  let mut channels = vec![l_ac, p_ac, q_ac];
+  if has_alpha {
+    let (a_dc, a_ac, a_scale) = encode_channel(&a, w, h, 5, 5);
+    hash.push((15.0 * a_dc).round() as u8 | (((15.0 * a_scale).round() as u8) << 4));
+    channels.push(a_ac);
+  }
+
+  // 交流分量每个 4 位，两个一字节（低位在前）
+  let ac_start = hash.len();
+  for (index, f) in channels.iter().flatten().enumerate() {
+    if index % 2 == 0 {
+      hash.push(0);
+    }
+    hash[ac_start + index / 2] |= ((15.0 * f).round() as u8) << ((index & 1) * 4);
+  }
+
+  Ok(hash)
+}
+
+/// 解码 ThumbHash，输出最长边为 32 像素的 RGBA 图片
+pub fn decode(hash: &[u8]) -> Result<RgbaImage, String> {
+  if hash.len() < 5 {
+    return Err("ThumbHash is too short".to_string());
+  }
+
+  let header24 = hash[0] as u32 | ((hash[1] as u32) << 8) | ((hash[2] as u32) << 16);
+  let header16 = hash[3] as u32 | ((hash[4] as u32) << 8);
+  let l_dc = (header24 & 63) as f64 / 63.0;
+  let p_dc = ((header24 >> 6) & 63) as f64 / 31.5 - 1.0;
+  let q_dc = ((header24 >> 12) & 63) as f64 / 31.5 - 1.0;
+  let l_scale = ((header24 >> 18) & 31) as f64 / 31.0;
+  let has_alpha = (header24 >> 23) != 0;
+  let p_scale = ((header16 >> 3) & 63) as f64 / 63.0;
+  let q_scale = ((header16 >> 9) & 63) as f64 / 63.0;
+  let is_landscape = (header16 >> 15) != 0;
+  let l_limit = if has_alpha { 5 } else { 7 };
+  let lx = (if is_landscape { l_limit } else { header16 & 7 }).max(3) as usize;
+  let ly = (if is_landscape { header16 & 7 } else { l_limit }).max(3) as usize;
+
+  let (a_dc, a_scale) = if has_alpha {
+    let byte = *hash.get(5).ok_or("ThumbHash is too short")?;
+    ((byte & 15) as f64 / 15.0, (byte >> 4) as f64 / 15.0)
+  } else {
+    (1.0, 0.0)
+  };
+
+  let mut reader = NibbleReader {
+    hash,
+    start: if has_alpha { 6 } else { 5 },
+    index: 0,
+  };
+  // 色度分量放大 1.25 倍以补偿量化损失的饱和度
+  let l_ac = reader.channel(lx, ly, l_scale)?;
+  let p_ac = reader.channel(3, 3, p_scale * 1.25)?;
+  let q_ac = reader.channel(3, 3, q_scale * 1.25)?;
+  let a_ac = if has_alpha { reader.channel(5, 5, a_scale)? } else { Vec::new() };
+
+  let ratio = approximate_aspect_ratio(hash);
+  let w = (if ratio > 1.0 { 32.0 } else { 32.0 * ratio }).round() as u32;
+  let h = (if ratio > 1.0 { 32.0 / ratio } else { 32.0 }).round() as u32;
+
+  let n = lx.max(if has_alpha { 5 } else { 3 });
+  let m = ly.max(if has_alpha { 5 } else { 3 });
+  Ok(RgbaImage::from_fn(w, h, |x, y| {
+    let fx: Vec<f64> = (0..n).map(|cx| (PI / w as f64 * (x as f64 + 0.5) * cx as f64).cos()).collect();
+    let fy: Vec<f64> = (0..m).map(|cy| (PI / h as f64 * (y as f64 + 0.5) * cy as f64).cos()).collect();
+
+    let l = l_dc + decode_channel(&l_ac, lx, ly, &fx, &fy);
+    let p = p_dc + decode_channel(&p_ac, 3, 3, &fx, &fy);
+    let q = q_dc + decode_channel(&q_ac, 3, 3, &fx, &fy);
+    let a = if has_alpha { a_dc + decode_channel(&a_ac, 5, 5, &fx, &fy) } else { a_dc };
+
+    let b = l - 2.0 / 3.0 * p;
+    let r = (3.0 * l - b + q) / 2.0;
+    let g = r - q;
+    Rgba([to_byte(r), to_byte(g), to_byte(b), to_byte(a)])
+  }))
+}
+
+/// 从 ThumbHash 头部估算原图宽高比
+pub fn approximate_aspect_ratio(hash: &[u8]) -> f64 {
+  if hash.len() < 5 {
+    return 1.0;
+  }
+  let has_alpha = hash[2] & 0x80 != 0;
+  let is_landscape = hash[4] & 0x80 != 0;
+  let l_limit = if has_alpha { 5.0 } else { 7.0 };
+  let lx = if is_landscape { l_limit } else { (hash[3] & 7) as f64 };
+  let ly = if is_landscape { (hash[3] & 7) as f64 } else { l_limit };
+  lx / ly
+}
+
+/// 对单个通道做余弦变换，返回直流分量、归一化到 0-1 的交流分量和缩放系数
+fn encode_channel(channel: &[f64], w: u32, h: u32, nx: u32, ny: u32) -> (f64, Vec<f64>, f64) {
+  let mut dc = 0.0;
+  let mut ac = Vec::new();
+  let mut scale = 0.0f64;
+  for cy in 0..ny {
+    let mut cx = 0;
+    while cx * ny < nx * (ny - cy) {
+      let fx: Vec<f64> = (0..w).map(|x| (PI / w as f64 * cx as f64 * (x as f64 + 0.5)).cos()).collect();
+      let mut f = 0.0;
+      for y in 0..h {
+        let fy = (PI / h as f64 * cy as f64 * (y as f64 + 0.5)).cos();
+        for x in 0..w {
+          f += channel[(x + y * w) as usize] * fx[x as usize] * fy;
+        }
+      }
+      f /= (w * h) as f64;
+      if cx > 0 || cy > 0 {
+        ac.push(f);
+        scale = scale.max(f.abs());
+      } else {
+        dc = f;
+      }
+      cx += 1;
+    }
+  }
+  if scale > 0.0 {
+    for f in ac.iter_mut() {
+      *f = 0.5 + 0.5 / scale * *f;
+    }
+  }
+  (dc, ac, scale)
+}
+
+/// 按编码顺序累加交流分量
+fn decode_channel(ac: &[f64], nx: usize, ny: usize, fx: &[f64], fy: &[f64]) -> f64 {
+  let mut value = 0.0;
+  let mut j = 0;
+  for (cy, fy) in fy.iter().enumerate().take(ny) {
+    let fy2 = fy * 2.0;
+    let mut cx = if cy > 0 { 0 } else { 1 };
+    while cx * ny < nx * (ny - cy) {
+      value += ac[j] * fx[cx] * fy2;
+      cx += 1;
+      j += 1;
+    }
+  }
+  value
+}
+
+/// 按顺序读取 4 位交流分量
+struct NibbleReader<'a> {
+  hash: &'a [u8],
+  start: usize,
+  index: usize,
+}
+
+impl NibbleReader<'_> {
+  fn channel(&mut self, nx: usize, ny: usize, scale: f64) -> Result<Vec<f64>, String> {
+    let mut ac = Vec::new();
+    for cy in 0..ny {
+      let mut cx = if cy > 0 { 0 } else { 1 };
+      while cx * ny < nx * (ny - cy) {
+        let byte = *self.hash.get(self.start + self.index / 2).ok_or("ThumbHash is too short")?;
+        let nibble = (byte >> ((self.index & 1) * 4)) & 15;
+        ac.push((nibble as f64 / 7.5 - 1.0) * scale);
+        self.index += 1;
+        cx += 1;
+      }
+    }
+    Ok(ac)
+  }
+}
+
+fn to_byte(value: f64) -> u8 {
+  (255.0 * value.clamp(0.0, 1.0)) as u8
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_opaque_round_trip() {
+    let img = RgbaImage::from_fn(80, 40, |x, _| {
+      if x < 40 { Rgba([220, 30, 30, 255]) } else { Rgba([30, 30, 220, 255]) }
+    });
+    let hash = encode(&img).unwrap();
+    // 不透明图片没有透明度字节
+    assert_eq!(hash[2] & 0x80, 0);
+    assert!((approximate_aspect_ratio(&hash) - 2.0).abs() < 0.5);
+
+    let decoded = decode(&hash).unwrap();
+    assert_eq!(decoded.width(), 32);
+    assert!(decoded.height() < 32);
+    let left = decoded.get_pixel(2, decoded.height() / 2).0;
+    let right = decoded.get_pixel(29, decoded.height() / 2).0;
+    assert!(left[0] > left[2] + 60);
+    assert!(right[2] > right[0] + 60);
+    assert_eq!(left[3], 255);
+  }
+
+  #[test]
+  fn test_alpha_round_trip() {
+    let img = RgbaImage::from_fn(40, 60, |_, y| {
+      if y < 30 { Rgba([0, 160, 0, 255]) } else { Rgba([0, 0, 0, 0]) }
+    });
+    let hash = encode(&img).unwrap();
+    assert_ne!(hash[2] & 0x80, 0);
+
+    let decoded = decode(&hash).unwrap();
+    assert_eq!(decoded.height(), 32);
+    assert!(decoded.width() < 32);
+    let top = decoded.get_pixel(decoded.width() / 2, 2).0;
+    let bottom = decoded.get_pixel(decoded.width() / 2, 29).0;
+    assert!(top[3] > 200);
+    assert!(bottom[3] < 60);
+    assert!(top[1] > top[0] + 60);
+  }
+
+  #[test]
+  fn test_invalid_input() {
+    assert!(encode(&RgbaImage::new(101, 10)).is_err());
+    assert!(encode(&RgbaImage::new(0, 10)).is_err());
+    assert!(decode(&[0, 0, 0]).is_err());
+
+    let hash = encode(&RgbaImage::from_pixel(10, 10, Rgba([1, 2, 3, 255]))).unwrap();
+    assert!(decode(&hash[..hash.len() - 2]).is_err());
+  }
+}